@@ -0,0 +1,64 @@
+//! Measures how fast a 10MB bracketed paste is read and decoded into a single event.
+//!
+//! Run with `cargo bench --bench paste` in a terminal, since a `Terminal` is needed.
+
+extern crate rustty;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rustty::{Event, ScriptSource, Terminal};
+
+const PASTE_LEN: usize = 10 * 1024 * 1024;
+const RUNS: u32 = 5;
+
+fn main() {
+    let mut term = match Terminal::new() {
+        Ok(term) => term,
+        Err(err) => {
+            println!("skipped, no terminal: {}", err);
+            return;
+        }
+    };
+    let source = ScriptSource::new().unwrap();
+    let feeder = source.feeder();
+    term.set_input_source(Some(Box::new(source))).unwrap();
+
+    // Multi-byte characters, so that reads split them.
+    let mut text = String::with_capacity(PASTE_LEN);
+    while text.len() < PASTE_LEN {
+        text.push_str("pasted text, ünïcödé ✓\n");
+    }
+    let mut paste = b"\x1b[200~".to_vec();
+    paste.extend(text.as_bytes());
+    paste.extend(b"\x1b[201~");
+
+    let mut total = Duration::from_secs(0);
+    for _ in 0..RUNS {
+        let input = paste.clone();
+        let feeder = feeder.clone();
+        let start = Instant::now();
+        // Fed from another thread, since the paste does not fit in the pipe.
+        let feeding = thread::spawn(move || feeder.feed(&input).unwrap());
+        loop {
+            match term.get_event(None).unwrap() {
+                Some(Event::Paste(pasted)) => {
+                    assert_eq!(pasted.len(), text.len());
+                    break;
+                }
+                Some(evt) => panic!("unexpected event {:?}", evt),
+                None => {}
+            }
+        }
+        total += start.elapsed();
+        feeding.join().unwrap();
+    }
+    drop(term);
+
+    let mean = total / RUNS;
+    let secs = mean.as_secs() as f64 + mean.subsec_nanos() as f64 / 1e9;
+    println!("{} byte paste: {:?} per run, {:.0} MB/s",
+             paste.len(),
+             mean,
+             paste.len() as f64 / secs / 1e6);
+}
@@ -2,9 +2,9 @@ extern crate rustty;
 
 use std::time::Duration;
 
-use rustty::{Terminal, Event, HasSize, CellAccessor};
+use rustty::{Terminal, Event, Key, HasSize, CellAccessor};
 
-use rustty::ui::{Painter, Dialog, Widget, Alignable, HorizontalAlign, VerticalAlign};
+use rustty::ui::{Painter, Dialog, Widget, Alignable, HorizontalAlign, VerticalAlign, Relayout};
 
 const BLOCK: char = '\u{25AA}';
 
@@ -30,16 +30,21 @@ fn main() {
     let mut canvas = Widget::new(term.size().0, term.size().1 - 4);
 
     // Align canvas to top left, and dialog to bottom right
-    optiondlg.window_mut().align(&term, HorizontalAlign::Right, VerticalAlign::Bottom, 0);
+    optiondlg.set_alignment(&term, HorizontalAlign::Right, VerticalAlign::Bottom, 0);
     canvas.align(&term, HorizontalAlign::Left, VerticalAlign::Top, 0);
 
     let mut radius = 10u32;
     'main: loop {
-        while let Some(Event::Key(ch)) = term.get_event(Some(Duration::new(0, 0))).unwrap() {
-            match ch {
-                'q' => break 'main,
-                '+' => radius = radius.saturating_add(1),
-                '-' => radius = radius.saturating_sub(1),
+        while let Some(evt) = term.get_event(Some(Duration::new(0, 0))).unwrap() {
+            match evt {
+                Event::Key(Key::Char('q')) => break 'main,
+                Event::Key(Key::Char('+')) => radius = radius.saturating_add(1),
+                Event::Key(Key::Char('-')) => radius = radius.saturating_sub(1),
+                // Keep the canvas and dialog in place when the terminal is resized
+                Event::Resize((cols, rows)) => {
+                    canvas.resize(cols, rows.saturating_sub(4));
+                    optiondlg.relayout(&term);
+                }
                 _ => {}
             }
         }
@@ -2,7 +2,7 @@ extern crate rustty;
 
 use std::time::Duration;
 
-use rustty::{Terminal, Event, Color};
+use rustty::{Terminal, Event, Key, Color};
 
 struct Cursor {
     pos: Position,
@@ -27,12 +27,12 @@ fn main() {
     term.swap_buffers().unwrap();
     loop {
         let evt = term.get_event(Some(Duration::from_millis(100))).unwrap();
-        if let Some(Event::Key(ch)) = evt {
-            match ch {
-                '`' => {
+        if let Some(Event::Key(key)) = evt {
+            match key {
+                Key::Char('`') => {
                     break;
                 }
-                '\x7f' => {
+                Key::Backspace => {
                     cursor.lpos = cursor.pos;
                     if cursor.pos.x == 0 {
                         cursor.pos.y = cursor.pos.y.saturating_sub(1);
@@ -41,16 +41,17 @@ fn main() {
                     }
                     term[(cursor.pos.x, cursor.pos.y)].set_ch(' ');
                 }
-                '\r' => {
+                Key::Enter => {
                     cursor.lpos = cursor.pos;
                     cursor.pos.x = 0;
                     cursor.pos.y += 1;
                 }
-                c @ _ => {
+                Key::Char(c) => {
                     term[(cursor.pos.x, cursor.pos.y)].set_ch(c);
                     cursor.lpos = cursor.pos;
                     cursor.pos.x += 1;
                 }
+                _ => {}
             }
             if cursor.pos.x >= term.cols() - 1 {
                 term[(cursor.lpos.x, cursor.lpos.y)].set_bg(Color::Default);
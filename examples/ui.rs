@@ -2,7 +2,7 @@ extern crate rustty;
 
 use std::time::Duration;
 
-use rustty::{Terminal, Event};
+use rustty::{Terminal, Event, Key};
 use rustty::ui::{Painter, Dialog, DialogResult, Alignable, HorizontalAlign, VerticalAlign};
 
 fn create_maindlg() -> Dialog {
@@ -23,7 +23,8 @@ fn main() {
     let mut maindlg = create_maindlg();
     maindlg.window_mut().align(&term, HorizontalAlign::Middle, VerticalAlign::Middle, 0);
     'main: loop {
-        while let Some(Event::Key(ch)) = term.get_event(Some(Duration::new(0, 0))).unwrap() {
+        let timeout = Some(Duration::new(0, 0));
+        while let Some(Event::Key(Key::Char(ch))) = term.get_event(timeout).unwrap() {
             match maindlg.result_for_key(ch) {
                 Some(DialogResult::Ok) => break 'main,
                 Some(DialogResult::Custom(i)) => {
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::process::Command;
+
+use libc;
+
+use core::cellbuffer::{Cell, CellAccessor, CellBuffer, Color};
+use core::width;
+
+/// Captures what the screen currently shows, to use as the backdrop of a `Terminal` drawing
+/// over it, see `Terminal::overlay()`.
+///
+/// Terminals offer no standard way to read back their contents, so this only works where
+/// another way exists: inside tmux, whose pane contents are captured without their styles, and
+/// on the Linux console, through its `/dev/vcsa` device, which needs read access to it. Returns
+/// `None` elsewhere, in which case an application can supply a backdrop of its own with
+/// `Terminal::set_backdrop()`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::{capture_screen, HasSize};
+///
+/// if let Some(screen) = capture_screen() {
+///     println!("captured a screen of {:?}", screen.size());
+/// }
+/// ```
+pub fn capture_screen() -> Option<CellBuffer> {
+    capture_tmux().or_else(|| {
+        // Standard input may be redirected, but the controlling terminal is what gets drawn on.
+        let tty = File::open("/dev/tty").ok()?;
+        capture_console(tty.as_raw_fd())
+    })
+}
+
+fn capture_tmux() -> Option<CellBuffer> {
+    env::var_os("TMUX")?;
+    let mut command = Command::new("tmux");
+    command.args(["capture-pane", "-p"]);
+    // Capture the pane the program runs in, rather than whichever pane is active.
+    if let Some(pane) = env::var_os("TMUX_PANE") {
+        command.arg("-t").arg(pane);
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = text.lines().collect();
+    let cols = lines.iter().map(|line| width::display_width(line)).max().unwrap_or(0);
+    let mut buf = CellBuffer::new(cols, lines.len(), Cell::default());
+    for (y, line) in lines.iter().enumerate() {
+        let mut x = 0;
+        for grapheme in width::graphemes(line) {
+            x += buf.put_char(x, y, grapheme.chars().next().unwrap());
+        }
+    }
+    Some(buf)
+}
+
+fn capture_console(fd: RawFd) -> Option<CellBuffer> {
+    let number = console_number(fd)?;
+    let mut data = Vec::new();
+    File::open(format!("/dev/vcsa{}", number)).ok()?.read_to_end(&mut data).ok()?;
+    // A header of the number of rows and columns and the cursor position, followed by a
+    // character and an attribute byte for every cell.
+    let (rows, cols) = (*data.first()? as usize, *data.get(1)? as usize);
+    let cells = data.get(4..4 + 2 * rows * cols)?;
+    let mut buf = CellBuffer::new(cols, rows, Cell::default());
+    for (i, pair) in cells.chunks(2).enumerate() {
+        let (ch, attr) = (pair[0], pair[1]);
+        // Bytes beyond ASCII are in the console font's encoding, which is unknown.
+        let ch = match ch {
+            0x20..=0x7e => ch as char,
+            0 => ' ',
+            _ => '?',
+        };
+        let (fg, bg) = (vga_color(attr & 0x0f), vga_color(attr >> 4 & 0x07));
+        if let Some(cell) = buf.get_mut(i % cols, i / cols) {
+            cell.set_ch(ch).set_fg(fg).set_bg(bg);
+        }
+    }
+    Some(buf)
+}
+
+// Returns N if `fd` is the virtual console /dev/ttyN, whose contents are in /dev/vcsaN.
+#[cfg(target_os = "linux")]
+fn console_number(fd: RawFd) -> Option<u32> {
+    // Asks for the device `fd` really is, since it may be opened as /dev/tty.
+    let mut dev: libc::c_uint = 0;
+    if unsafe { libc::ioctl(fd, libc::TIOCGDEV, &mut dev) } != 0 {
+        return None;
+    }
+    // Virtual consoles have major number 4 and minor numbers 1 to 63.
+    let dev = dev as libc::dev_t;
+    match (libc::major(dev), libc::minor(dev)) {
+        (4, number @ 1..=63) => Some(number),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn console_number(_: RawFd) -> Option<u32> {
+    None
+}
+
+// Returns the color of a VGA color number, whose red and blue bits are swapped relative to ANSI
+// colors, with the intensity bit selecting the bright variants.
+fn vga_color(vga: u8) -> Color {
+    let ansi = (vga & 0x01) << 2 | (vga & 0x02) | (vga & 0x04) >> 2 | (vga & 0x08);
+    Color::Byte(ansi)
+}
@@ -0,0 +1,105 @@
+use std::env;
+
+use core::driver::{DevFn, Driver};
+use core::features::{self, Feature};
+
+// Terminals known to support synchronized output (mode 2026), by the prefix of `TERM` and by
+// `TERM_PROGRAM`. There is no terminfo capability the terminfo parser reads for it.
+const SYNC_TERMS: &[&str] = &["xterm-kitty", "wezterm", "foot", "contour", "alacritty"];
+const SYNC_PROGRAMS: &[&str] = &["WezTerm", "iTerm.app", "ghostty"];
+
+/// The number of colors a terminal can display, from fewest to most.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors.
+    Monochrome,
+    /// The 8 basic colors.
+    Colors8,
+    /// The 8 basic colors and their bright variants.
+    Colors16,
+    /// The 256 colors of the xterm palette.
+    Colors256,
+    /// 24-bit colors.
+    Truecolor,
+}
+
+/// What the attached terminal supports, see `Terminal::capabilities()`.
+///
+/// The report is derived from the terminfo database and the environment. The fields corresponding
+/// to a `Feature` take overrides made with `set_supported()` into account, while `dim` and
+/// `synchronized_output` are always detected. Terminals misdescribed by their `TERM` are reported
+/// according to what they claim to be.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::{ColorDepth, Terminal};
+///
+/// let term = Terminal::new().unwrap();
+/// let caps = term.capabilities();
+/// if caps.colors >= ColorDepth::Colors256 && caps.italic {
+///     // Use the fancy theme.
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The number of colors.
+    pub colors: ColorDepth,
+    /// Characters beyond ASCII can be displayed, see `Feature::Unicode`.
+    pub unicode: bool,
+    /// Box drawing characters can be displayed, see `Feature::LineDrawing`.
+    pub line_drawing: bool,
+    /// Mouse events are reported.
+    pub mouse: bool,
+    /// The `Terminal` draws on an alternate screen, which leaves the contents of the primary
+    /// screen untouched. Not so with `Terminal::overlay()` or where the terminal has none.
+    pub alt_screen: bool,
+    /// Text can be displayed in italics.
+    pub italic: bool,
+    /// Text can be displayed dimmed.
+    pub dim: bool,
+    /// Styled and colored underlines, see `Feature::StyledUnderline`.
+    pub styled_underline: bool,
+    /// A status line separate from the window, see `Feature::StatusLine`.
+    pub status_line: bool,
+    /// Updates can be displayed at once rather than as they arrive, with synchronized output.
+    /// Only known terminals are reported to support it.
+    pub synchronized_output: bool,
+}
+
+impl Capabilities {
+    // Returns the capabilities of the terminal described by `driver`, drawn on by a `Terminal`
+    // that enters the alternate screen if `alt_screen` is set.
+    pub fn detect(driver: &Driver, alt_screen: bool) -> Capabilities {
+        let colors = if features::supported(Feature::Truecolor) {
+            ColorDepth::Truecolor
+        } else if features::supported(Feature::Colors256) {
+            ColorDepth::Colors256
+        } else {
+            match driver.colors() {
+                0..=7 => ColorDepth::Monochrome,
+                8..=15 => ColorDepth::Colors8,
+                16..=255 => ColorDepth::Colors16,
+                _ => ColorDepth::Colors256,
+            }
+        };
+        Capabilities {
+            colors,
+            unicode: features::supported(Feature::Unicode),
+            line_drawing: features::supported(Feature::LineDrawing),
+            mouse: features::supported(Feature::Mouse),
+            alt_screen: alt_screen && !driver.get(DevFn::EnterCa).is_empty(),
+            italic: features::supported(Feature::Italic),
+            dim: driver.has_dim(),
+            styled_underline: features::supported(Feature::StyledUnderline),
+            status_line: features::supported(Feature::StatusLine),
+            synchronized_output: has_synchronized_output(),
+        }
+    }
+}
+
+// Returns `true` if the terminal is known to support synchronized output.
+fn has_synchronized_output() -> bool {
+    env::var("TERM").is_ok_and(|term| SYNC_TERMS.iter().any(|prefix| term.starts_with(prefix))) ||
+    env::var("TERM_PROGRAM").is_ok_and(|program| SYNC_PROGRAMS.contains(&program.as_str()))
+}
@@ -1,6 +1,11 @@
-use std::ops::{Index, IndexMut, Deref, DerefMut};
+use std::cmp;
+use std::ops::{BitOr, BitOrAssign, Index, IndexMut, Deref, DerefMut};
 
 use core::position::{Pos, Size, HasSize};
+use core::width;
+
+// The character of the cell covered by the right half of a double-width character.
+const CONTINUATION: char = '\0';
 
 // I tried really hard to implement Index + IndexMut directly in the trait, but I coudn't get it
 // to compile...
@@ -16,6 +21,11 @@ pub trait CellAccessor: HasSize {
         }
     }
 
+    /// Returns the index into `cellvec()` of the `Cell` at column `x` and row `y`, or `None` if
+    /// the coordinates are out of bounds.
+    ///
+    /// Cells are always stored row by row, so the cell to the right of a cell is the next one in
+    /// `cellvec()`.
     fn pos_to_index(&self, x: usize, y: usize) -> Option<usize> {
         let (cols, rows) = self.size();
         if x < cols && y < rows {
@@ -25,6 +35,65 @@ pub trait CellAccessor: HasSize {
         }
     }
 
+    /// Returns the `(x, y)` coordinates of the `Cell` at an index into `cellvec()`, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::Widget;
+    ///
+    /// let w = Widget::new(10, 3);
+    /// assert_eq!(w.pos_to_index(4, 2), Some(24));
+    /// assert_eq!(w.index_to_pos(24), Some((4, 2)));
+    /// assert_eq!(w.index_to_pos(30), None);
+    /// ```
+    fn index_to_pos(&self, i: usize) -> Option<Pos> {
+        let (cols, rows) = self.size();
+        if i < cols * rows {
+            Some((i % cols, i / cols))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the `Cell` at column `x` and row `y`, or `None` if the coordinates
+    /// are out of bounds. The same as `get()`, spelled out for clarity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::Widget;
+    ///
+    /// let mut w = Widget::new(10, 3);
+    /// w.at_xy_mut(7, 1).unwrap().set_ch('x');
+    /// assert_eq!(w.at_rc(1, 7).unwrap().ch(), 'x');
+    /// assert!(w.at_xy(1, 7).is_none());
+    /// ```
+    fn at_xy(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.get(x, y)
+    }
+
+    /// Returns a mutable reference to the `Cell` at column `x` and row `y`, or `None` if the
+    /// coordinates are out of bounds.
+    fn at_xy_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        self.get_mut(x, y)
+    }
+
+    /// Returns a reference to the `Cell` in row `row` and column `col`, or `None` if the
+    /// coordinates are out of bounds.
+    fn at_rc(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.get(col, row)
+    }
+
+    /// Returns a mutable reference to the `Cell` in row `row` and column `col`, or `None` if the
+    /// coordinates are out of bounds.
+    fn at_rc_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.get_mut(col, row)
+    }
+
     /// Returns a reference to the `Cell` at the given coordinates, or `None` if the index is out of
     /// bounds.
     ///
@@ -62,6 +131,70 @@ pub trait CellAccessor: HasSize {
             None => None,
         }
     }
+
+    /// Returns a mutable reference to the `Cell` at the given coordinates, or `None` if the index
+    /// is out of bounds or the `Cell` is protected.
+    ///
+    /// Editing widgets should use this instead of `get_mut()` so that protected regions, such as
+    /// prompts, cannot be overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::Widget;
+    ///
+    /// let mut line = Widget::new(10, 1);
+    /// line.get_mut(0, 0).unwrap().set_ch('>').set_protected(true);
+    ///
+    /// assert!(line.get_editable_mut(0, 0).is_none());
+    /// assert!(line.get_editable_mut(1, 0).is_some());
+    /// ```
+    fn get_editable_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        self.get_mut(x, y).and_then(|c| if c.is_protected() { None } else { Some(c) })
+    }
+
+    /// Sets the character of the `Cell` at the given coordinates to `ch`, keeping its style, and
+    /// returns the number of columns `ch` takes up. Out of bounds coordinates are ignored.
+    ///
+    /// A double-width character takes up the next cell too, which becomes a continuation cell,
+    /// see `Cell::is_continuation()`. Overwriting either half of a double-width character blanks
+    /// the other half, as terminals do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::Widget;
+    ///
+    /// let mut w = Widget::new(4, 1);
+    /// assert_eq!(w.put_char(0, 0, '世'), 2);
+    /// assert!(w.get(1, 0).unwrap().is_continuation());
+    ///
+    /// // Overwriting the right half blanks the left one.
+    /// w.put_char(1, 0, 'x');
+    /// assert_eq!(w.get(0, 0).unwrap().ch(), ' ');
+    /// ```
+    fn put_char(&mut self, x: usize, y: usize, ch: char) -> usize {
+        let width = width::char_width(ch);
+        let cols = self.size().0;
+        if self.get(x, y).is_none() {
+            return width;
+        }
+        let end = if width > 1 { cmp::min(x + 2, cols) } else { x + 1 };
+        // Blank the halves of double-width characters left behind.
+        if x > 0 && self.get(x, y).is_some_and(Cell::is_continuation) {
+            self.get_mut(x - 1, y).unwrap().set_ch(' ');
+        }
+        if self.get(end, y).is_some_and(Cell::is_continuation) {
+            self.get_mut(end, y).unwrap().set_ch(' ');
+        }
+        let cell = *self.get_mut(x, y).unwrap().set_ch(ch);
+        if end > x + 1 {
+            *self.get_mut(x + 1, y).unwrap() = cell.with_ch(CONTINUATION);
+        }
+        width
+    }
 }
 
 /// An array of `Cell`s that represents a terminal display.
@@ -69,8 +202,9 @@ pub trait CellAccessor: HasSize {
 /// A `CellBuffer` is a two-dimensional array of `Cell`s, each pair of indices correspond to a
 /// single point on the underlying terminal.
 ///
-/// The first index, `Cellbuffer[y]`, corresponds to a row, and thus the y-axis. The second
-/// index, `Cellbuffer[y][x]`, corresponds to a column within a row and thus the x-axis.
+/// Like every `CellAccessor`, a `CellBuffer` stores its cells row by row and is indexed by
+/// `(x, y)` tuples, where `x` is the column and `y` the row: `buffer[(x, y)]`. Use `at_xy()` or
+/// `at_rc()` to make the order of the coordinates explicit.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CellBuffer {
     cols: usize,
@@ -159,6 +293,38 @@ impl Default for CellBuffer {
     }
 }
 
+/// Declares an array of `Cell`s, for static tables of sprites and other screen art.
+///
+/// The cells are given as a list of `char`s, optionally preceded by the foreground, background
+/// and attribute they all share and `=>`. The array can be used as a `const` or `static`
+/// initializer.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate rustty;
+///
+/// use rustty::{Attr, Cell, Color};
+///
+/// static SHIP: [Cell; 3] = cells!['<', '=', '>'];
+/// static ALERT: [Cell; 2] = cells![Color::Red, Color::Default, Attr::Bold => '!', '!'];
+///
+/// fn main() {
+///     assert_eq!(SHIP[1].ch(), '=');
+///     assert_eq!(ALERT[0], Cell::new('!', Color::Red, Color::Default, Attr::Bold));
+/// }
+/// ```
+#[macro_export]
+macro_rules! cells {
+    ($fg:expr, $bg:expr, $attr:expr => $($ch:expr),* $(,)*) => {
+        [$($crate::Cell::new($ch, $fg, $bg, $attr)),*]
+    };
+    ($($ch:expr),* $(,)*) => {
+        [$($crate::Cell::with_char($ch)),*]
+    };
+}
+
 /// A single point on a terminal display.
 ///
 /// A `Cell` contains a character and style.
@@ -168,11 +334,17 @@ pub struct Cell {
     fg: Color,
     bg: Color,
     attrs: Attr,
+    underline_style: UnderlineStyle,
+    underline_color: Color,
+    protected: bool,
 }
 
 impl Cell {
     /// Creates a new `Cell` with the given `char`, `Color`s and `Attr`.
     ///
+    /// Like the other constructors this is a `const fn`, so cells can be declared in statics; see
+    /// also the `cells!` macro.
+    ///
     /// # Examples
     ///
     /// ```
@@ -184,12 +356,15 @@ impl Cell {
     /// assert_eq!(cell.bg(), Color::Green);
     /// assert_eq!(cell.attrs(), Attr::Default);
     /// ```
-    pub fn new(ch: char, fg: Color, bg: Color, attrs: Attr) -> Cell {
+    pub const fn new(ch: char, fg: Color, bg: Color, attrs: Attr) -> Cell {
         Cell {
-            ch: ch,
-            fg: fg,
-            bg: bg,
-            attrs: attrs,
+            ch,
+            fg,
+            bg,
+            attrs,
+            underline_style: UnderlineStyle::Single,
+            underline_color: Color::Default,
+            protected: false,
         }
     }
 
@@ -206,7 +381,7 @@ impl Cell {
     /// assert_eq!(cell.bg(), Color::Default);
     /// assert_eq!(cell.attrs(), Attr::Default);
     /// ```
-    pub fn with_char(ch: char) -> Cell {
+    pub const fn with_char(ch: char) -> Cell {
         Cell::new(ch, Color::Default, Color::Default, Attr::Default)
     }
 
@@ -223,10 +398,58 @@ impl Cell {
     /// assert_eq!(cell.attrs(), Attr::Bold);
     /// assert_eq!(cell.ch(), ' ');
     /// ```
-    pub fn with_style(fg: Color, bg: Color, attr: Attr) -> Cell {
+    pub const fn with_style(fg: Color, bg: Color, attr: Attr) -> Cell {
         Cell::new(' ', fg, bg, attr)
     }
 
+    /// Returns a `CellBuilder` starting from the default `Cell`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Cell, Color, Attr};
+    ///
+    /// let cell = Cell::builder().ch('x').fg(Color::Red).bold().underline().build();
+    /// assert_eq!(cell, Cell::new('x', Color::Red, Color::Default, Attr::BoldUnderline));
+    /// ```
+    pub const fn builder() -> CellBuilder {
+        CellBuilder { cell: Cell::with_char(' ') }
+    }
+
+    /// Returns the `Cell` with its character replaced by `ch`.
+    pub const fn with_ch(mut self, ch: char) -> Cell {
+        self.ch = ch;
+        self
+    }
+
+    /// Returns the `Cell` with its foreground replaced by `fg`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Cell, Color, Attr};
+    ///
+    /// let warning = Cell::with_char('!').with_fg(Color::Yellow).with_attr(Attr::Bold);
+    /// assert_eq!(warning.fg(), Color::Yellow);
+    /// assert_eq!(warning.attrs(), Attr::Bold);
+    /// ```
+    pub const fn with_fg(mut self, fg: Color) -> Cell {
+        self.fg = fg;
+        self
+    }
+
+    /// Returns the `Cell` with its background replaced by `bg`.
+    pub const fn with_bg(mut self, bg: Color) -> Cell {
+        self.bg = bg;
+        self
+    }
+
+    /// Returns the `Cell` with its attributes replaced by `attr`.
+    pub const fn with_attr(mut self, attr: Attr) -> Cell {
+        self.attrs = attr;
+        self
+    }
+
     /// Returns the `Cell`'s character.
     ///
     /// # Examples
@@ -331,6 +554,185 @@ impl Cell {
         self.attrs = newattrs;
         self
     }
+
+    /// Returns the style of the line drawn under the `Cell` when it has `Attr::Underline`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Attr, Cell, Color, UnderlineStyle};
+    ///
+    /// let mut cell = Cell::with_char('x');
+    /// assert_eq!(cell.underline_style(), UnderlineStyle::Single);
+    ///
+    /// // An error squiggle.
+    /// cell.set_attrs(Attr::Underline)
+    ///     .set_underline_style(UnderlineStyle::Curly)
+    ///     .set_underline_color(Color::Red);
+    /// assert_eq!(cell.underline_style(), UnderlineStyle::Curly);
+    /// assert_eq!(cell.underline_color(), Color::Red);
+    /// ```
+    pub fn underline_style(&self) -> UnderlineStyle {
+        self.underline_style
+    }
+
+    /// Sets the style of the line drawn under the `Cell` when it has `Attr::Underline`.
+    pub fn set_underline_style(&mut self, style: UnderlineStyle) -> &mut Cell {
+        self.underline_style = style;
+        self
+    }
+
+    /// Returns the color of the line drawn under the `Cell`. `Color::Default` draws it in the
+    /// foreground color, which is the default.
+    pub fn underline_color(&self) -> Color {
+        self.underline_color
+    }
+
+    /// Sets the color of the line drawn under the `Cell`.
+    pub fn set_underline_color(&mut self, color: Color) -> &mut Cell {
+        self.underline_color = color;
+        self
+    }
+
+    /// Returns `true` if the `Cell` is protected from editing.
+    ///
+    /// Protected cells hold content such as prompts and decorations that editing widgets must not
+    /// overwrite; they are skipped by `CellAccessor::get_editable_mut()` and the drawing helpers
+    /// built on it. Protection has no effect on how a cell is displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Cell;
+    ///
+    /// let mut cell = Cell::with_char('>');
+    /// assert!(!cell.is_protected());
+    ///
+    /// cell.set_protected(true);
+    /// assert!(cell.is_protected());
+    /// ```
+    pub fn is_protected(&self) -> bool {
+        self.protected
+    }
+
+    /// Sets whether the `Cell` is protected from editing.
+    pub fn set_protected(&mut self, protected: bool) -> &mut Cell {
+        self.protected = protected;
+        self
+    }
+
+    /// Returns `true` if the `Cell` is covered by the right half of the double-width character
+    /// in the cell before it, as written by `CellAccessor::put_char()`. Continuation cells hold
+    /// the character `'\0'` and are not drawn themselves; one that is not preceded by a
+    /// double-width character is drawn blank.
+    pub fn is_continuation(&self) -> bool {
+        self.ch == CONTINUATION
+    }
+}
+
+/// Builds a `Cell` one property at a time, see `Cell::builder()`.
+///
+/// Attribute methods such as `bold()` add to the attributes already set rather than replacing
+/// them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CellBuilder {
+    cell: Cell,
+}
+
+impl CellBuilder {
+    /// Sets the character.
+    pub const fn ch(mut self, ch: char) -> CellBuilder {
+        self.cell.ch = ch;
+        self
+    }
+
+    /// Sets the foreground `Color`.
+    pub const fn fg(mut self, fg: Color) -> CellBuilder {
+        self.cell.fg = fg;
+        self
+    }
+
+    /// Sets the background `Color`.
+    pub const fn bg(mut self, bg: Color) -> CellBuilder {
+        self.cell.bg = bg;
+        self
+    }
+
+    /// Replaces the attributes.
+    pub const fn attrs(mut self, attrs: Attr) -> CellBuilder {
+        self.cell.attrs = attrs;
+        self
+    }
+
+    /// Adds bold to the attributes.
+    pub const fn bold(self) -> CellBuilder {
+        self.add_attr(Attr::Bold)
+    }
+
+    /// Adds underlining to the attributes.
+    pub const fn underline(self) -> CellBuilder {
+        self.add_attr(Attr::Underline)
+    }
+
+    /// Adds reverse video to the attributes.
+    pub const fn reverse(self) -> CellBuilder {
+        self.add_attr(Attr::Reverse)
+    }
+
+    /// Adds italics to the attributes.
+    pub const fn italic(self) -> CellBuilder {
+        self.add_attr(Attr::Italic)
+    }
+
+    /// Adds dimming to the attributes.
+    pub const fn dim(self) -> CellBuilder {
+        self.add_attr(Attr::Dim)
+    }
+
+    /// Adds strikethrough to the attributes.
+    pub const fn strikethrough(self) -> CellBuilder {
+        self.add_attr(Attr::Strikethrough)
+    }
+
+    /// Adds blinking to the attributes.
+    pub const fn blink(self) -> CellBuilder {
+        self.add_attr(Attr::Blink)
+    }
+
+    /// Adds underlining in the given style to the attributes.
+    pub const fn underline_style(mut self, style: UnderlineStyle) -> CellBuilder {
+        self.cell.underline_style = style;
+        self.add_attr(Attr::Underline)
+    }
+
+    /// Sets the color of the underline.
+    pub const fn underline_color(mut self, color: Color) -> CellBuilder {
+        self.cell.underline_color = color;
+        self
+    }
+
+    /// Sets whether the cell is protected from editing.
+    pub const fn protected(mut self, protected: bool) -> CellBuilder {
+        self.cell.protected = protected;
+        self
+    }
+
+    /// Returns the built `Cell`.
+    pub const fn build(self) -> Cell {
+        self.cell
+    }
+
+    const fn add_attr(mut self, attr: Attr) -> CellBuilder {
+        self.cell.attrs = self.cell.attrs.union(attr);
+        self
+    }
+}
+
+impl From<Cell> for CellBuilder {
+    /// Returns a `CellBuilder` starting from `cell`.
+    fn from(cell: Cell) -> CellBuilder {
+        CellBuilder { cell }
+    }
 }
 
 impl Default for Cell {
@@ -359,7 +761,14 @@ impl Default for Cell {
 /// color range; in addition, the eight basic colors coupled with `Attr::Bold` correspond to
 /// 0x08..0x0f in the 8-bit color range.
 ///
-/// `Color::Byte(..)` may be used to specify a color in the 8-bit range.
+/// `Color::Byte(..)` may be used to specify a color in the 8-bit range. Constants such as
+/// `Color::BrightRed` name the bright variants of the basic colors, and `Color::cube()` and
+/// `Color::grayscale()` pick colors from the rest of the range.
+///
+/// `Color::Rgb(..)` specifies a 24-bit color. Terminals without `Feature::Truecolor` are sent the
+/// closest 8-bit color instead.
+///
+/// `Color::Slot(..)` refers to a slot of the terminal's `Palette` rather than to a fixed color.
 ///
 /// # Examples
 ///
@@ -376,7 +785,11 @@ impl Default for Cell {
 /// let fancy = Color::Byte(0x01);
 ///
 /// // Basic colors are also 8-bit colors (but not vice-versa).
-/// assert_eq!(red.as_byte(), fancy.as_byte())
+/// assert_eq!(red.as_byte(), fancy.as_byte());
+///
+/// // A 24-bit color, close to 8-bit color 208.
+/// let orange = Color::Rgb(0xff, 0x88, 0x00);
+/// assert_eq!(orange.as_byte(), 208);
 /// ```
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Color {
@@ -389,12 +802,184 @@ pub enum Color {
     Cyan,
     White,
     Byte(u8),
+    Rgb(u8, u8, u8),
+    Slot(u8),
     Default,
 }
 
+#[allow(non_upper_case_globals)]
 impl Color {
-    /// Returns the `u8` representation of the `Color`.
-    pub fn as_byte(&self) -> u8 {
+    /// Bright black, often displayed as dark gray. 8-bit color 8.
+    pub const BrightBlack: Color = Color::Byte(0x08);
+    /// Bright red, 8-bit color 9.
+    pub const BrightRed: Color = Color::Byte(0x09);
+    /// Bright green, 8-bit color 10.
+    pub const BrightGreen: Color = Color::Byte(0x0a);
+    /// Bright yellow, 8-bit color 11.
+    pub const BrightYellow: Color = Color::Byte(0x0b);
+    /// Bright blue, 8-bit color 12.
+    pub const BrightBlue: Color = Color::Byte(0x0c);
+    /// Bright magenta, 8-bit color 13.
+    pub const BrightMagenta: Color = Color::Byte(0x0d);
+    /// Bright cyan, 8-bit color 14.
+    pub const BrightCyan: Color = Color::Byte(0x0e);
+    /// Bright white, 8-bit color 15.
+    pub const BrightWhite: Color = Color::Byte(0x0f);
+
+    /// Returns the color of the 6x6x6 color cube of the 8-bit range with the given levels of
+    /// red, green and blue, each from 0 to 5. Greater levels are taken as 5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// assert_eq!(Color::cube(0, 0, 0), Color::Byte(16));
+    /// assert_eq!(Color::cube(5, 2, 0), Color::Byte(208));
+    /// assert_eq!(Color::cube(5, 5, 5), Color::Byte(231));
+    /// ```
+    pub const fn cube(r: u8, g: u8, b: u8) -> Color {
+        let (r, g, b) = (clamp_level(r, 5), clamp_level(g, 5), clamp_level(b, 5));
+        Color::Byte(16 + 36 * r + 6 * g + b)
+    }
+
+    /// Returns the gray of the 24-step grayscale ramp of the 8-bit range at the given level,
+    /// from 0, nearly black, to 23, nearly white. Greater levels are taken as 23.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// assert_eq!(Color::grayscale(0), Color::Byte(232));
+    /// assert_eq!(Color::grayscale(23), Color::Byte(255));
+    /// ```
+    pub const fn grayscale(level: u8) -> Color {
+        Color::Byte(232 + clamp_level(level, 23))
+    }
+
+    /// Returns the color of the 8-bit range closest to a 24-bit color, out of the color cube and
+    /// the grayscale ramp. This is the color sent for a `Color::Rgb(..)` to terminals without
+    /// `Feature::Truecolor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// assert_eq!(Color::from_rgb_approx(0xff, 0x88, 0x00), Color::cube(5, 2, 0));
+    /// assert_eq!(Color::from_rgb_approx(0x80, 0x80, 0x80), Color::grayscale(12));
+    /// ```
+    pub const fn from_rgb_approx(r: u8, g: u8, b: u8) -> Color {
+        Color::Byte(rgb_to_byte(r, g, b))
+    }
+
+    /// Returns the red, green and blue components of the color, or `None` for `Color::Default`
+    /// and `Color::Slot(..)`, which have no fixed color.
+    ///
+    /// Colors of the 8-bit range are converted with the xterm defaults, which most terminals
+    /// share for the color cube and the grayscale ramp but not necessarily for the first 16
+    /// colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// assert_eq!(Color::cube(5, 2, 0).to_rgb(), Some((0xff, 0x87, 0x00)));
+    /// assert_eq!(Color::grayscale(0).to_rgb(), Some((0x08, 0x08, 0x08)));
+    /// assert_eq!(Color::Default.to_rgb(), None);
+    /// ```
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match *self {
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            Color::Default | Color::Slot(_) => None,
+            color => Some(byte_to_rgb(color.as_byte())),
+        }
+    }
+
+    /// Returns the color a fraction `t` of the way from `self` to `other`, from `0.0` for `self`
+    /// to `1.0` for `other`, as a `Color::Rgb(..)`. This is a linear blend of the components,
+    /// for fades and for mixing a color into a background.
+    ///
+    /// Returns `self` if either color has no fixed color, see `to_rgb()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// let fade = Color::Rgb(0, 0, 0).lerp(Color::Rgb(200, 100, 0), 0.25);
+    /// assert_eq!(fade, Color::Rgb(50, 25, 0));
+    /// assert_eq!(Color::Default.lerp(Color::Red, 0.5), Color::Default);
+    /// ```
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let ((r1, g1, b1), (r2, g2, b2)) = match (self.to_rgb(), other.to_rgb()) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return self,
+        };
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+    }
+
+    /// Returns the color blended a fraction `amount` of the way to white, such as for the hover
+    /// state of a button.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// let button = Color::Rgb(0x20, 0x40, 0x80);
+    /// assert_eq!(button.lighten(0.5), Color::Rgb(0x90, 0xa0, 0xc0));
+    /// assert_eq!(button.darken(0.5), Color::Rgb(0x10, 0x20, 0x40));
+    /// ```
+    pub fn lighten(self, amount: f32) -> Color {
+        self.lerp(Color::Rgb(0xff, 0xff, 0xff), amount)
+    }
+
+    /// Returns the color blended a fraction `amount` of the way to black, such as for the
+    /// pressed state of a button.
+    pub fn darken(self, amount: f32) -> Color {
+        self.lerp(Color::Rgb(0, 0, 0), amount)
+    }
+
+    /// Returns the relative luminance of the color as defined by WCAG, from `0.0` for black to
+    /// `1.0` for white, or `None` if it has no fixed color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Color;
+    ///
+    /// // Pick readable text for a background.
+    /// let bg = Color::Rgb(0x20, 0x40, 0x80);
+    /// let dark = bg.luminance().is_some_and(|l| l < 0.5);
+    /// let fg = if dark { Color::White } else { Color::Black };
+    /// assert_eq!(fg, Color::White);
+    /// ```
+    pub fn luminance(&self) -> Option<f32> {
+        let (r, g, b) = self.to_rgb()?;
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+    }
+
+    /// Returns the `u8` representation of the `Color`, which for a `Color::Rgb(..)` is the
+    /// closest color of the 6x6x6 color cube or the grayscale ramp of the 8-bit range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the color is `Color::Default` or a `Color::Slot(..)`, neither of which has a
+    /// fixed representation.
+    pub const fn as_byte(&self) -> u8 {
         match *self {
             Color::Black => 0x00,
             Color::Red => 0x01,
@@ -405,16 +990,113 @@ impl Color {
             Color::Cyan => 0x06,
             Color::White => 0x07,
             Color::Byte(b) => b,
+            Color::Rgb(r, g, b) => rgb_to_byte(r, g, b),
+            Color::Slot(_) => panic!("Attempted to cast palette slot to u8"),
             Color::Default => panic!("Attempted to cast default color to u8"),
         }
     }
 }
 
-/// The attributes of a `Cell`.
+const fn clamp_level(level: u8, max: u8) -> u8 {
+    if level < max { level } else { max }
+}
+
+// Levels of each component in the 6x6x6 color cube, which starts at 8-bit color 16.
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+// Returns the index of the cube level closest to `v`.
+const fn cube_index(v: u8) -> u8 {
+    let mut i = 0;
+    while i < 5 && (v as u16) * 2 > CUBE_LEVELS[i] as u16 + CUBE_LEVELS[i + 1] as u16 {
+        i += 1;
+    }
+    i as u8
+}
+
+// Returns the square of the distance between two colors.
+const fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let (dr, dg, db) = (r1 as i32 - r2 as i32, g1 as i32 - g2 as i32, b1 as i32 - b2 as i32);
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+// The first 16 colors of the 8-bit range as xterm displays them by default.
+const BASIC_RGB: [(u8, u8, u8); 16] = [(0x00, 0x00, 0x00),
+                                       (0xcd, 0x00, 0x00),
+                                       (0x00, 0xcd, 0x00),
+                                       (0xcd, 0xcd, 0x00),
+                                       (0x00, 0x00, 0xee),
+                                       (0xcd, 0x00, 0xcd),
+                                       (0x00, 0xcd, 0xcd),
+                                       (0xe5, 0xe5, 0xe5),
+                                       (0x7f, 0x7f, 0x7f),
+                                       (0xff, 0x00, 0x00),
+                                       (0x00, 0xff, 0x00),
+                                       (0xff, 0xff, 0x00),
+                                       (0x5c, 0x5c, 0xff),
+                                       (0xff, 0x00, 0xff),
+                                       (0x00, 0xff, 0xff),
+                                       (0xff, 0xff, 0xff)];
+
+// Returns the 24-bit color of an 8-bit one.
+fn byte_to_rgb(byte: u8) -> (u8, u8, u8) {
+    match byte {
+        0..=15 => BASIC_RGB[byte as usize],
+        16..=231 => {
+            let i = byte - 16;
+            let level = |i: u8| CUBE_LEVELS[i as usize];
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let level = 8 + (byte - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+// Returns the 8-bit color closest to a 24-bit one, out of the color cube and the grayscale ramp
+// (colors 232 to 255, from 0x08 to 0xee in steps of 10). The first 16 colors are left out since
+// terminals display them differently.
+const fn rgb_to_byte(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (CUBE_LEVELS[ri as usize], CUBE_LEVELS[gi as usize], CUBE_LEVELS[bi as usize]);
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let step = if average < 8 {
+        0
+    } else if average > 238 {
+        23
+    } else {
+        (average - 8 + 5) / 10
+    };
+    let level = (8 + step * 10) as u8;
+    if distance((r, g, b), (level, level, level)) < distance((r, g, b), cube) {
+        232 + step as u8
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// The style of the line under a `Cell` with `Attr::Underline`.
 ///
-/// `Attr` enumerates all combinations of attributes a given style may have.
+/// Styles other than `UnderlineStyle::Single`, as well as underline colors, are only sent to
+/// terminals with `Feature::StyledUnderline`. Other terminals draw a single underline in the
+/// foreground color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    Single = 1,
+    Double = 2,
+    Curly = 3,
+    Dotted = 4,
+    Dashed = 5,
+}
+
+/// The attributes of a `Cell`, a set of text styles.
+///
+/// The single attributes, such as `Attr::Bold`, combine with `|` into sets. `Attr::Default`
+/// represents no attribute, and the combinations of bold, underline and reverse have names of
+/// their own, such as `Attr::BoldUnderline`.
 ///
-/// `Attr::Default` represents no attribute.
+/// Terminals that lack an attribute display the text without it, except for reverse, bold and
+/// underline, which every supported terminal has.
 ///
 /// # Examples
 ///
@@ -427,17 +1109,71 @@ impl Color {
 /// // Base attribute.
 /// let base = Attr::Bold;
 ///
-/// // Combination.
+/// // Combinations.
 /// let comb = Attr::UnderlineReverse;
+/// assert_eq!(comb, Attr::Underline | Attr::Reverse);
+/// let quote = Attr::Italic | Attr::Dim;
+/// assert!(quote.contains(Attr::Italic));
+/// assert!(!quote.contains(Attr::Bold));
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum Attr {
-    Default = 0b000,
-    Bold = 0b001,
-    Underline = 0b010,
-    BoldUnderline = 0b011,
-    Reverse = 0b100,
-    BoldReverse = 0b101,
-    UnderlineReverse = 0b110,
-    BoldReverseUnderline = 0b111,
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Attr(u8);
+
+#[allow(non_upper_case_globals)]
+impl Attr {
+    pub const Default: Attr = Attr(0b000);
+    pub const Bold: Attr = Attr(0b001);
+    pub const Underline: Attr = Attr(0b010);
+    pub const BoldUnderline: Attr = Attr(0b011);
+    pub const Reverse: Attr = Attr(0b100);
+    pub const BoldReverse: Attr = Attr(0b101);
+    pub const UnderlineReverse: Attr = Attr(0b110);
+    pub const BoldReverseUnderline: Attr = Attr(0b111);
+    /// Slanted text, where the terminal has `Feature::Italic`.
+    pub const Italic: Attr = Attr(0b000_1000);
+    /// Faint text.
+    pub const Dim: Attr = Attr(0b001_0000);
+    /// Text with a line through it.
+    pub const Strikethrough: Attr = Attr(0b010_0000);
+    /// Blinking text.
+    pub const Blink: Attr = Attr(0b100_0000);
+
+    /// Returns the attributes as bits, one per single attribute.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the attributes with the given bits, ignoring bits that stand for no attribute.
+    pub const fn from_bits(bits: u8) -> Attr {
+        Attr(bits & 0b111_1111)
+    }
+
+    /// Returns `true` if all attributes of `other` are set.
+    pub const fn contains(self, other: Attr) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the attributes of both `self` and `other`.
+    pub const fn union(self, other: Attr) -> Attr {
+        Attr(self.0 | other.0)
+    }
+
+    /// Returns the attributes of `self` that are not in `other`.
+    pub const fn difference(self, other: Attr) -> Attr {
+        Attr(self.0 & !other.0)
+    }
+}
+
+impl BitOr for Attr {
+    type Output = Attr;
+
+    fn bitor(self, other: Attr) -> Attr {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for Attr {
+    fn bitor_assign(&mut self, other: Attr) {
+        *self = self.union(other);
+    }
 }
@@ -0,0 +1,29 @@
+use core::cellbuffer::Attr;
+use core::position::Pos;
+
+/// A problem found while displaying a frame that `swap_buffers()` worked around rather than
+/// failing, see `Terminal::take_diagnostics()`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::{Cell, Diagnostic, Terminal};
+///
+/// let mut term = Terminal::new().unwrap();
+/// term[(0, 0)] = Cell::with_char('\t');
+/// term.swap_buffers().unwrap();
+/// assert_eq!(term.take_diagnostics(),
+///            [Diagnostic::ControlChar { pos: (0, 0), ch: '\t' }]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// A cell held a control character, which would have moved the cursor or changed the state
+    /// of the terminal. A space was drawn instead.
+    ControlChar { pos: Pos, ch: char },
+    /// A double-width character was in the last column, where it does not fit. A space was
+    /// drawn instead.
+    Clipped { pos: Pos, ch: char },
+    /// A cell had an attribute the terminal has no capability for, such as `Attr::Italic`
+    /// without `sitm`. The cell was drawn without it.
+    MissingCapability { pos: Pos, attr: Attr },
+}
@@ -1,12 +1,15 @@
 // Temporary fix before certain constants are used.
 #![allow(dead_code)]
 
+use std::env;
 use std::io::{Error, ErrorKind};
 
 use term::terminfo::TermInfo;
 use term::terminfo::parm;
 use term::terminfo::parm::{Param, Variables};
 
+use core::input::Key;
+
 // Terminfo keys. These are arrays because the terminfo database from the `term` crate sometimes
 // uses the variable name and othertimes the capname.
 //
@@ -27,11 +30,71 @@ const KEY_UP: &'static [&'static str] = &["key_up", "kcuu1"];
 const KEY_DOWN: &'static [&'static str] = &["key_down", "kcud1"];
 const KEY_LEFT: &'static [&'static str] = &["key_left", "kcub1"];
 const KEY_RIGHT: &'static [&'static str] = &["key_right", "kcuf1"];
+const KEY_HOME: &'static [&'static str] = &["key_home", "khome"];
+const KEY_END: &'static [&'static str] = &["key_end", "kend"];
+const KEY_PPAGE: &'static [&'static str] = &["key_ppage", "kpp"];
+const KEY_NPAGE: &'static [&'static str] = &["key_npage", "knp"];
+const KEY_BACKSPACE: &'static [&'static str] = &["key_backspace", "kbs"];
+const KEY_DC: &'static [&'static str] = &["key_dc", "kdch1"];
+const KEY_MOUSE: &'static [&'static str] = &["key_mouse", "kmous"];
+
+// Optional capabilities, only checked for.
+const ENTER_ITALICS: &'static [&'static str] = &["enter_italics_mode", "sitm"];
+const MAX_COLORS: &'static [&'static str] = &["max_colors", "colors"];
+const ACS_CHARS: &'static [&'static str] = &["acs_chars", "acsc"];
+
+// Line drawing and other characters in the VT100 alternate character set, and the characters
+// selecting them. Double, heavy and rounded lines are drawn as light lines.
+const ACS_GLYPHS: &'static [(char, u8)] = &[('┌', b'l'), ('╔', b'l'), ('╭', b'l'), ('┏', b'l'),
+                                            ('┐', b'k'), ('╗', b'k'), ('╮', b'k'), ('┓', b'k'),
+                                            ('┘', b'j'), ('╝', b'j'), ('╯', b'j'), ('┛', b'j'),
+                                            ('└', b'm'), ('╚', b'm'), ('╰', b'm'), ('┗', b'm'),
+                                            ('─', b'q'), ('═', b'q'), ('━', b'q'),
+                                            ('│', b'x'), ('║', b'x'), ('┃', b'x'),
+                                            ('├', b't'), ('╞', b't'), ('┣', b't'),
+                                            ('┤', b'u'), ('╡', b'u'), ('┫', b'u'),
+                                            ('┬', b'w'),
+                                            ('┴', b'v'),
+                                            ('┼', b'n'),
+                                            ('◆', b'`'),
+                                            ('▒', b'a'),
+                                            ('█', b'0'),
+                                            ('°', b'f'),
+                                            ('±', b'g'),
+                                            ('·', b'~'),
+                                            ('≤', b'y'),
+                                            ('≥', b'z'),
+                                            ('π', b'{'),
+                                            ('≠', b'|'),
+                                            ('£', b'}'),
+                                            ('←', b','),
+                                            ('→', b'+'),
+                                            ('↑', b'-'),
+                                            ('↓', b'.')];
 
-// Array of terminal keys.
-const KEYS: &'static [&'static [&'static str]] = &[KEY_F1, KEY_F2, KEY_F3, KEY_F4, KEY_F5, KEY_F6,
-                                                   KEY_F7, KEY_F8, KEY_F9, KEY_F10, KEY_F11,
-                                                   KEY_F12, KEY_UP, KEY_DOWN, KEY_LEFT, KEY_RIGHT];
+// Array of terminal keys and the keys they are decoded as.
+const KEYS: &'static [(&'static [&'static str], Key)] = &[(KEY_F1, Key::F(1)),
+                                                          (KEY_F2, Key::F(2)),
+                                                          (KEY_F3, Key::F(3)),
+                                                          (KEY_F4, Key::F(4)),
+                                                          (KEY_F5, Key::F(5)),
+                                                          (KEY_F6, Key::F(6)),
+                                                          (KEY_F7, Key::F(7)),
+                                                          (KEY_F8, Key::F(8)),
+                                                          (KEY_F9, Key::F(9)),
+                                                          (KEY_F10, Key::F(10)),
+                                                          (KEY_F11, Key::F(11)),
+                                                          (KEY_F12, Key::F(12)),
+                                                          (KEY_UP, Key::Up),
+                                                          (KEY_DOWN, Key::Down),
+                                                          (KEY_LEFT, Key::Left),
+                                                          (KEY_RIGHT, Key::Right),
+                                                          (KEY_HOME, Key::Home),
+                                                          (KEY_END, Key::End),
+                                                          (KEY_PPAGE, Key::PageUp),
+                                                          (KEY_NPAGE, Key::PageDown),
+                                                          (KEY_BACKSPACE, Key::Backspace),
+                                                          (KEY_DC, Key::Delete)];
 
 // String constants correspond to terminfo capnames and are used inside the module for convenience.
 const ENTER_CA: &'static str = "smcup";
@@ -44,9 +107,49 @@ const RESET: &'static str = "sgr0";
 const UNDERLINE: &'static str = "smul";
 const BOLD: &'static str = "bold";
 const BLINK: &'static str = "blink";
+const ITALIC: &'static str = "sitm";
+const DIM: &'static str = "dim";
+// Strikethrough is described by the `smxx` extension, which is not read, so the ECMA-48 sequence
+// is always used.
+const STRIKETHROUGH: &'static str = "smxx";
 const REVERSE: &'static str = "rev";
 const SETFG: &'static str = "setaf";
 const SETBG: &'static str = "setab";
+// 24-bit colors are described by the `setrgbf` and `setrgbb` extensions, which are not read, so
+// the ISO 8613-6 sequences are always used.
+const SETRGBF: &'static str = "setrgbf";
+const SETRGBB: &'static str = "setrgbb";
+// Flags announcing 24-bit colors: `RGB` is the ncurses extension, `Tc` the one tmux introduced.
+const TRUECOLOR_FLAGS: &'static [&'static str] = &["RGB", "Tc"];
+// Underline styles and colors are described by the `Smulx` and `Setulc` extensions. They are
+// only checked for, and the sequences understood by the terminals that have them are always used.
+const SET_UNDERLINE_STYLE: &'static str = "Smulx";
+const SET_UNDERLINE_COLOR: &'static str = "Setulc";
+const BELL: &'static str = "bel";
+const TO_STATUS_LINE: &'static str = "tsl";
+const FROM_STATUS_LINE: &'static str = "fsl";
+const DISABLE_STATUS_LINE: &'static str = "dsl";
+// Mouse tracking has no standard capability. `XM` is the ncurses extension describing it, but the
+// terminfo parser does not read extended capabilities, so the xterm sequences are always used.
+const MOUSE: &'static str = "XM";
+// Reports use the SGR encoding (1006) where supported, which has no limit on the coordinates,
+// and the legacy X10 encoding elsewhere.
+const ENTER_MOUSE: &'static [u8] = b"\x1b[?1000h\x1b[?1002h\x1b[?1006h";
+const EXIT_MOUSE: &'static [u8] = b"\x1b[?1006l\x1b[?1002l\x1b[?1000l";
+const ENTER_MOUSE_MOTION: &'static [u8] = b"\x1b[?1003h";
+const EXIT_MOUSE_MOTION: &'static [u8] = b"\x1b[?1003l";
+// Focus reporting is described by the `fe` and `fd` extensions, which are not read either.
+const ENTER_FOCUS: &'static str = "fe";
+const EXIT_FOCUS: &'static str = "fd";
+// Bracketed paste is described by the `BE` and `BD` extensions, which are not read either.
+const ENTER_PASTE: &'static str = "BE";
+const EXIT_PASTE: &'static str = "BD";
+// Palette entries are redefined with `initc`, which takes its parameters in a scale that varies
+// between terminals, so the xterm OSC 4 and OSC 104 sequences are always used.
+const SET_PALETTE_COLOR: &'static str = "initc";
+const RESET_PALETTE_COLOR: &'static str = "oc";
+const ENTER_ACS: &'static str = "smacs";
+const EXIT_ACS: &'static str = "rmacs";
 
 // Array of terminal capabilities. Used as an iterator to test for functionality.
 //
@@ -73,6 +176,7 @@ const CAPABILITIES: &'static [&'static str] = &[ENTER_CA,
 // to take advantage of compile-time type-checking instead of hoping invalid strings aren't passed.
 // This allows us to guarantee that driver accesses will succeed. In addition, using an enum means
 // Driver doesn't need hard-coded methods for each capability we want to use.
+#[derive(Clone, Copy)]
 pub enum DevFn {
     EnterCa,
     ExitCa,
@@ -85,8 +189,32 @@ pub enum DevFn {
     Bold,
     Blink,
     Reverse,
+    Italic,
+    Dim,
+    Strikethrough,
     SetFg(u8),
     SetBg(u8),
+    SetFgRgb(u8, u8, u8),
+    SetBgRgb(u8, u8, u8),
+    SetUnderlineStyle(u8),
+    SetUnderlineColor(u8),
+    SetUnderlineRgb(u8, u8, u8),
+    Bell,
+    ToStatusLine,
+    FromStatusLine,
+    DisableStatusLine,
+    EnterMouse,
+    ExitMouse,
+    EnterMouseMotion,
+    ExitMouseMotion,
+    EnterFocus,
+    ExitFocus,
+    EnterPaste,
+    ExitPaste,
+    EnterAcs,
+    ExitAcs,
+    SetPaletteColor(u8, u8, u8, u8),
+    ResetPaletteColor(u8),
 }
 
 impl DevFn {
@@ -103,14 +231,43 @@ impl DevFn {
             DevFn::Bold => BOLD,
             DevFn::Blink => BLINK,
             DevFn::Reverse => REVERSE,
+            DevFn::Italic => ITALIC,
+            DevFn::Dim => DIM,
+            DevFn::Strikethrough => STRIKETHROUGH,
             DevFn::SetFg(..) => SETFG,
             DevFn::SetBg(..) => SETBG,
+            DevFn::SetFgRgb(..) => SETRGBF,
+            DevFn::SetBgRgb(..) => SETRGBB,
+            DevFn::SetUnderlineStyle(..) => SET_UNDERLINE_STYLE,
+            DevFn::SetUnderlineColor(..) | DevFn::SetUnderlineRgb(..) => SET_UNDERLINE_COLOR,
+            DevFn::Bell => BELL,
+            DevFn::ToStatusLine => TO_STATUS_LINE,
+            DevFn::FromStatusLine => FROM_STATUS_LINE,
+            DevFn::DisableStatusLine => DISABLE_STATUS_LINE,
+            DevFn::EnterMouse |
+            DevFn::ExitMouse |
+            DevFn::EnterMouseMotion |
+            DevFn::ExitMouseMotion => MOUSE,
+            DevFn::EnterFocus => ENTER_FOCUS,
+            DevFn::ExitFocus => EXIT_FOCUS,
+            DevFn::EnterPaste => ENTER_PASTE,
+            DevFn::ExitPaste => EXIT_PASTE,
+            DevFn::EnterAcs => ENTER_ACS,
+            DevFn::ExitAcs => EXIT_ACS,
+            DevFn::SetPaletteColor(..) => SET_PALETTE_COLOR,
+            DevFn::ResetPaletteColor(..) => RESET_PALETTE_COLOR,
         }
     }
 }
 
 pub struct Driver {
     tinfo: TermInfo,
+    truecolor: bool,
+}
+
+// Returns `true` if `COLORTERM` announces 24-bit colors.
+pub fn colorterm_truecolor() -> bool {
+    env::var("COLORTERM").is_ok_and(|val| val == "truecolor" || val == "24bit")
 }
 
 // Validates and returns a reference to the terminfo database.
@@ -145,7 +302,12 @@ impl Driver {
     // If successful, the terminfo database is guaranteed to contain all capabilities we support.
     pub fn new() -> Result<Driver, Error> {
         let tinfo = try!(get_tinfo());
-        Ok(Driver { tinfo: tinfo })
+        // The `*-direct` terminfo entries describe terminals with 24-bit colors only.
+        let truecolor = colorterm_truecolor() ||
+                        TRUECOLOR_FLAGS.iter().any(|name| tinfo.bools.get(*name) == Some(&true)) ||
+                        tinfo.strings.contains_key(SETRGBF) ||
+                        tinfo.names.iter().any(|name| name.ends_with("-direct"));
+        Ok(Driver { tinfo, truecolor })
     }
 
     // Returns the device specific escape sequence for the given `DevFn`.
@@ -156,10 +318,63 @@ impl Driver {
     // crate.
     //
     // Furthermore, the pre-flight checks on initialization of `Driver` ensure that every
-    // capability is present, thus the call to `Hashmap::get()` should never fail.
+    // required capability is present, thus the call to `Hashmap::get()` should never fail.
+    // Optional capabilities fall back to a sensible default when missing. Attributes without
+    // one come back empty, which `Terminal` reports as `Diagnostic::MissingCapability`.
     pub fn get(&self, dfn: DevFn) -> Vec<u8> {
         let capname = dfn.as_str();
-        let cap = self.tinfo.strings.get(capname).unwrap();
+        // Without a usable status line, the status line functions set the window title instead.
+        let status_line = self.has_status_line();
+        let cap = match (self.tinfo.strings.get(capname), &dfn) {
+            (_, &DevFn::ToStatusLine) if !status_line => return b"\x1b]2;".to_vec(),
+            (_, &DevFn::FromStatusLine) if !status_line => return b"\x07".to_vec(),
+            (_, &DevFn::DisableStatusLine) if !status_line => return b"\x1b]2;\x07".to_vec(),
+            (_, &DevFn::EnterMouse) => return ENTER_MOUSE.to_vec(),
+            (_, &DevFn::ExitMouse) => return EXIT_MOUSE.to_vec(),
+            (_, &DevFn::EnterMouseMotion) => return ENTER_MOUSE_MOTION.to_vec(),
+            (_, &DevFn::ExitMouseMotion) => return EXIT_MOUSE_MOTION.to_vec(),
+            (_, &DevFn::Strikethrough) => return b"\x1b[9m".to_vec(),
+            (_, &DevFn::SetUnderlineStyle(style)) => {
+                return format!("\x1b[4:{}m", style).into_bytes()
+            }
+            (_, &DevFn::SetUnderlineColor(color)) => {
+                return format!("\x1b[58;5;{}m", color).into_bytes()
+            }
+            (_, &DevFn::SetUnderlineRgb(r, g, b)) => {
+                return format!("\x1b[58;2;{};{};{}m", r, g, b).into_bytes()
+            }
+            (_, &DevFn::SetPaletteColor(index, r, g, b)) => {
+                return format!("\x1b]4;{};rgb:{:02x}/{:02x}/{:02x}\x1b\\", index, r, g, b)
+                    .into_bytes()
+            }
+            (_, &DevFn::ResetPaletteColor(index)) => {
+                return format!("\x1b]104;{}\x1b\\", index).into_bytes()
+            }
+            (_, &DevFn::SetFgRgb(r, g, b)) => {
+                return format!("\x1b[38;2;{};{};{}m", r, g, b).into_bytes()
+            }
+            (_, &DevFn::SetBgRgb(r, g, b)) => {
+                return format!("\x1b[48;2;{};{};{}m", r, g, b).into_bytes()
+            }
+            (Some(cap), _) => cap,
+            (None, &DevFn::Bell) => return b"\x07".to_vec(),
+            (None, &DevFn::EnterFocus) => return b"\x1b[?1004h".to_vec(),
+            (None, &DevFn::ExitFocus) => return b"\x1b[?1004l".to_vec(),
+            (None, &DevFn::EnterPaste) => return b"\x1b[?2004h".to_vec(),
+            (None, &DevFn::ExitPaste) => return b"\x1b[?2004l".to_vec(),
+            (None, &DevFn::EnterAcs) | (None, &DevFn::ExitAcs) => return Vec::new(),
+            // Text is displayed without the attributes the terminal lacks.
+            (None, &DevFn::Italic) | (None, &DevFn::Dim) | (None, &DevFn::Blink) => {
+                return Vec::new()
+            }
+            (None, &DevFn::DisableStatusLine) => {
+                // Clear the status line instead.
+                let mut seq = self.get(DevFn::ToStatusLine);
+                seq.extend(self.get(DevFn::FromStatusLine));
+                return seq;
+            }
+            (None, _) => unreachable!("required capability missing: '{}'", capname),
+        };
 
         match dfn {
             DevFn::SetFg(attr) |
@@ -168,6 +383,11 @@ impl Driver {
                 let mut vars = Variables::new();
                 parm::expand(cap, params, &mut vars).unwrap()
             }
+            DevFn::ToStatusLine => {
+                let params = &[Param::Number(0)];
+                let mut vars = Variables::new();
+                parm::expand(cap, params, &mut vars).unwrap()
+            }
             DevFn::SetCursor(x, y) => {
                 let params = &[Param::Number(y as i32), Param::Number(x as i32)];
                 let mut vars = Variables::new();
@@ -176,4 +396,72 @@ impl Driver {
             _ => cap.clone(),
         }
     }
+
+    // Returns the sequences the terminal sends for the keys in KEYS. Keys missing from the
+    // terminfo database are left out.
+    pub fn keys(&self) -> Vec<(Vec<u8>, Key)> {
+        KEYS.iter()
+            .filter_map(|&(names, key)| {
+                names.iter().filter_map(|name| self.tinfo.strings.get(*name)).next().map(|seq| {
+                    (seq.clone(), key)
+                })
+            })
+            .collect()
+    }
+
+    // Returns the number of colors the terminal supports.
+    pub fn colors(&self) -> u16 {
+        MAX_COLORS.iter().filter_map(|name| self.tinfo.numbers.get(*name)).next().map_or(0, |&n| n)
+    }
+
+    // Returns `true` if the terminal can display 24-bit colors, as announced by `COLORTERM` or
+    // the terminfo database.
+    pub fn has_truecolor(&self) -> bool {
+        self.truecolor
+    }
+
+    // Returns `true` if the terminal can display italics.
+    pub fn has_italics(&self) -> bool {
+        ENTER_ITALICS.iter().any(|name| self.tinfo.strings.contains_key(*name))
+    }
+
+    // Returns `true` if the terminal can display dimmed text.
+    pub fn has_dim(&self) -> bool {
+        self.tinfo.strings.contains_key(DIM)
+    }
+
+    // Returns `true` if the terminal describes styled underlines.
+    pub fn has_styled_underlines(&self) -> bool {
+        self.tinfo.strings.contains_key(SET_UNDERLINE_STYLE)
+    }
+
+    // Returns `true` if the terminal reports mouse events.
+    pub fn has_mouse(&self) -> bool {
+        KEY_MOUSE.iter().any(|name| self.tinfo.strings.contains_key(*name))
+    }
+
+    // Returns the characters that draw the glyphs in ACS_GLYPHS in the terminal's alternate
+    // character set, or `None` if it has none. Glyphs missing from the terminal's set are left
+    // out.
+    pub fn acs_map(&self) -> Option<Vec<(char, u8)>> {
+        let strings = &self.tinfo.strings;
+        if !strings.contains_key(ENTER_ACS) || !strings.contains_key(EXIT_ACS) {
+            return None;
+        }
+        let acsc = ACS_CHARS.iter().filter_map(|name| strings.get(*name)).next()?;
+        let map = ACS_GLYPHS.iter()
+            .filter_map(|&(glyph, vt100)| {
+                acsc.chunks(2)
+                    .find(|pair| pair.len() == 2 && pair[0] == vt100)
+                    .map(|pair| (glyph, pair[1]))
+            })
+            .collect();
+        Some(map)
+    }
+
+    // Returns `true` if the terminal has a status line that can be written to.
+    pub fn has_status_line(&self) -> bool {
+        self.tinfo.strings.contains_key(TO_STATUS_LINE) &&
+        self.tinfo.strings.contains_key(FROM_STATUS_LINE)
+    }
 }
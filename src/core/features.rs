@@ -0,0 +1,145 @@
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use core::driver::{colorterm_truecolor, Driver};
+use core::width;
+
+/// The supported features as a set of `Feature` bits, `0` until they are detected.
+static FEATURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Set in `FEATURES` once the features have been detected.
+const DETECTED: usize = 1 << 31;
+
+const ALL: [Feature; 8] = [Feature::Unicode,
+                           Feature::LineDrawing,
+                           Feature::Colors256,
+                           Feature::Truecolor,
+                           Feature::Mouse,
+                           Feature::Italic,
+                           Feature::StatusLine,
+                           Feature::StyledUnderline];
+
+// Terminals known to draw styled and colored underlines, by the prefix of `TERM`.
+const STYLED_UNDERLINE_TERMS: &[&str] = &["xterm-kitty", "wezterm", "foot", "contour"];
+
+// The first version of VTE, as given by `VTE_VERSION`, to draw styled and colored underlines.
+const STYLED_UNDERLINE_VTE: u32 = 5102;
+
+/// Optional terminal functionality that programs can adapt to.
+///
+/// The features are detected from the environment and the terminfo database the first time they
+/// are asked about. Built-in widgets check them to pick fallbacks, such as drawing borders in
+/// plain ASCII when the locale cannot display box drawing characters, so that one program renders
+/// acceptably on old and new terminals alike. Detection can be overridden with
+/// `set_supported()`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, Feature, set_supported, supported};
+/// use rustty::ui::{Painter, Widget};
+///
+/// set_supported(Feature::LineDrawing, false);
+/// let mut panel = Widget::new(4, 3);
+/// panel.draw_box();
+/// assert_eq!(panel.get(0, 0).unwrap().ch(), '+');
+///
+/// set_supported(Feature::LineDrawing, true);
+/// assert!(supported(Feature::LineDrawing));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Characters beyond ASCII, such as box drawing characters, can be displayed. Assumed unless
+    /// the locale names another character set, as in `C` or `en_US.ISO-8859-1`.
+    Unicode,
+    /// Box drawing characters can be displayed, either as Unicode or, on terminals without it,
+    /// through the terminal's alternate character set.
+    LineDrawing,
+    /// At least 256 colors.
+    Colors256,
+    /// 24-bit colors, as announced by `COLORTERM=truecolor` or `COLORTERM=24bit`, or by the
+    /// `RGB` or `Tc` flags or a `*-direct` entry in the terminfo database.
+    Truecolor,
+    /// Mouse events are reported.
+    Mouse,
+    /// Text can be displayed in italics.
+    Italic,
+    /// A status line separate from the window, see `Terminal::set_status_line()`.
+    StatusLine,
+    /// Curly, dotted, dashed and double underlines and underline colors, see `UnderlineStyle`.
+    /// Detected from the `Smulx` terminfo extension or a known terminal.
+    StyledUnderline,
+}
+
+impl Feature {
+    fn bit(self) -> usize {
+        1 << self as usize
+    }
+
+    // Returns `true` if the feature is found in the environment or in the terminfo database of
+    // `driver`.
+    fn detect(self, driver: Option<&Driver>) -> bool {
+        match self {
+            Feature::Unicode => width::locale().is_none_or(|locale| is_unicode_locale(&locale)),
+            Feature::LineDrawing => {
+                Feature::Unicode.detect(driver) ||
+                driver.and_then(Driver::acs_map).is_some_and(|map| !map.is_empty())
+            }
+            Feature::Colors256 => {
+                Feature::Truecolor.detect(driver) || driver.is_some_and(|d| d.colors() >= 256)
+            }
+            Feature::Truecolor => driver.map_or_else(colorterm_truecolor, Driver::has_truecolor),
+            Feature::Mouse => driver.is_some_and(Driver::has_mouse),
+            Feature::Italic => driver.is_some_and(Driver::has_italics),
+            Feature::StatusLine => driver.is_some_and(Driver::has_status_line),
+            Feature::StyledUnderline => {
+                driver.is_some_and(Driver::has_styled_underlines) ||
+                env::var("TERM").is_ok_and(|term| {
+                    STYLED_UNDERLINE_TERMS.iter().any(|prefix| term.starts_with(prefix))
+                }) ||
+                env::var("VTE_VERSION").ok()
+                    .and_then(|version| version.parse::<u32>().ok())
+                    .is_some_and(|version| version >= STYLED_UNDERLINE_VTE)
+            }
+        }
+    }
+}
+
+// Returns `true` if the character set of `locale`, such as `en_US.UTF-8`, is UTF-8.
+fn is_unicode_locale(locale: &str) -> bool {
+    let codeset = locale.split('@').next().unwrap_or("").split('.').nth(1).unwrap_or("");
+    codeset.eq_ignore_ascii_case("utf-8") || codeset.eq_ignore_ascii_case("utf8")
+}
+
+// Returns the set of supported features, detecting them if needed.
+fn features() -> usize {
+    let features = FEATURES.load(Ordering::SeqCst);
+    if features & DETECTED != 0 {
+        return features;
+    }
+    let driver = Driver::new().ok();
+    let detected = ALL.iter()
+        .filter(|feature| feature.detect(driver.as_ref()))
+        .fold(DETECTED, |set, feature| set | feature.bit());
+    // Keep the result of a concurrent detection or override.
+    match FEATURES.compare_exchange(0, detected, Ordering::SeqCst, Ordering::SeqCst) {
+        Ok(_) => detected,
+        Err(current) => current,
+    }
+}
+
+/// Returns `true` if the terminal supports `feature`. See `Feature`.
+pub fn supported(feature: Feature) -> bool {
+    features() & feature.bit() != 0
+}
+
+/// Overrides whether the terminal is taken to support `feature`, for example after asking the
+/// user or to test the fallbacks.
+pub fn set_supported(feature: Feature, on: bool) {
+    features();
+    if on {
+        FEATURES.fetch_or(feature.bit(), Ordering::SeqCst);
+    } else {
+        FEATURES.fetch_and(!feature.bit(), Ordering::SeqCst);
+    }
+}
@@ -1,8 +1,638 @@
+use std::cmp::{self, Reverse};
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+use std::str;
+use std::time::{Duration, Instant};
+
+use core::ringbuf::RingBuffer;
+use core::sender::CustomEvent;
+
+/// Prefix of the legacy X10 mouse reports, followed by the button and coordinates in one byte
+/// each, which limits the coordinates to 223.
+const MOUSE_REPORT: &[u8] = b"\x1b[M";
+
+/// Prefix of the SGR (1006) mouse reports, followed by the button and coordinates in decimal,
+/// separated by `;`, then `M` for a press or motion and `m` for a release.
+const SGR_MOUSE_REPORT: &[u8] = b"\x1b[<";
+
+/// Sent by the terminal when it gains focus while focus reporting is enabled.
+const FOCUS_IN: &[u8] = b"\x1b[I";
+
+/// Sent by the terminal when it loses focus while focus reporting is enabled.
+const FOCUS_OUT: &[u8] = b"\x1b[O";
+
+/// Sent by the terminal before pasted text while bracketed paste is enabled.
+const PASTE_START: &[u8] = b"\x1b[200~";
+
+/// Sent by the terminal after pasted text while bracketed paste is enabled.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Starts control sequences (CSI), which end in a byte from `0x40` to `0x7E`.
+const CSI: &[u8] = b"\x1b[";
+
+/// Starts single shift sequences (SS3), which are followed by one byte.
+const SS3: &[u8] = b"\x1bO";
+
+/// Start string sequences (OSC, DCS, APC, PM and SOS), which end in `BEL` or `ST`.
+const STRING_STARTS: &[&[u8]] = &[b"\x1b]", b"\x1bP", b"\x1b_", b"\x1b^", b"\x1bX"];
+
+/// The string terminator.
+const ST: &[u8] = b"\x1b\\";
+
+/// How long an escape sequence may take to arrive completely before its start is decoded as
+/// separate keys, by default.
+const ESC_TIMEOUT_MS: u64 = 50;
+
+// Sequences sent by xterm-compatible terminals for keys that are missing from the terminfo
+// entry, or sent in the other cursor key mode than the one it describes.
+const XTERM_KEYS: &[(&[u8], Key)] = &[(b"\x1b[A", Key::Up),
+                                      (b"\x1b[B", Key::Down),
+                                      (b"\x1b[C", Key::Right),
+                                      (b"\x1b[D", Key::Left),
+                                      (b"\x1bOA", Key::Up),
+                                      (b"\x1bOB", Key::Down),
+                                      (b"\x1bOC", Key::Right),
+                                      (b"\x1bOD", Key::Left),
+                                      (b"\x1b[H", Key::Home),
+                                      (b"\x1b[F", Key::End),
+                                      (b"\x1bOH", Key::Home),
+                                      (b"\x1bOF", Key::End),
+                                      (b"\x1b[1~", Key::Home),
+                                      (b"\x1b[4~", Key::End),
+                                      (b"\x1b[3~", Key::Delete),
+                                      (b"\x1b[5~", Key::PageUp),
+                                      (b"\x1b[6~", Key::PageDown),
+                                      (b"\x1bOP", Key::F(1)),
+                                      (b"\x1bOQ", Key::F(2)),
+                                      (b"\x1bOR", Key::F(3)),
+                                      (b"\x1bOS", Key::F(4)),
+                                      (b"\x1b[15~", Key::F(5)),
+                                      (b"\x1b[17~", Key::F(6)),
+                                      (b"\x1b[18~", Key::F(7)),
+                                      (b"\x1b[19~", Key::F(8)),
+                                      (b"\x1b[20~", Key::F(9)),
+                                      (b"\x1b[21~", Key::F(10)),
+                                      (b"\x1b[23~", Key::F(11)),
+                                      (b"\x1b[24~", Key::F(12))];
+
 /// An input event.
 ///
-/// An `Event` represents a single event from the underying terminal. At the moment no further
-/// processing is done on events and raw escape sequences will also be passed as `Key`s.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// An `Event` represents a single event from the underying terminal. Escape sequences for the
+/// keys described by the terminal's terminfo entry are decoded into a single `Key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
-    Key(char),
+    Key(Key),
+    /// No input has been received for the given idle threshold. Sent once per idle period, see
+    /// `Terminal::set_idle_threshold()`.
+    Idle(Duration),
+    /// A mouse button was pressed or released, or the mouse moved. Mouse tracking is enabled
+    /// while a `Terminal` exists.
+    Mouse(MouseEvent),
+    /// The terminal window gained focus. Focus reporting is enabled while a `Terminal` exists,
+    /// but not all terminals support it.
+    FocusGained,
+    /// The terminal window lost focus.
+    FocusLost,
+    /// Text was pasted into the terminal. Bracketed paste is enabled while a `Terminal` exists,
+    /// so terminals supporting it deliver a paste of any size as a single event rather than as
+    /// one key per character. Invalid UTF-8 is replaced with U+FFFD.
+    Paste(String),
+    /// The terminal window was resized to the given `(cols, rows)`. The `Terminal`'s buffers
+    /// have already been resized when this event is delivered.
+    Resize((usize, usize)),
+    /// An application-defined event, sent with an `EventSender`.
+    Custom(CustomEvent),
+    /// A file descriptor registered with `Terminal::register_fd()` is readable. Carries the
+    /// token it was registered with.
+    Io(usize),
+    /// A timer started with `Terminal::add_timer()` is due. Carries the timer's id.
+    Tick(usize),
+    /// A well-formed escape sequence that rustty does not recognize, such as a key it has no
+    /// `Key` for or a reply to a query the application sent. Carries the whole sequence.
+    Unknown(Vec<u8>),
+    /// Input read from the terminal while raw input is enabled with
+    /// `Terminal::set_raw_input()`, exactly as it was received.
+    Raw(Vec<u8>),
+    /// The interrupt key, normally Ctrl-C, was pressed while signals are enabled with
+    /// `Terminal::set_signals()`.
+    Interrupt,
+    /// The program was continued after being stopped, such as by the suspend key while signals
+    /// are enabled with `Terminal::set_signals()`. The terminal has been taken back, and is
+    /// redrawn entirely on the next buffer swap.
+    Resume,
+}
+
+/// A key press.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::Key;
+///
+/// assert_eq!(Key::from('a'), Key::Char('a'));
+/// assert_eq!(Key::from('\r'), Key::Enter);
+/// assert_eq!(Key::Enter.as_char(), Some('\r'));
+/// assert_eq!(Key::Up.as_char(), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A character, including control characters without a key of their own.
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    /// A function key, numbered from 1.
+    F(u8),
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Backspace,
+    Delete,
+    Enter,
+    Esc,
+}
+
+impl Key {
+    /// Returns the character the terminal sends for the key, if it sends a single one. Widgets
+    /// that handle characters, such as `Keymap`, can be fed keys with this.
+    pub fn as_char(&self) -> Option<char> {
+        match *self {
+            Key::Char(ch) => Some(ch),
+            Key::Backspace => Some('\x7f'),
+            Key::Enter => Some('\r'),
+            Key::Esc => Some('\x1b'),
+            _ => None,
+        }
+    }
+}
+
+impl From<char> for Key {
+    /// Converts the character sent for a key into the key.
+    fn from(ch: char) -> Key {
+        match ch {
+            '\r' | '\n' => Key::Enter,
+            '\x1b' => Key::Esc,
+            '\x7f' | '\x08' => Key::Backspace,
+            ch => Key::Char(ch),
+        }
+    }
+}
+
+/// What a `Terminal` does when more events arrive than its event buffer can hold.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Discards the oldest events.
+    #[default]
+    DropOldest,
+    /// Collapses runs of identical events, such as a held-down key, into a single event, then
+    /// discards the oldest events if that is not enough.
+    Coalesce,
+    /// Discards the newest events and reports an error from the next call to `get_event()`.
+    Error,
+}
+
+/// A mouse button, or a turn of the scroll wheel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    WheelUp,
+    WheelDown,
+    /// No button is held, for `MouseAction::Moved`.
+    None,
+}
+
+/// What the mouse did. The scroll wheel only reports presses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseAction {
+    Press,
+    Release,
+    /// The mouse moved to another cell with the button held down.
+    Drag,
+    /// The mouse moved to another cell with no button held down. Only reported while enabled
+    /// with `Terminal::set_mouse_motion()`.
+    Moved,
+}
+
+/// A mouse button press, release or motion at a cell of the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub button: MouseButton,
+    pub action: MouseAction,
+    /// The column of the cell under the mouse, starting at 0.
+    pub x: usize,
+    /// The row of the cell under the mouse, starting at 0.
+    pub y: usize,
+}
+
+/// Decodes the bytes read from a terminal into events.
+pub struct Parser {
+    keys: Vec<(Vec<u8>, Key)>, // Key sequences, longest first.
+    pressed: Option<MouseButton>, // Button held down, reported again on release.
+    timeout: Duration, // Grace period for incomplete sequences.
+    pending_since: Option<Instant>, // When the incomplete sequence being held back started.
+    in_paste: bool, // Whether the input is pasted text, up to the end of the paste.
+    paste_scanned: usize, // Length of the pasted text held back known not to end the paste.
+}
+
+impl Parser {
+    /// Constructs a new `Parser` recognizing the given key sequences, in addition to the ones
+    /// sent by xterm-compatible terminals.
+    pub fn new(keys: Vec<(Vec<u8>, Key)>) -> Parser {
+        let mut keys: Vec<_> = keys.into_iter().filter(|k| !k.0.is_empty()).collect();
+        keys.extend(XTERM_KEYS.iter().map(|&(seq, key)| (seq.to_vec(), key)));
+        keys.sort_by_key(|k| Reverse(k.0.len()));
+        Parser {
+            keys,
+            pressed: None,
+            timeout: Duration::from_millis(ESC_TIMEOUT_MS),
+            pending_since: None,
+            in_paste: false,
+            paste_scanned: 0,
+        }
+    }
+
+    /// Returns how long an incomplete escape sequence is held back waiting for the rest of it.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Sets how long an incomplete escape sequence is held back waiting for the rest of it.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the time left before held back input is decoded anyway by `expire()`, or `None`
+    /// if no input is held back.
+    pub fn pending_in(&self) -> Option<Duration> {
+        self.pending_since.map(|since| self.timeout.saturating_sub(since.elapsed()))
+    }
+
+    /// Decodes the keys, mouse reports, focus changes and pastes at the start of `input` into
+    /// events, leaving input at the end that may be the start of a longer sequence, such as a
+    /// lone Esc or an unfinished paste, in place until more input arrives or `expire()` gives up
+    /// on it. Returns the number of events added.
+    pub fn parse(&mut self, input: &mut RingBuffer, events: &mut VecDeque<Event>) -> usize {
+        let before = events.len();
+        let used = self.decode(input.make_contiguous(), events, false);
+        input.consume(used);
+        if input.is_empty() {
+            self.pending_since = None;
+        } else if self.pending_since.is_none() || used > 0 {
+            // The input held back is new.
+            self.pending_since = Some(Instant::now());
+        }
+        events.len() - before
+    }
+
+    /// Decodes the input held back by `parse()` as it is once it has waited for longer than the
+    /// timeout, so that the start of a sequence becomes separate keys. Returns the number of
+    /// events added.
+    pub fn expire(&mut self, input: &mut RingBuffer, events: &mut VecDeque<Event>) -> usize {
+        match self.pending_in() {
+            Some(left) if left == Duration::from_secs(0) => {
+                self.pending_since = None;
+                let before = events.len();
+                let used = self.decode(input.make_contiguous(), events, true);
+                input.consume(used);
+                events.len() - before
+            }
+            _ => 0,
+        }
+    }
+
+    // Decodes `input` into events, returning the number of bytes used. Unless `force` is set,
+    // decoding stops at an incomplete sequence, character or paste at the end.
+    fn decode(&mut self, input: &[u8], events: &mut VecDeque<Event>, force: bool) -> usize {
+        let mut pos = 0;
+        while pos < input.len() {
+            let rest = &input[pos..];
+            if self.in_paste {
+                pos += match self.paste_len(rest) {
+                    Some(len) => {
+                        let text = String::from_utf8_lossy(&rest[..len]).into_owned();
+                        events.push_back(Event::Paste(text));
+                        self.in_paste = false;
+                        len + PASTE_END.len()
+                    }
+                    // Deliver what was pasted so far, and the rest as another paste.
+                    None if force => {
+                        events.push_back(Event::Paste(String::from_utf8_lossy(rest).into_owned()));
+                        rest.len()
+                    }
+                    None => break,
+                };
+                self.paste_scanned = 0;
+                continue;
+            }
+            // Mouse reports are the prefix followed by the button and coordinate bytes.
+            let mouse_len = MOUSE_REPORT.len() + 3;
+            if !force && self.is_incomplete(rest) {
+                break;
+            }
+            if rest.starts_with(PASTE_START) {
+                self.in_paste = true;
+                pos += PASTE_START.len();
+            } else if rest.starts_with(MOUSE_REPORT) && rest.len() >= mouse_len {
+                let report = &rest[MOUSE_REPORT.len()..mouse_len];
+                let (cb, x, y) = (report[0].wrapping_sub(32), report[1], report[2]);
+                let (x, y) = (x.saturating_sub(33) as usize, y.saturating_sub(33) as usize);
+                if let Some(evt) = self.decode_mouse(cb, x, y, false) {
+                    events.push_back(Event::Mouse(evt));
+                }
+                pos += mouse_len;
+            } else if let Some((report, len)) = sgr_mouse(rest) {
+                let (cb, x, y, release) = report;
+                if let Some(evt) = self.decode_mouse(cb, x, y, release) {
+                    events.push_back(Event::Mouse(evt));
+                }
+                pos += len;
+            } else if rest.starts_with(FOCUS_IN) {
+                events.push_back(Event::FocusGained);
+                pos += FOCUS_IN.len();
+            } else if rest.starts_with(FOCUS_OUT) {
+                events.push_back(Event::FocusLost);
+                pos += FOCUS_OUT.len();
+            } else if let Some(&(ref seq, key)) = self.key_at(rest) {
+                events.push_back(Event::Key(key));
+                pos += seq.len();
+            } else if let Escape::Complete(len) = escape(rest) {
+                events.push_back(Event::Unknown(rest[..len].to_vec()));
+                pos += len;
+            } else {
+                match decode_char(rest) {
+                    Some((ch, len)) => {
+                        events.push_back(Event::Key(Key::from(ch)));
+                        pos += len;
+                    }
+                    // An incomplete character at the end of the input.
+                    None if !force => break,
+                    None => {
+                        events.push_back(Event::Key(Key::Char('\u{FFFD}')));
+                        pos = input.len();
+                    }
+                }
+            }
+        }
+        pos
+    }
+
+    // Returns the length of the pasted text at the start of `input`, which is followed by the
+    // end of the paste, or `None` if it has not all arrived yet. Only the text that arrived since
+    // the last call is searched, so that a large paste read in many parts is searched once.
+    fn paste_len(&mut self, input: &[u8]) -> Option<usize> {
+        let from = cmp::min(self.paste_scanned, input.len());
+        let found = input[from..].windows(PASTE_END.len()).position(|w| w == PASTE_END);
+        if found.is_none() {
+            // The end may have arrived in part, so its first bytes are searched again.
+            self.paste_scanned = input.len().saturating_sub(PASTE_END.len() - 1);
+        }
+        found.map(|at| from + at)
+    }
+
+    // Returns `true` if `input` is the start of a sequence, but not all of it.
+    fn is_incomplete(&self, input: &[u8]) -> bool {
+        let partial = |seq: &[u8]| input.len() < seq.len() && seq.starts_with(input);
+        (input.starts_with(MOUSE_REPORT) && input.len() < MOUSE_REPORT.len() + 3) ||
+        partial(MOUSE_REPORT) || partial(FOCUS_IN) || partial(FOCUS_OUT) ||
+        self.keys.iter().any(|k| partial(&k.0)) || escape(input) == Escape::Partial
+    }
+
+    /// Decodes `seq` as `key`, replacing what it was decoded as before, which is returned.
+    pub fn add_key(&mut self, seq: Vec<u8>, key: Key) -> Option<Key> {
+        let old = self.remove_key(&seq);
+        if !seq.is_empty() {
+            // Keep the longest sequences first.
+            let at = self.keys.iter().position(|k| k.0.len() <= seq.len());
+            let at = at.unwrap_or(self.keys.len());
+            self.keys.insert(at, (seq, key));
+        }
+        old
+    }
+
+    /// Stops decoding `seq` as a key, returning the key it was decoded as.
+    pub fn remove_key(&mut self, seq: &[u8]) -> Option<Key> {
+        let key = self.key_at(seq).filter(|k| k.0 == seq).map(|k| k.1);
+        self.keys.retain(|k| k.0 != seq);
+        key
+    }
+
+    /// Adds the key sequences listed in `table`, in the format read by
+    /// `Terminal::load_key_sequences()`, returning how many there are. Nothing is added if any
+    /// line is invalid.
+    pub fn add_keys(&mut self, table: &str) -> Result<usize, Error> {
+        let mut keys = Vec::new();
+        for (n, line) in table.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let seq = fields.next().and_then(unescape);
+            let key = fields.next().and_then(key_named);
+            match (seq, key, fields.next()) {
+                (Some(seq), Some(key), None) => keys.push((seq, key)),
+                _ => {
+                    let msg = format!("invalid key sequence on line {}: '{}'", n + 1, line);
+                    return Err(Error::new(ErrorKind::InvalidData, msg));
+                }
+            }
+        }
+        let n = keys.len();
+        for (seq, key) in keys {
+            self.add_key(seq, key);
+        }
+        Ok(n)
+    }
+
+    // Returns the longest key sequence `input` starts with.
+    fn key_at(&self, input: &[u8]) -> Option<&(Vec<u8>, Key)> {
+        self.keys.iter().find(|k| input.starts_with(&k.0))
+    }
+
+    // Decodes the button number `cb` of an xterm mouse report at the cell `(x, y)`. `release` is
+    // set for SGR reports of a release, which say which button was released.
+    fn decode_mouse(&mut self, cb: u8, x: usize, y: usize, release: bool) -> Option<MouseEvent> {
+        let (button, action) = match (cb & 64 != 0, cb & 3) {
+            (true, _) if release => return None,
+            (true, 0) => (MouseButton::WheelUp, MouseAction::Press),
+            (true, 1) => (MouseButton::WheelDown, MouseAction::Press),
+            (true, _) => return None,
+            // Motion reports have bit 5 set, and no button bits if no button is held.
+            (false, 3) if cb & 32 != 0 => (MouseButton::None, MouseAction::Moved),
+            (false, b) if cb & 32 != 0 => (button_of(b), MouseAction::Drag),
+            (false, b) if release => {
+                self.pressed = None;
+                (button_of(b), MouseAction::Release)
+            }
+            // X10 releases don't say which button was released.
+            (false, 3) => (self.pressed.take()?, MouseAction::Release),
+            (false, b) => {
+                let button = button_of(b);
+                self.pressed = Some(button);
+                (button, MouseAction::Press)
+            }
+        };
+        Some(MouseEvent {
+            button,
+            action,
+            x,
+            y,
+        })
+    }
+}
+
+// Decodes the SGR mouse report at the start of `input` into the button number, the coordinates
+// starting at 0 and whether it is a release, returned with the length of the report.
+fn sgr_mouse(input: &[u8]) -> Option<((u8, usize, usize, bool), usize)> {
+    if !input.starts_with(SGR_MOUSE_REPORT) {
+        return None;
+    }
+    let len = match escape(input) {
+        Escape::Complete(len) => len,
+        _ => return None,
+    };
+    let release = match input[len - 1] {
+        b'M' => false,
+        b'm' => true,
+        _ => return None,
+    };
+    let params = str::from_utf8(&input[SGR_MOUSE_REPORT.len()..len - 1]).ok()?;
+    let mut params = params.split(';').map(|param| param.parse::<usize>().ok());
+    match (params.next()?, params.next()?, params.next()?, params.next()) {
+        (Some(cb), Some(x), Some(y), None) => {
+            Some(((cb as u8, x.saturating_sub(1), y.saturating_sub(1), release), len))
+        }
+        _ => None,
+    }
+}
+
+// Decodes the escapes of a sequence in a key table, returning `None` if it is malformed or empty.
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut seq = Vec::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buf = [0; 4];
+            seq.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            'e' => seq.push(0x1b),
+            '\\' => seq.push(b'\\'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 {
+                    return None;
+                }
+                seq.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    if seq.is_empty() { None } else { Some(seq) }
+}
+
+// Returns the key named `name` in a key table.
+fn key_named(name: &str) -> Option<Key> {
+    let key = match name {
+        "Up" => Key::Up,
+        "Down" => Key::Down,
+        "Left" => Key::Left,
+        "Right" => Key::Right,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+        "Backspace" => Key::Backspace,
+        "Delete" => Key::Delete,
+        "Enter" => Key::Enter,
+        "Esc" => Key::Esc,
+        _ if name.starts_with('F') && name.len() > 1 => {
+            match name[1..].parse() {
+                Ok(n) if n > 0 => Key::F(n),
+                _ => return None,
+            }
+        }
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Key::Char(ch),
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+// Returns the button numbered `b` in mouse reports.
+fn button_of(b: u8) -> MouseButton {
+    match b {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        _ => MouseButton::Right,
+    }
+}
+
+// How the input starts, as far as escape sequences are concerned.
+#[derive(PartialEq, Eq)]
+enum Escape {
+    // Not with an escape sequence.
+    None,
+    // With the start of a control or single shift sequence.
+    Partial,
+    // With a complete escape sequence of the given length.
+    Complete(usize),
+}
+
+// Finds the control, single shift or string sequence at the start of `input`.
+//
+// String sequences are only recognized complete: their introducers are also what Alt sends with
+// some keys, so holding them back would swallow the input typed after such a key.
+fn escape(input: &[u8]) -> Escape {
+    if input.starts_with(CSI) {
+        let body = &input[CSI.len()..];
+        // Parameter and intermediate bytes, then the final byte.
+        match body.iter().position(|&b| !(0x20..0x40).contains(&b)) {
+            Some(i) if (0x40..0x7f).contains(&body[i]) => Escape::Complete(CSI.len() + i + 1),
+            Some(_) => Escape::None,
+            None => Escape::Partial,
+        }
+    } else if input.starts_with(SS3) {
+        match input.get(SS3.len()) {
+            Some(&b) if (0x20..0x7f).contains(&b) => Escape::Complete(SS3.len() + 1),
+            Some(_) => Escape::None,
+            None => Escape::Partial,
+        }
+    } else if STRING_STARTS.iter().any(|start| input.starts_with(start)) {
+        let body = &input[2..];
+        let bel = body.iter().position(|&b| b == 0x07).map(|i| i + 1);
+        let st = body.windows(2).position(|w| w == ST).map(|i| i + 2);
+        match (bel, st) {
+            (Some(a), Some(b)) => Escape::Complete(2 + a.min(b)),
+            (Some(end), None) | (None, Some(end)) => Escape::Complete(2 + end),
+            (None, None) => Escape::None,
+        }
+    } else {
+        Escape::None
+    }
+}
+
+// Decodes the UTF-8 character at the start of `input`, returning it with its length in bytes, or
+// `None` if it is incomplete. Invalid bytes are decoded as U+FFFD.
+fn decode_char(input: &[u8]) -> Option<(char, usize)> {
+    let head = &input[..input.len().min(4)];
+    let valid = match str::from_utf8(head) {
+        Ok(text) => text,
+        Err(err) => {
+            if err.valid_up_to() == 0 {
+                return err.error_len().map(|len| ('\u{FFFD}', len));
+            }
+            str::from_utf8(&head[..err.valid_up_to()]).unwrap()
+        }
+    };
+    valid.chars().next().map(|ch| (ch, ch.len_utf8()))
 }
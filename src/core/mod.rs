@@ -1,6 +1,21 @@
+#[macro_use]
+mod trace;
+
 pub mod terminal;
 pub mod cellbuffer;
 pub mod driver;
 pub mod position;
 pub mod input;
 pub mod termctl;
+pub mod palette;
+pub mod width;
+pub mod sender;
+pub mod source;
+pub mod backdrop;
+pub mod features;
+pub mod capabilities;
+pub mod diagnostic;
+pub mod panic_screen;
+pub mod watchdog;
+pub mod wrap;
+pub mod ringbuf;
@@ -0,0 +1,210 @@
+use core::cellbuffer::{Cell, CellAccessor, CellBuffer, Color};
+
+/// A table of logical colors.
+///
+/// Cells colored with `Color::Slot(n)` are drawn in whatever color slot `n` of the terminal's
+/// palette holds at the time. Changing a slot recolors every cell using it on the next buffer
+/// swap without touching the cells themselves, which makes palette rotation effects such as
+/// flowing water or pulsing lights cheap.
+///
+/// Slots hold concrete colors; a slot set to another `Color::Slot(..)` resolves to
+/// `Color::Default`, as do slots that were never set.
+///
+/// The palette also remaps the 16 base colors, the eight named colors and `Color::Byte(0)` to
+/// `Color::Byte(15)`, for every cell drawn. This lets an application switch to a color scheme
+/// that is easier to tell apart for color-blind users without touching the cells it creates.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{Color, Palette};
+///
+/// let mut palette = Palette::new();
+/// palette.set(0, Color::Blue);
+/// palette.set(1, Color::Cyan);
+/// palette.set(2, Color::White);
+/// assert_eq!(palette.resolve(Color::Slot(1)), Color::Cyan);
+/// assert_eq!(palette.resolve(Color::Red), Color::Red);
+///
+/// // Shift every color in slots 0 to 2 up by one slot.
+/// palette.cycle(0, 2);
+/// assert_eq!(palette.get(0), Color::White);
+/// assert_eq!(palette.get(1), Color::Blue);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Palette {
+    slots: Vec<Color>,
+    base: [Option<Color>; 16],
+}
+
+/// A built-in mapping of the 16 base colors.
+///
+/// The color-blind friendly schemes replace the base colors with the Okabe-Ito colors, as
+/// approximated by the 256-color palette, so they need a terminal with 256 colors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+    /// The terminal's own base colors.
+    #[default]
+    Standard,
+    /// Colors distinguishable with deuteranopia, the most common red-green deficiency.
+    Deuteranopia,
+    /// Colors distinguishable with protanopia, where reds also appear darker.
+    Protanopia,
+}
+
+impl ColorScheme {
+    // Returns the 256-color palette indices the base colors are remapped to.
+    fn colors(self) -> Option<[u8; 16]> {
+        match self {
+            ColorScheme::Standard => None,
+            ColorScheme::Deuteranopia => {
+                Some([16, 166, 36, 221, 25, 175, 74, 252, 244, 214, 43, 228, 32, 218, 117, 231])
+            }
+            ColorScheme::Protanopia => {
+                Some([16, 214, 36, 221, 25, 175, 74, 252, 244, 220, 43, 228, 32, 218, 117, 231])
+            }
+        }
+    }
+}
+
+impl Palette {
+    /// Constructs a new `Palette` with all 256 slots set to `Color::Default`.
+    pub fn new() -> Palette {
+        Palette {
+            slots: vec![Color::Default; 256],
+            base: [None; 16],
+        }
+    }
+
+    /// Returns the color in a slot.
+    pub fn get(&self, slot: u8) -> Color {
+        self.slots[slot as usize]
+    }
+
+    /// Sets the color in a slot.
+    pub fn set(&mut self, slot: u8, color: Color) {
+        self.slots[slot as usize] = color;
+    }
+
+    /// Remaps base color `index`, from 0 to 15, to `color` wherever it is drawn. Passing `None`
+    /// restores the base color.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 15 or `color` is a `Color::Slot(..)`.
+    pub fn set_base(&mut self, index: u8, color: Option<Color>) {
+        assert!(!matches!(color, Some(Color::Slot(_))), "base color remapped to a slot");
+        self.base[index as usize] = color;
+    }
+
+    /// Returns the color base color `index` is remapped to, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 15.
+    pub fn base(&self, index: u8) -> Option<Color> {
+        self.base[index as usize]
+    }
+
+    /// Remaps all 16 base colors according to a built-in scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Color, ColorScheme, Palette};
+    ///
+    /// let mut palette = Palette::new();
+    /// palette.set_scheme(ColorScheme::Deuteranopia);
+    /// assert_eq!(palette.resolve(Color::Red), Color::Byte(166));
+    /// assert_eq!(palette.resolve(Color::Byte(9)), Color::Byte(214));
+    ///
+    /// palette.set_scheme(ColorScheme::Standard);
+    /// assert_eq!(palette.resolve(Color::Red), Color::Red);
+    /// ```
+    pub fn set_scheme(&mut self, scheme: ColorScheme) {
+        self.base = match scheme.colors() {
+            Some(colors) => {
+                let mut base = [None; 16];
+                for (b, &c) in base.iter_mut().zip(colors.iter()) {
+                    *b = Some(Color::Byte(c));
+                }
+                base
+            }
+            None => [None; 16],
+        };
+    }
+
+    /// Returns the concrete color `color` stands for: the contents of its slot if it is a
+    /// `Color::Slot(..)`, otherwise `color` itself, after remapping base colors.
+    pub fn resolve(&self, color: Color) -> Color {
+        let color = match color {
+            Color::Slot(n) => {
+                match self.get(n) {
+                    Color::Slot(_) => Color::Default,
+                    c => c,
+                }
+            }
+            c => c,
+        };
+        let index = match color {
+            Color::Default | Color::Slot(_) | Color::Rgb(..) => return color,
+            Color::Byte(b) if b >= 16 => return color,
+            c => c.as_byte(),
+        };
+        self.base[index as usize].unwrap_or(color)
+    }
+
+    /// Rotates the colors in slots `first` through `last` up by one slot, with the color in
+    /// `last` moving to `first`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `first` is greater than `last`.
+    pub fn cycle(&mut self, first: u8, last: u8) {
+        assert!(first <= last, "invalid slot range");
+        self.slots[first as usize..last as usize + 1].rotate_right(1);
+    }
+
+    /// Returns a copy of `cells` with every color resolved, as it looks now. Without `truecolor`,
+    /// 24-bit colors are replaced with the closest 8-bit ones, including those held in slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{CellAccessor, Color, Palette};
+    /// use rustty::ui::Widget;
+    ///
+    /// let mut palette = Palette::new();
+    /// palette.set(0, Color::Rgb(255, 0, 0));
+    /// let mut w = Widget::new(1, 1);
+    /// w.get_mut(0, 0).unwrap().set_fg(Color::Slot(0));
+    ///
+    /// let copy = palette.resolve_cells(&w, false);
+    /// assert_eq!(copy.get(0, 0).unwrap().fg(), Color::Byte(196));
+    /// let copy = palette.resolve_cells(&w, true);
+    /// assert_eq!(copy.get(0, 0).unwrap().fg(), Color::Rgb(255, 0, 0));
+    /// ```
+    pub fn resolve_cells(&self, cells: &dyn CellAccessor, truecolor: bool) -> CellBuffer {
+        let (cols, rows) = cells.size();
+        let resolve = |color| {
+            match self.resolve(color) {
+                c @ Color::Rgb(..) if !truecolor => Color::Byte(c.as_byte()),
+                c => c,
+            }
+        };
+        let mut copy = CellBuffer::new(cols, rows, Cell::default());
+        for (dst, src) in copy.cellvec_mut().iter_mut().zip(cells.cellvec()) {
+            *dst = *src;
+            dst.set_fg(resolve(src.fg()))
+                .set_bg(resolve(src.bg()))
+                .set_underline_color(resolve(src.underline_color()));
+        }
+        copy
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::new()
+    }
+}
@@ -0,0 +1,86 @@
+use std::cmp;
+
+use core::cellbuffer::{Attr, Cell, CellAccessor, Color};
+use core::width;
+
+const TITLE: &str = " application panicked ";
+const BACKTRACE_HEADER: &str = "stack backtrace:";
+const HINT: &str = "Up/Down, PageUp/PageDown to scroll, any other key to exit";
+const HINT_NO_BACKTRACE: &str = "Press any key to exit";
+
+// The report of a panic, drawn by a `Terminal` dropped while panicking, from what was written to
+// standard error: the panic message and, with `RUST_BACKTRACE` set, the backtrace that follows it.
+pub struct PanicScreen {
+    message: Vec<String>,
+    backtrace: Vec<String>,
+}
+
+impl PanicScreen {
+    // Splits `report` into the message and the backtrace.
+    pub fn new(report: &str) -> PanicScreen {
+        let mut lines = report.lines().map(|line| line.replace('\t', "    "));
+        let message = lines.by_ref().take_while(|line| line != BACKTRACE_HEADER).collect();
+        PanicScreen {
+            message,
+            backtrace: lines.collect(),
+        }
+    }
+
+    // Returns the number of rows the backtrace can be scrolled by on a screen of `rows` rows.
+    pub fn max_scroll(&self, rows: usize) -> usize {
+        self.backtrace.len().saturating_sub(self.backtrace_rows(rows))
+    }
+
+    // Draws the report over the whole of `cells`, with the backtrace scrolled by `scroll` rows.
+    pub fn draw(&self, cells: &mut dyn CellAccessor, scroll: usize) {
+        let (cols, rows) = cells.size();
+        if rows == 0 {
+            return;
+        }
+        let blank = Cell::new(' ', Color::White, Color::Red, Attr::Default);
+        cells.clear(blank);
+        let title_x = cols.saturating_sub(width::display_width(TITLE)) / 2;
+        put(cells, title_x, 0, TITLE, blank.fg(), Attr::Bold | Attr::Reverse);
+
+        // The message is cut short to leave room for the hint and some of the backtrace.
+        let mut y = 2;
+        let message_rows = if self.backtrace.is_empty() { rows } else { rows / 2 };
+        for line in self.message.iter().take(message_rows.saturating_sub(y + 1)) {
+            put(cells, 1, y, line, Color::White, Attr::Bold);
+            y += 1;
+        }
+        if !self.backtrace.is_empty() {
+            y += 1;
+            put(cells, 1, y, BACKTRACE_HEADER, Color::White, Attr::Underline);
+            let shown = self.backtrace_rows(rows);
+            let start = cmp::min(scroll, self.max_scroll(rows));
+            for (i, line) in self.backtrace.iter().skip(start).take(shown).enumerate() {
+                put(cells, 1, rows - 1 - shown + i, line, Color::White, Attr::Default);
+            }
+        }
+
+        let hint = if self.backtrace.is_empty() { HINT_NO_BACKTRACE } else { HINT };
+        put(cells, 1, rows.saturating_sub(1), hint, Color::Yellow, Attr::Default);
+    }
+
+    // Returns the number of rows left for the backtrace on a screen of `rows` rows.
+    fn backtrace_rows(&self, rows: usize) -> usize {
+        let message_rows = cmp::min(self.message.len(), (rows / 2).saturating_sub(3));
+        // The title and the hint, the message and the backtrace header with blank rows around.
+        rows.saturating_sub(message_rows + 6)
+    }
+}
+
+// Writes `text` at (`x`, `y`) in `fg` with `attrs`, cut at the edge of `cells`.
+fn put(cells: &mut dyn CellAccessor, mut x: usize, y: usize, text: &str, fg: Color, attrs: Attr) {
+    for grapheme in width::graphemes(text) {
+        let w = width::grapheme_width(grapheme);
+        if x + w > cells.size().0 {
+            break;
+        }
+        if let Some(cell) = cells.get_mut(x, y) {
+            cell.set_fg(fg).set_attrs(attrs);
+        }
+        x += cells.put_char(x, y, grapheme.chars().next().unwrap());
+    }
+}
@@ -1,17 +1,211 @@
+use std::cmp;
+
 /// A `(x, y)` position on screen.
 pub type Pos = (usize, usize);
 /// A `(cols, rows)` size.
 pub type Size = (usize, usize);
 
+/// Component-wise arithmetic on `Pos` and `Size` pairs.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::Coords;
+///
+/// assert_eq!((2, 3).plus((1, 1)), (3, 4));
+/// assert_eq!((2, 3).minus((3, 1)), (0, 2));
+/// assert_eq!((2, 3).min_each((1, 5)), (1, 3));
+/// ```
+pub trait Coords {
+    /// Adds the components of `other`.
+    fn plus(self, other: Self) -> Self;
+    /// Subtracts the components of `other`, stopping at zero.
+    fn minus(self, other: Self) -> Self;
+    /// Returns the smaller of each pair of components.
+    fn min_each(self, other: Self) -> Self;
+    /// Returns the larger of each pair of components.
+    fn max_each(self, other: Self) -> Self;
+}
+
+impl Coords for (usize, usize) {
+    fn plus(self, other: (usize, usize)) -> (usize, usize) {
+        (self.0 + other.0, self.1 + other.1)
+    }
+
+    fn minus(self, other: (usize, usize)) -> (usize, usize) {
+        (self.0.saturating_sub(other.0), self.1.saturating_sub(other.1))
+    }
+
+    fn min_each(self, other: (usize, usize)) -> (usize, usize) {
+        (cmp::min(self.0, other.0), cmp::min(self.1, other.1))
+    }
+
+    fn max_each(self, other: (usize, usize)) -> (usize, usize) {
+        (cmp::max(self.0, other.0), cmp::max(self.1, other.1))
+    }
+}
+
+/// A rectangle of cells, given by its top-left corner and its size.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::Rect;
+///
+/// let screen = Rect::new((0, 0), (80, 24));
+/// let dialog = Rect::new((70, 20), (20, 10));
+/// assert_eq!(screen.intersection(&dialog), Some(Rect::new((70, 20), (10, 4))));
+/// assert!(screen.contains((79, 23)));
+/// assert!(!screen.contains((80, 0)));
+///
+/// let (sidebar, main) = screen.split_cols(20);
+/// assert_eq!(sidebar, Rect::new((0, 0), (20, 24)));
+/// assert_eq!(main.inset(1, 1), Rect::new((21, 1), (58, 22)));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub origin: Pos,
+    pub size: Size,
+}
+
+impl Rect {
+    /// Constructs a new `Rect` with the given top-left corner and size.
+    pub fn new(origin: Pos, size: Size) -> Rect {
+        Rect { origin, size }
+    }
+
+    /// Returns the column just past the right edge.
+    pub fn right(&self) -> usize {
+        self.origin.0 + self.size.0
+    }
+
+    /// Returns the row just past the bottom edge.
+    pub fn bottom(&self) -> usize {
+        self.origin.1 + self.size.1
+    }
+
+    /// Returns `true` if the rectangle covers no cells.
+    pub fn is_empty(&self) -> bool {
+        self.size.0 == 0 || self.size.1 == 0
+    }
+
+    /// Returns the number of cells the rectangle covers.
+    pub fn area(&self) -> usize {
+        self.size.0 * self.size.1
+    }
+
+    /// Returns `true` if the cell at `pos` is inside the rectangle.
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.0 >= self.origin.0 && pos.1 >= self.origin.1 && pos.0 < self.right() &&
+        pos.1 < self.bottom()
+    }
+
+    /// Returns `true` if `other` lies entirely inside the rectangle. Empty rectangles lie inside
+    /// every rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty() ||
+        (other.origin.0 >= self.origin.0 && other.origin.1 >= self.origin.1 &&
+         other.right() <= self.right() && other.bottom() <= self.bottom())
+    }
+
+    /// Returns the cells covered by both rectangles, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let origin = self.origin.max_each(other.origin);
+        let end = (self.right(), self.bottom()).min_each((other.right(), other.bottom()));
+        if origin.0 < end.0 && origin.1 < end.1 {
+            Some(Rect::new(origin, end.minus(origin)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest rectangle covering both rectangles. Empty rectangles are ignored.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let origin = self.origin.min_each(other.origin);
+        let end = (self.right(), self.bottom()).max_each((other.right(), other.bottom()));
+        Rect::new(origin, end.minus(origin))
+    }
+
+    /// Shrinks the rectangle by `cols` columns on the left and right and `rows` rows at the top
+    /// and bottom, down to nothing.
+    pub fn inset(&self, cols: usize, rows: usize) -> Rect {
+        let size = self.size.minus((2 * cols, 2 * rows));
+        let origin = self.origin.plus((cmp::min(cols, self.size.0), cmp::min(rows, self.size.1)));
+        Rect::new(origin, size)
+    }
+
+    /// Splits the rectangle into the first `cols` columns and the rest.
+    pub fn split_cols(&self, cols: usize) -> (Rect, Rect) {
+        let cols = cmp::min(cols, self.size.0);
+        (Rect::new(self.origin, (cols, self.size.1)),
+         Rect::new((self.origin.0 + cols, self.origin.1), (self.size.0 - cols, self.size.1)))
+    }
+
+    /// Splits the rectangle into the first `rows` rows and the rest.
+    pub fn split_rows(&self, rows: usize) -> (Rect, Rect) {
+        let rows = cmp::min(rows, self.size.1);
+        (Rect::new(self.origin, (self.size.0, rows)),
+         Rect::new((self.origin.0, self.origin.1 + rows), (self.size.0, self.size.1 - rows)))
+    }
+}
+
+impl From<(Pos, Size)> for Rect {
+    fn from((origin, size): (Pos, Size)) -> Rect {
+        Rect::new(origin, size)
+    }
+}
+
 pub trait HasSize {
     fn size(&self) -> Size;
 }
 
+impl<T: HasSize + ?Sized> HasSize for Box<T> {
+    fn size(&self) -> Size {
+        (**self).size()
+    }
+}
+
+impl<T: HasSize + ?Sized> HasSize for &T {
+    fn size(&self) -> Size {
+        (**self).size()
+    }
+}
+
+/// A plain `(cols, rows)` size, so that sizes can be passed wherever something with a size is
+/// expected.
+impl HasSize for (usize, usize) {
+    fn size(&self) -> Size {
+        *self
+    }
+}
+
 pub trait HasPosition {
     fn origin(&self) -> Pos;
     fn set_origin(&mut self, new_origin: Pos);
 }
 
+impl HasSize for Rect {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl HasPosition for Rect {
+    fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.origin = new_origin;
+    }
+}
+
 /// A cursor position.
 pub struct Cursor {
     pos: Option<Pos>,
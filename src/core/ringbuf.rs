@@ -0,0 +1,77 @@
+use std::io::Error;
+
+/// A byte ring buffer holding input read from the terminal until it is decoded.
+///
+/// Bytes are read in after the ones held, and decoded bytes are consumed from the front without
+/// moving the rest, so the buffer is reused across reads however the input is split up. It only
+/// grows when more input arrives at once than it can hold.
+pub struct RingBuffer {
+    buf: Vec<u8>, // Storage, wrapping around at its end.
+    head: usize, // Index of the first byte held.
+    len: usize, // Number of bytes held.
+}
+
+impl RingBuffer {
+    /// Constructs a new, empty `RingBuffer` holding up to `capacity` bytes before it grows.
+    pub fn with_capacity(capacity: usize) -> RingBuffer {
+        RingBuffer {
+            buf: vec![0; capacity.max(1)],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns whether no bytes are held.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads bytes in after the ones held with `read`, which is given the free space following
+    /// them and returns how much of it it filled. The buffer grows first if it is full. Returns
+    /// the number of bytes read.
+    pub fn fill<F>(&mut self, read: F) -> Result<usize, Error>
+        where F: FnOnce(&mut [u8]) -> Result<usize, Error>
+    {
+        if self.len == self.buf.len() {
+            self.make_contiguous();
+            let cap = self.buf.len();
+            self.buf.resize(cap * 2, 0);
+        }
+        let cap = self.buf.len();
+        let tail = (self.head + self.len) % cap;
+        // The free space runs up to the end of the storage or, once wrapped, to the head.
+        let end = if tail < self.head { self.head } else { cap };
+        let n = try!(read(&mut self.buf[tail..end]));
+        self.len += n;
+        Ok(n)
+    }
+
+    /// Returns the bytes held as one slice, first moving them to the start of the storage if
+    /// they wrap around its end.
+    pub fn make_contiguous(&mut self) -> &[u8] {
+        if self.head + self.len > self.buf.len() {
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+        }
+        &self.buf[self.head..self.head + self.len]
+    }
+
+    /// Discards the first `n` bytes held.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` bytes are held.
+    pub fn consume(&mut self, n: usize) {
+        assert!(n <= self.len, "consumed more bytes than held");
+        self.len -= n;
+        // Start over at the beginning when empty, so the next read is not split by the wrap.
+        self.head = if self.len == 0 { 0 } else { (self.head + n) % self.buf.len() };
+    }
+
+    /// Removes and returns all bytes held.
+    pub fn take(&mut self) -> Vec<u8> {
+        let bytes = self.make_contiguous().to_vec();
+        self.consume(bytes.len());
+        bytes
+    }
+}
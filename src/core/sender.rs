@@ -0,0 +1,189 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use libc;
+
+use core::input::Event;
+
+/// An application-defined event, carrying a value of any type.
+///
+/// Custom events are compared by identity: a custom event only equals its own clones.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::CustomEvent;
+///
+/// let evt = CustomEvent::new(42u32);
+/// assert_eq!(evt.downcast_ref::<u32>(), Some(&42));
+/// assert!(evt.downcast_ref::<String>().is_none());
+/// assert_eq!(evt, evt.clone());
+/// ```
+#[derive(Clone)]
+pub struct CustomEvent {
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomEvent {
+    /// Constructs a new `CustomEvent` carrying `value`.
+    pub fn new<T: Any + Send + Sync>(value: T) -> CustomEvent {
+        CustomEvent { value: Arc::new(value) }
+    }
+
+    /// Returns `true` if the event carries a value of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+
+    /// Returns the value the event carries if it is of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
+
+impl PartialEq for CustomEvent {
+    fn eq(&self, other: &CustomEvent) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for CustomEvent {}
+
+impl fmt::Debug for CustomEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CustomEvent(..)")
+    }
+}
+
+/// A handle for sending events to a `Terminal` from other threads.
+///
+/// Sent events are returned by `Terminal::get_event()` after the events already queued, waking
+/// it up if it is waiting for input. Handles are obtained with `Terminal::event_sender()` and can
+/// be cloned freely.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::thread;
+/// use std::time::Duration;
+/// use rustty::{CustomEvent, Event, Terminal};
+///
+/// let mut term = Terminal::new().unwrap();
+/// let sender = term.event_sender();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(1));
+///     sender.send(Event::Custom(CustomEvent::new("download finished"))).unwrap();
+/// });
+///
+/// if let Some(Event::Custom(evt)) = term.get_event(None).unwrap() {
+///     assert_eq!(evt.downcast_ref::<&str>(), Some(&"download finished"));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct EventSender {
+    queue: Arc<EventQueue>,
+}
+
+impl EventSender {
+    /// Sends an event to the terminal. Fails if the terminal no longer exists.
+    pub fn send(&self, evt: Event) -> Result<(), Error> {
+        if self.queue.closed.load(Ordering::SeqCst) {
+            return Err(Error::new(ErrorKind::BrokenPipe, "terminal closed"));
+        }
+        self.queue.events.lock().unwrap().push_back(evt);
+        self.queue.wake()
+    }
+}
+
+/// The events sent to a terminal by its `EventSender`s, with a pipe that becomes readable when
+/// there are any, so that waiting for input can also wait for them.
+pub struct EventQueue {
+    events: Mutex<VecDeque<Event>>,
+    closed: AtomicBool,
+    wake_read: RawFd,
+    wake_write: RawFd,
+}
+
+impl EventQueue {
+    pub fn new() -> Result<Arc<EventQueue>, Error> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(Error::last_os_error());
+        }
+        let queue = EventQueue {
+            events: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+            wake_read: fds[0],
+            wake_write: fds[1],
+        };
+        // Set the flags separately, since `pipe2()` is not portable.
+        for &fd in &fds {
+            if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 ||
+               unsafe { libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK) } != 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+        Ok(Arc::new(queue))
+    }
+
+    /// Returns a new sender for the queue.
+    pub fn sender(queue: &Arc<EventQueue>) -> EventSender {
+        EventSender { queue: queue.clone() }
+    }
+
+    /// Returns the file descriptor that becomes readable when events have been sent.
+    pub fn fd(&self) -> RawFd {
+        self.wake_read
+    }
+
+    /// Returns the file descriptor written to wake the queue up, which signal handlers can write
+    /// to as well.
+    pub fn wake_fd(&self) -> RawFd {
+        self.wake_write
+    }
+
+    /// Moves the events sent so far to the end of `events` and returns their number.
+    pub fn receive(&self, events: &mut VecDeque<Event>) -> usize {
+        // Empty the pipe before taking the events, so that events sent in the meantime leave it
+        // readable again.
+        let mut buf = [0u8; 64];
+        let ptr = buf.as_mut_ptr() as *mut libc::c_void;
+        while unsafe { libc::read(self.wake_read, ptr, buf.len()) } > 0 {}
+        let mut sent = self.events.lock().unwrap();
+        let n = sent.len();
+        events.extend(sent.drain(..));
+        n
+    }
+
+    /// Makes senders fail from now on.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    fn wake(&self) -> Result<(), Error> {
+        let byte = [0u8];
+        let res = unsafe { libc::write(self.wake_write, byte.as_ptr() as *const libc::c_void, 1) };
+        // A full pipe is readable already.
+        if res < 0 {
+            let err = Error::last_os_error();
+            if err.kind() != ErrorKind::WouldBlock {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for EventQueue {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.wake_read);
+            libc::close(self.wake_write);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::{Error, ErrorKind};
+use std::net::TcpStream;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+
+use libc;
+
+/// Where a `Terminal` reads the input it decodes into events, set with
+/// `Terminal::set_input_source()`. By default, input is read from the terminal itself.
+///
+/// `get_event()` waits for the file descriptor of the source to become readable, along with its
+/// other sources of events, then reads everything available. An `EventSender` wakes it up early.
+///
+/// Sources are implemented for sockets, to take input from a remote client, and by
+/// `ScriptSource`, to feed it from the program itself.
+pub trait InputSource: Send {
+    /// Returns a file descriptor that is readable while input is available.
+    fn fd(&self) -> RawFd;
+
+    /// Reads available input into `buf` without blocking, returning `0` if there is none left
+    /// for now. Fails if the source has ended.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+}
+
+// Receives from the socket `fd` without blocking.
+fn recv(fd: RawFd, buf: &mut [u8]) -> Result<usize, Error> {
+    let ptr = buf.as_mut_ptr() as *mut libc::c_void;
+    let res = unsafe { libc::recv(fd, ptr, buf.len(), libc::MSG_DONTWAIT) };
+    match res {
+        0 if !buf.is_empty() => Err(Error::new(ErrorKind::UnexpectedEof, "input source closed")),
+        n if n >= 0 => Ok(n as usize),
+        _ => {
+            let err = Error::last_os_error();
+            if err.kind() == ErrorKind::WouldBlock {
+                Ok(0)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+impl InputSource for TcpStream {
+    fn fd(&self) -> RawFd {
+        self.as_raw_fd()
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        recv(self.as_raw_fd(), buf)
+    }
+}
+
+impl InputSource for UnixStream {
+    fn fd(&self) -> RawFd {
+        self.as_raw_fd()
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        recv(self.as_raw_fd(), buf)
+    }
+}
+
+/// An `InputSource` fed by the program, for scripted input in tests and demos.
+///
+/// Bytes fed through a `ScriptFeeder` are decoded as if they had been typed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::{Event, Key, ScriptSource, Terminal};
+///
+/// let mut term = Terminal::new().unwrap();
+/// let source = ScriptSource::new().unwrap();
+/// let feeder = source.feeder();
+/// term.set_input_source(Some(Box::new(source))).unwrap();
+///
+/// feeder.feed(b"q\x1b[A").unwrap();
+/// assert_eq!(term.get_event(None).unwrap(), Some(Event::Key(Key::Char('q'))));
+/// assert_eq!(term.get_event(None).unwrap(), Some(Event::Key(Key::Up)));
+/// ```
+pub struct ScriptSource {
+    pipe: Arc<Pipe>,
+}
+
+/// A handle for feeding input to a `ScriptSource`, which can be cloned and sent to other threads.
+#[derive(Clone)]
+pub struct ScriptFeeder {
+    pipe: Arc<Pipe>,
+}
+
+struct Pipe {
+    read: RawFd,
+    write: RawFd,
+}
+
+impl ScriptSource {
+    /// Constructs a new `ScriptSource` with no input.
+    pub fn new() -> Result<ScriptSource, Error> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(Error::last_os_error());
+        }
+        let pipe = Pipe {
+            read: fds[0],
+            write: fds[1],
+        };
+        // Set the flags separately, since `pipe2()` is not portable. Only reading must not block.
+        for &(fd, cmd, flag) in &[(pipe.read, libc::F_SETFD, libc::FD_CLOEXEC),
+                                  (pipe.write, libc::F_SETFD, libc::FD_CLOEXEC),
+                                  (pipe.read, libc::F_SETFL, libc::O_NONBLOCK)] {
+            if unsafe { libc::fcntl(fd, cmd, flag) } != 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+        Ok(ScriptSource { pipe: Arc::new(pipe) })
+    }
+
+    /// Returns a new feeder for the source.
+    pub fn feeder(&self) -> ScriptFeeder {
+        ScriptFeeder { pipe: self.pipe.clone() }
+    }
+}
+
+impl ScriptFeeder {
+    /// Feeds `input` to the source.
+    ///
+    /// Blocks while the source holds more input than fits in a pipe, so large scripts are fed
+    /// from another thread than the one reading events.
+    pub fn feed(&self, mut input: &[u8]) -> Result<(), Error> {
+        while !input.is_empty() {
+            let ptr = input.as_ptr() as *const libc::c_void;
+            let res = unsafe { libc::write(self.pipe.write, ptr, input.len()) };
+            if res < 0 {
+                let err = Error::last_os_error();
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            } else {
+                input = &input[res as usize..];
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InputSource for ScriptSource {
+    fn fd(&self) -> RawFd {
+        self.pipe.read
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let ptr = buf.as_mut_ptr() as *mut libc::c_void;
+        let res = unsafe { libc::read(self.pipe.read, ptr, buf.len()) };
+        if res >= 0 {
+            return Ok(res as usize);
+        }
+        let err = Error::last_os_error();
+        if err.kind() == ErrorKind::WouldBlock {
+            Ok(0)
+        } else {
+            Err(err)
+        }
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
@@ -5,9 +5,12 @@ use std::mem;
 use libc;
 
 /// Controller for low-level interaction with a terminal device.
+#[derive(Clone, Copy)]
 pub struct TermCtl {
     fd: RawFd,
     orig_tios: libc::termios,
+    signals: bool, // Whether the interrupt, quit and suspend keys generate signals.
+    flow_control: bool, // Whether Ctrl-S and Ctrl-Q stop and restart output.
 }
 
 impl TermCtl {
@@ -22,6 +25,8 @@ impl TermCtl {
             Ok(TermCtl {
                 fd: fd,
                 orig_tios: termios,
+                signals: false,
+                flow_control: false,
             })
         }
     }
@@ -29,10 +34,15 @@ impl TermCtl {
     pub fn set(&self) -> Result<(), Error> {
         let mut tios = self.orig_tios.clone();
         tios.c_iflag &= !(libc::IGNBRK | libc::BRKINT | libc::PARMRK | libc::ISTRIP |
-                          libc::INLCR | libc::IGNCR | libc::ICRNL |
-                          libc::IXON);
+                          libc::INLCR | libc::IGNCR | libc::ICRNL);
+        if !self.flow_control {
+            tios.c_iflag &= !libc::IXON;
+        }
         tios.c_oflag &= !libc::OPOST;
-        tios.c_lflag &= !(libc::ECHO | libc::ECHONL | libc::ICANON | libc::ISIG | libc::IEXTEN);
+        tios.c_lflag &= !(libc::ECHO | libc::ECHONL | libc::ICANON | libc::IEXTEN);
+        if !self.signals {
+            tios.c_lflag &= !libc::ISIG;
+        }
         tios.c_cflag &= !(libc::CSIZE | libc::PARENB);
         tios.c_cflag |= libc::CS8;
         tios.c_cc[libc::VMIN] = 0;
@@ -47,6 +57,36 @@ impl TermCtl {
         }
     }
 
+    /// Sets whether the interrupt, quit and suspend keys generate signals in the mode set by
+    /// `set()`, taking effect on its next call.
+    pub fn set_signals(&mut self, on: bool) {
+        self.signals = on;
+    }
+
+    /// Sets whether software flow control with Ctrl-S and Ctrl-Q is left on in the mode set by
+    /// `set()`, taking effect on its next call.
+    pub fn set_flow_control(&mut self, on: bool) {
+        self.flow_control = on;
+    }
+
+    /// Puts the terminal into a mode suitable for reading a secret: input is not echoed and
+    /// bytes are delivered one at a time, without line editing or signal generation, while
+    /// output processing is left untouched.
+    pub fn set_noecho(&self) -> Result<(), Error> {
+        let mut tios = self.orig_tios;
+        tios.c_lflag &= !(libc::ECHO | libc::ECHONL | libc::ICANON | libc::ISIG | libc::IEXTEN);
+        tios.c_cc[libc::VMIN] = 1;
+        tios.c_cc[libc::VTIME] = 0;
+
+        let res = unsafe { libc::tcsetattr(self.fd, libc::TCSAFLUSH, &tios) };
+
+        if res != 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn window_size(&self) -> Result<(usize, usize), Error> {
         let mut ws: libc::winsize = unsafe { mem::uninitialized() };
         let res = unsafe { libc::ioctl(self.fd, libc::TIOCGWINSZ, &mut ws) };
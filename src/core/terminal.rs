@@ -1,13 +1,17 @@
+use std::cmp;
 use std::ops::{Index, IndexMut, Deref, DerefMut};
 use std::io::prelude::*;
 use std::io::{Error, ErrorKind};
 use std::fs::OpenOptions;
 use std::fs::File;
-use std::os::unix::io::AsRawFd;
-use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+use std::path::Path;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering, ATOMIC_BOOL_INIT};
 use std::collections::VecDeque;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
 use std::ptr;
 use std::mem;
 
@@ -15,22 +19,58 @@ use libc;
 
 use gag::BufferRedirect;
 
-use core::cellbuffer::{CellAccessor, CellBuffer, Cell, Color, Attr};
-use core::input::Event;
+use core::cellbuffer::{CellAccessor, CellBuffer, Cell, Color, Attr, UnderlineStyle};
+use core::input::{Event, Key, OverflowPolicy, Parser};
 use core::position::{Cursor, Pos, Size, HasSize};
 use core::driver::{DevFn, Driver};
 use core::termctl::TermCtl;
+use core::palette::Palette;
+use core::sender::{EventQueue, EventSender};
+use core::source::InputSource;
+use core::ringbuf::RingBuffer;
+use core::backdrop::capture_screen;
+use core::features::{self, Feature};
+use core::capabilities::Capabilities;
+use core::panic_screen::PanicScreen;
+use core::watchdog::Watchdog;
+use core::diagnostic::Diagnostic;
+use core::width;
+use offscreen;
 
 /// Set to true by the sigwinch handler. Reset to false when buffers are resized.
 static SIGWINCH_STATUS: AtomicBool = ATOMIC_BOOL_INIT;
 
+/// Set to true when a bell has been requested from outside the `Terminal`, e.g. by widgets giving
+/// feedback. Reset to false when the bell is sent on the next buffer swap.
+static BELL_STATUS: AtomicBool = AtomicBool::new(false);
+
+/// Signals caught since they were last handled, as bits of `1 << signum`. See `set_signals()`.
+static SIGNAL_STATUS: AtomicUsize = AtomicUsize::new(0);
+
+/// The file descriptor the signal handler writes to, waking up the `Terminal` if it is waiting
+/// for input, or -1 while signals are not caught.
+static SIGNAL_WAKE_FD: AtomicI32 = AtomicI32::new(-1);
+
+/// Signals caught while enabled with `set_signals()`.
+const CAUGHT_SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTSTP, libc::SIGCONT];
+
 /// Ensures that there is only ever one Terminal object at any one time.
 /// Set to true on creation of a Terminal object.
 /// Reset to false when terminal object goes out of scope.
 static RUSTTY_STATUS: AtomicBool = ATOMIC_BOOL_INIT;
 
 type OutBuffer = Vec<u8>;
-type EventBuffer = VecDeque<Event>;
+type EventBuffer = VecDeque<(Event, Instant)>; // Events with the time they were read.
+/// Callback run on the backbuffer before it is displayed.
+type BeforeRefresh = Box<dyn FnMut(&mut CellBuffer) + Send>;
+/// Callback run on the frame that was just displayed.
+type AfterRefresh = Box<dyn FnMut(&CellBuffer) + Send>;
+
+/// Number of diagnostics kept until they are taken. Later ones are dropped.
+const MAX_DIAGNOSTICS: usize = 1024;
+
+/// Number of bytes the input buffer holds before it grows.
+const INPUT_CAPACITY: usize = 4096;
 
 /// A representation of the current terminal window.
 ///
@@ -64,15 +104,53 @@ pub struct Terminal {
     rows: usize, // Number of rows in the terminal window.
     driver: Driver, // Terminal driver (terminfo).
     backbuffer: CellBuffer, // Internal backbuffer.
-    frontbuffer: CellBuffer, // Internal frontbuffer.
+    frontbuffer: Arc<CellBuffer>, // Internal frontbuffer, shared with snapshots.
     outbuffer: OutBuffer, // Internal output buffer.
     eventbuffer: EventBuffer, // Event buffer.
+    inbuffer: RingBuffer, // Input read from the terminal but not yet decoded.
+    parser: Parser, // Decodes the input buffer into events.
+    raw_input: bool, // Whether input is reported undecoded.
+    source: Option<Box<dyn InputSource>>, // Where input is read instead of the terminal.
+    sent: Arc<EventQueue>, // Events sent from other threads.
+    watched: Vec<(RawFd, usize)>, // File descriptors waited on with their tokens.
+    event_capacity: Option<(usize, OverflowPolicy)>, // Limit on the number of queued events.
+    overflowed: bool, // Whether events were discarded under `OverflowPolicy::Error`.
+    resize_debounce: Option<Duration>, // How long the size has to settle before resizing.
+    resize_seen: Option<Instant>, // When a pending size change was last seen.
     laststyle: Cell, // Last cell to have its style (fg, bg, attrs) written to the output buffer.
+    acs: Option<Vec<(char, u8)>>, // Glyphs drawn in the alternate character set, if any are.
+    acs_on: bool, // Whether the alternate character set is selected.
+    mouse: bool, // Whether mouse events are reported.
+    mouse_motion: bool, // Whether motion without a button held is reported.
+    diagnostics: Vec<Diagnostic>, // Problems worked around since they were last taken.
     cursor: Cursor, // Current cursor position.
+    palette: Palette, // Colors of the palette slots.
+    palette_dirty: bool, // Whether the palette may have changed since the last buffer swap.
+    drawn_palette: Palette, // Palette the frontbuffer was drawn with.
+    last_input: Instant, // When the last event was read from the terminal.
+    idle_threshold: Option<Duration>, // Idle time after which an idle event is sent.
+    idle_sent: bool, // Whether an idle event was sent since the last input.
+    timers: Vec<(usize, Duration, Instant)>, // Timer ids with their intervals and next ticks.
+    replayed: VecDeque<(Event, Instant)>, // Events being replayed with when they are due.
+    before_refresh: Vec<BeforeRefresh>, // Hooks run before each buffer swap.
+    after_refresh: Vec<AfterRefresh>, // Hooks run after each buffer swap.
+    alt_screen: bool, // Whether the alternate screen is used.
+    backdrop: Option<CellBuffer>, // Shown behind what is drawn, when drawing over the screen.
+    exit_prints: Vec<CellBuffer>, // Printed to the primary screen when dropped.
+    redefined_colors: Vec<u8>, // Palette entries of the terminal redefined, restored when dropped.
+    panic_screen: bool, // Whether a panic is reported on screen before restoring the terminal.
+    watchdog: Option<Watchdog>, // Restores the terminal if the program hangs.
     stderr_handle: BufferRedirect,
 }
 
 impl Terminal {
+    /// Returns `true` if the process has a controlling terminal and standard output goes to a
+    /// terminal. See `rustty::can_init()` for a complete check.
+    pub fn is_tty() -> bool {
+        let stdout = unsafe { libc::isatty(libc::STDOUT_FILENO) } == 1;
+        stdout && OpenOptions::new().write(true).read(true).open("/dev/tty").is_ok()
+    }
+
     /// Constructs a new `Terminal` using the default `Cell` as a blank.
     ///
     /// # Examples
@@ -115,12 +193,58 @@ impl Terminal {
     /// assert_eq!(term[(0, 0)].ch(), 'x');
     /// ```
     pub fn with_cell(cell: Cell) -> Result<Terminal, Error> {
+        Terminal::init(cell, true)
+    }
+
+    /// Constructs a new `Terminal` drawing over the current screen instead of switching to the
+    /// alternate screen, the way popup tools such as fzf draw over the shell.
+    ///
+    /// The screen is captured with `capture_screen()` before anything is drawn and becomes the
+    /// backdrop, which `clear_to_backdrop()` fills the backbuffer with so that dialogs float
+    /// over what was there. Where the screen can't be captured, the application can supply a
+    /// backdrop with `set_backdrop()`. When the `Terminal` is dropped, the backdrop is drawn
+    /// again, or the screen is cleared if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    /// use rustty::ui::{Dialog, DialogResult, HorizontalAlign, VerticalAlign};
+    ///
+    /// let mut term = Terminal::overlay().unwrap();
+    /// let mut dlg = Dialog::new(30, 5);
+    /// dlg.draw_box();
+    /// dlg.add_button("Ok", 'o', DialogResult::Ok);
+    /// dlg.draw_buttons();
+    /// dlg.set_alignment(&term, HorizontalAlign::Middle, VerticalAlign::Middle, 0);
+    ///
+    /// term.clear_to_backdrop().unwrap();
+    /// dlg.window().draw_into(&mut term);
+    /// term.swap_buffers().unwrap();
+    /// ```
+    pub fn overlay() -> Result<Terminal, Error> {
+        // Capture the screen before it is cleared.
+        let backdrop = capture_screen();
+        let mut terminal = try!(Terminal::init(Cell::default(), false));
+        terminal.backdrop = backdrop;
+        try!(terminal.clear_to_backdrop());
+        Ok(terminal)
+    }
+
+    fn init(cell: Cell, alt_screen: bool) -> Result<Terminal, Error> {
         // Make sure there is only ever one instance.
         if RUSTTY_STATUS.compare_and_swap(false, true, Ordering::SeqCst) {
             return Err(Error::new(ErrorKind::AlreadyExists, "terminal already initialized"));
         }
 
         let driver = try!(Driver::new());
+        let parser = Parser::new(driver.keys());
+        // Without Unicode, line drawing characters are drawn in the alternate character set.
+        let acs = if features::supported(Feature::Unicode) {
+            None
+        } else {
+            driver.acs_map()
+        };
 
         // Open the terminal file for the controlling process.
         let tty = try!(OpenOptions::new()
@@ -151,20 +275,62 @@ impl Terminal {
             rows: 0,
             driver: driver,
             backbuffer: CellBuffer::new(0, 0, cell),
-            frontbuffer: CellBuffer::new(0, 0, cell),
+            frontbuffer: Arc::new(CellBuffer::new(0, 0, cell)),
             outbuffer: OutBuffer::with_capacity(32 * 1024),
             eventbuffer: EventBuffer::with_capacity(128),
+            inbuffer: RingBuffer::with_capacity(INPUT_CAPACITY),
+            parser,
+            raw_input: false,
+            source: None,
+            sent: try!(EventQueue::new()),
+            watched: Vec::new(),
+            event_capacity: None,
+            overflowed: false,
+            resize_debounce: None,
+            resize_seen: None,
             laststyle: cell,
+            acs,
+            acs_on: false,
+            mouse: true,
+            mouse_motion: false,
+            diagnostics: Vec::new(),
             cursor: Cursor::new(),
+            palette: Palette::new(),
+            palette_dirty: false,
+            drawn_palette: Palette::new(),
+            last_input: Instant::now(),
+            idle_threshold: None,
+            idle_sent: false,
+            timers: Vec::new(),
+            replayed: VecDeque::new(),
+            before_refresh: Vec::new(),
+            after_refresh: Vec::new(),
+            alt_screen,
+            backdrop: None,
+            exit_prints: Vec::new(),
+            redefined_colors: Vec::new(),
+            panic_screen: true,
+            watchdog: None,
             stderr_handle: BufferRedirect::stderr().unwrap(),
         };
 
         // Switch to alternate screen buffer. Writes the control code to the output buffer.
-        try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::EnterCa)));
+        if alt_screen {
+            try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::EnterCa)));
+        }
 
         // Hide cursor. Writes the control code to the output buffer.
         try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::HideCursor)));
 
+        // Enable mouse tracking. Writes the control code to the output buffer.
+        try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::EnterMouse)));
+
+        // Enable focus reporting. Writes the control code to the output buffer.
+        try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::EnterFocus)));
+
+        // Enable bracketed paste. Writes the control code to the output buffer.
+        try!(terminal.outbuffer.write_all(&terminal.driver.get(DevFn::EnterPaste)));
+
         // Resize the buffers to the size of the underlying terminals. Using the given cell as a
         // blank.
         try!(terminal.resize_with_cell(cell));
@@ -184,29 +350,196 @@ impl Terminal {
     /// term.swap_buffers().unwrap();
     /// ```
     pub fn swap_buffers(&mut self) -> Result<(), Error> {
+        trace_span!("refresh");
         // Check whether the window has been resized; if it has then update and resize the buffers.
-        if SIGWINCH_STATUS.compare_and_swap(true, false, Ordering::SeqCst) {
+        try!(self.feed_watchdog());
+        if self.resize_due() {
             try!(self.resize());
         }
 
+        if BELL_STATUS.swap(false, Ordering::SeqCst) {
+            try!(self.bell());
+        }
+
+        for hook in &mut self.before_refresh {
+            hook(&mut self.backbuffer);
+        }
+
+        try!(self.draw_frame());
+
+        for hook in &mut self.after_refresh {
+            hook(&self.frontbuffer);
+        }
+        Ok(())
+    }
+
+    // Displays the backbuffer, drawing the cells that differ from the frontbuffer.
+    fn draw_frame(&mut self) -> Result<(), Error> {
         // Invalidate the last cursor position.
         self.cursor.invalidate_last_pos();
 
-        for y in 0..self.rows() {
-            for x in 0..self.cols() {
-                if self.frontbuffer[(x, y)] == self.backbuffer[(x, y)] {
-                    continue; // Don't redraw cells that haven't changed.
-                } else {
-                    let cell = self.backbuffer[(x, y)];
-                    try!(self.send_style(cell));
-                    try!(self.send_char(Some((x, y)), cell.ch()));
-                    self.frontbuffer[(x, y)] = cell;
+        // Cells whose colors resolve differently have to be redrawn if the palette changed.
+        let repaint = mem::replace(&mut self.palette_dirty, false);
+
+        {
+            trace_span!("diff");
+            let mut changed = 0;
+            for y in 0..self.rows() {
+                for x in 0..self.cols() {
+                    let cell = self.display_cell(x, y);
+                    if self.frontbuffer[(x, y)] == cell && !(repaint && self.recolored(&cell)) {
+                        continue; // Don't redraw cells that haven't changed.
+                    } else if cell.is_continuation() {
+                        // Drawn along with the double-width character before it.
+                        Arc::make_mut(&mut self.frontbuffer)[(x, y)] = cell;
+                    } else {
+                        let ch = self.checked_char((x, y), cell.ch());
+                        try!(self.send_style(Some((x, y)), cell));
+                        try!(self.send_char(Some((x, y)), ch));
+                        if width::char_width(ch) > 1 {
+                            // The cursor has moved past the continuation cell.
+                            self.cursor.set_pos(Some((x + 1, y)));
+                        }
+                        Arc::make_mut(&mut self.frontbuffer)[(x, y)] = cell;
+                        changed += 1;
+                    }
                 }
             }
+            trace_count!("cells_drawn", changed);
         }
+        if repaint {
+            self.drawn_palette = self.palette.clone();
+        }
+        try!(self.exit_acs());
         try!(self.send_cursor());
-        try!(self.flush());
-        Ok(())
+        self.flush()
+    }
+
+    /// Returns the problems `swap_buffers()` has worked around since the last call, oldest first,
+    /// and clears them.
+    ///
+    /// Cells that cannot be displayed as they are, such as ones holding control characters or
+    /// attributes the terminal lacks, are drawn as best they can and reported here instead of
+    /// failing the buffer swap, which only returns errors writing to the terminal. Cells are only
+    /// checked when they are redrawn.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        mem::take(&mut self.diagnostics)
+    }
+
+    // Returns the cell of the backbuffer at (`x`, `y`) as it is displayed.
+    fn display_cell(&self, x: usize, y: usize) -> Cell {
+        width::display_cell(&self.backbuffer, x, y)
+    }
+
+    // Returns the character to draw for `ch` at `pos`, which is a space if `ch` cannot be drawn
+    // there, recording why.
+    fn checked_char(&mut self, pos: Pos, ch: char) -> char {
+        let diagnostic = if ch.is_control() {
+            Diagnostic::ControlChar { pos, ch }
+        } else if pos.0 + 1 == self.cols && width::char_width(ch) > 1 {
+            Diagnostic::Clipped { pos, ch }
+        } else {
+            return ch;
+        };
+        self.diagnose(diagnostic);
+        ' '
+    }
+
+    // Records `diagnostic` for `take_diagnostics()`, unless too many are waiting already.
+    fn diagnose(&mut self, diagnostic: Diagnostic) {
+        if self.diagnostics.len() < MAX_DIAGNOSTICS {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Registers a hook that `swap_buffers()` runs on the backbuffer just before displaying it,
+    /// after any resize. Hooks run in the order they were registered.
+    ///
+    /// Hooks let integrations take part in every frame without wrapping each call site, for
+    /// example to draw an overlay on top of whatever the application drew.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{CellAccessor, Terminal};
+    /// use rustty::ui::Painter;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// let mut frames = 0;
+    /// term.on_before_refresh(move |buffer| {
+    ///     frames += 1;
+    ///     buffer.printline(0, 0, &format!("frame {}", frames));
+    /// });
+    /// term.swap_buffers().unwrap();
+    /// ```
+    pub fn on_before_refresh<F>(&mut self, hook: F)
+        where F: FnMut(&mut CellBuffer) + Send + 'static
+    {
+        self.before_refresh.push(Box::new(hook));
+    }
+
+    /// Registers a hook that `swap_buffers()` runs on the displayed frame once it has been
+    /// written to the terminal. Hooks run in the order they were registered.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Instant;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// let frame_times = Arc::new(Mutex::new(Vec::new()));
+    /// let times = frame_times.clone();
+    /// let mut last = Instant::now();
+    /// term.on_after_refresh(move |_| {
+    ///     times.lock().unwrap().push(last.elapsed());
+    ///     last = Instant::now();
+    /// });
+    /// term.swap_buffers().unwrap();
+    /// assert_eq!(frame_times.lock().unwrap().len(), 1);
+    /// ```
+    pub fn on_after_refresh<F>(&mut self, hook: F)
+        where F: FnMut(&CellBuffer) + Send + 'static
+    {
+        self.after_refresh.push(Box::new(hook));
+    }
+
+    /// Removes all hooks registered with `on_before_refresh()` and `on_after_refresh()`.
+    pub fn clear_refresh_hooks(&mut self) {
+        self.before_refresh.clear();
+        self.after_refresh.clear();
+    }
+
+    // Returns `true` if the cell's colors resolve differently than when the frontbuffer was drawn.
+    fn recolored(&self, cell: &Cell) -> bool {
+        let (old, new) = (&self.drawn_palette, &self.palette);
+        old.resolve(cell.fg()) != new.resolve(cell.fg()) ||
+        old.resolve(cell.bg()) != new.resolve(cell.bg())
+    }
+
+    /// Returns the frame presented by the last call to `swap_buffers()`.
+    ///
+    /// The snapshot is shared rather than copied, and can be sent to another thread, e.g. to
+    /// record or export the display, without blocking the render loop. The frontbuffer is only
+    /// copied when the next buffer swap changes it while a snapshot is still held.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::thread;
+    /// use rustty::{CellAccessor, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.swap_buffers().unwrap();
+    ///
+    /// let frame = term.frontbuffer_snapshot();
+    /// let recorder = thread::spawn(move || frame.get(0, 0).map(|cell| cell.ch()));
+    /// term.swap_buffers().unwrap();
+    /// recorder.join().unwrap();
+    /// ```
+    pub fn frontbuffer_snapshot(&self) -> Arc<CellBuffer> {
+        self.frontbuffer.clone()
     }
 
     /// Returns the width of the terminal in columns.
@@ -252,7 +585,7 @@ impl Terminal {
     /// ```
     pub fn clear(&mut self) -> Result<(), Error> {
         // Check whether the window has been resized; if it has then update and resize the buffers.
-        if SIGWINCH_STATUS.compare_and_swap(true, false, Ordering::SeqCst) {
+        if self.resize_due() {
             try!(self.resize());
         }
         self.backbuffer.clear(Cell::default());
@@ -274,7 +607,7 @@ impl Terminal {
     /// ```
     pub fn clear_with_char(&mut self, ch: char) -> Result<(), Error> {
         // Check whether the window has been resized; if it has then update and resize the buffers.
-        if SIGWINCH_STATUS.compare_and_swap(true, false, Ordering::SeqCst) {
+        if self.resize_due() {
             try!(self.resize());
         }
         self.backbuffer.clear(Cell::with_char(ch));
@@ -299,17 +632,226 @@ impl Terminal {
     /// ```
     pub fn clear_with_cell(&mut self, cell: Cell) -> Result<(), Error> {
         // Check whether the window has been resized; if it has then update and resize the buffers.
-        if SIGWINCH_STATUS.compare_and_swap(true, false, Ordering::SeqCst) {
+        if self.resize_due() {
             try!(self.resize());
         }
         self.backbuffer.clear(cell);
         Ok(())
     }
 
+    /// Clears the internal backbuffer to the backdrop, leaving the cells it doesn't cover blank.
+    /// Without a backdrop this is the same as `clear()`. See `Terminal::overlay()`.
+    pub fn clear_to_backdrop(&mut self) -> Result<(), Error> {
+        try!(self.clear());
+        if let Some(ref backdrop) = self.backdrop {
+            let (cols, rows) = backdrop.size();
+            for y in 0..rows {
+                for x in 0..cols {
+                    if let Some(cell) = self.backbuffer.get_mut(x, y) {
+                        *cell = backdrop[(x, y)];
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the backdrop `clear_to_backdrop()` fills the backbuffer with, such as a screen
+    /// captured by other means than `capture_screen()`, or `None` to have none.
+    pub fn set_backdrop(&mut self, backdrop: Option<CellBuffer>) {
+        self.backdrop = backdrop;
+    }
+
+    /// Returns the backdrop.
+    pub fn backdrop(&self) -> Option<&CellBuffer> {
+        self.backdrop.as_ref()
+    }
+
+    /// Registers cells to print to the primary screen when the `Terminal` is dropped, after
+    /// leaving the alternate screen, so that they stay in the scrollback. Cells registered by
+    /// several calls are printed in order.
+    ///
+    /// The cells are copied with their colors as they look now, and printed as lines of text
+    /// with escape sequences for their styles.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    /// use rustty::ui::{Painter, Widget};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// // ...
+    /// let mut summary = Widget::new(20, 1);
+    /// summary.printline(0, 0, "3 files copied");
+    /// term.print_on_exit(&summary);
+    /// ```
+    pub fn print_on_exit(&mut self, cells: &dyn CellAccessor) {
+        let truecolor = features::supported(Feature::Truecolor);
+        let copy = self.palette.resolve_cells(cells, truecolor);
+        self.exit_prints.push(copy);
+    }
+
+    /// Sets whether a panic is reported on screen. On by default.
+    ///
+    /// Standard error is captured while the `Terminal` exists, so that it does not garble the
+    /// display. When the `Terminal` is dropped during a panic, the panic message, and the backtrace
+    /// if `RUST_BACKTRACE` is set, are shown on a red screen, where the backtrace can be scrolled,
+    /// until input is read. They are then printed to the primary screen once the terminal is
+    /// restored. The screen is skipped while input comes from an `InputSource` other than the
+    /// terminal. Turn this off for programs that cannot wait for input, to only print them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_panic_screen(false);
+    /// ```
+    pub fn set_panic_screen(&mut self, on: bool) {
+        self.panic_screen = on;
+    }
+
+    /// Starts a watchdog that restores the terminal if the program hangs, or stops it with `None`.
+    ///
+    /// The watchdog is fed every time the buffers are swapped or events are polled for, and
+    /// waiting for events does not count as hanging. If it is not fed for `timeout`, a deadlocked
+    /// program would leave the terminal unusable, so it puts the terminal back in its original
+    /// mode, leaves the alternate screen and prints a warning, from a thread of its own. Should
+    /// the program recover, the next swap or poll takes the terminal back and redraws it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_watchdog(Some(Duration::from_secs(10)));
+    /// ```
+    pub fn set_watchdog(&mut self, timeout: Option<Duration>) {
+        // Stop the running watchdog first, so that only one can restore the terminal.
+        self.watchdog = None;
+        self.watchdog = timeout.map(|timeout| {
+            Watchdog::start(timeout, self.termctl, self.tty.as_raw_fd(), self.restore_sequence())
+        });
+    }
+
+    // Returns what puts the terminal back as it was before initialization, for the watchdog and
+    // for suspending.
+    fn restore_sequence(&self) -> Vec<u8> {
+        let mut restore = Vec::new();
+        for dfn in &[DevFn::ExitPaste, DevFn::ExitFocus, DevFn::ExitMouseMotion,
+                     DevFn::ExitMouse, DevFn::ShowCursor] {
+            restore.extend(self.driver.get(*dfn));
+        }
+        for &index in &self.redefined_colors {
+            restore.extend(self.driver.get(DevFn::ResetPaletteColor(index)));
+        }
+        restore.extend(self.driver.get(DevFn::Reset));
+        if self.alt_screen {
+            restore.extend(self.driver.get(DevFn::ExitCa));
+        }
+        restore
+    }
+
+    // Lets the watchdog restore what changed since it was started.
+    fn update_watchdog(&self) {
+        if let Some(ref watchdog) = self.watchdog {
+            watchdog.set_restore(self.restore_sequence());
+        }
+    }
+
+    // Feeds the watchdog, taking the terminal back if it was restored in the meantime.
+    fn feed_watchdog(&mut self) -> Result<(), Error> {
+        if !self.watchdog.as_ref().is_some_and(Watchdog::feed) {
+            return Ok(());
+        }
+        self.take_back()
+    }
+
+    // Puts the terminal back into the mode it is used in after it was restored, and has
+    // everything redrawn on the next buffer swap.
+    fn take_back(&mut self) -> Result<(), Error> {
+        try!(self.termctl.set());
+        if self.alt_screen {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterCa)));
+        }
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::HideCursor)));
+        if self.mouse {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouse)));
+            if self.mouse_motion {
+                try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouseMotion)));
+            }
+        }
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterFocus)));
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterPaste)));
+        // Redraw everything over what was printed meanwhile.
+        Arc::make_mut(&mut self.frontbuffer).clear(Cell::default());
+        try!(self.send_style(None, Cell::default()));
+        try!(self.send_clear());
+        self.flush()
+    }
+
+    // Handles the signals caught since the last call, adding their events to `events`.
+    fn handle_signals(&mut self, events: &mut VecDeque<Event>) -> Result<(), Error> {
+        let mut caught = SIGNAL_STATUS.swap(0, Ordering::SeqCst);
+        if caught & 1 << libc::SIGTSTP != 0 {
+            try!(self.suspend());
+            // Take the terminal back even if the stop was discarded, as it is for orphaned
+            // process groups, and without a second resume once `SIGCONT` is caught.
+            caught |= SIGNAL_STATUS.swap(0, Ordering::SeqCst) | 1 << libc::SIGCONT;
+        }
+        if caught & 1 << libc::SIGINT != 0 {
+            events.push_back(Event::Interrupt);
+        }
+        if caught & 1 << libc::SIGCONT != 0 {
+            try!(self.take_back());
+            events.push_back(Event::Resume);
+        }
+        Ok(())
+    }
+
+    // Restores the terminal and stops the program, returning once it is continued.
+    fn suspend(&mut self) -> Result<(), Error> {
+        let restore = self.restore_sequence();
+        try!(self.outbuffer.write_all(&restore));
+        try!(self.flush());
+        try!(self.termctl.reset());
+        // Stop the way the suspend key does without a handler, then catch it again.
+        try!(set_signal_handler(libc::SIGTSTP, libc::SIG_DFL));
+        unsafe { libc::raise(libc::SIGTSTP) };
+        let handler = signal_handler as extern "C" fn(i32) as libc::sighandler_t;
+        set_signal_handler(libc::SIGTSTP, handler)
+    }
+
+    // Shows the panic `report` until input other than a scrolling key is read. Refresh hooks are
+    // not run, since a panic in one now would abort.
+    fn show_panic(&mut self, report: &str) -> Result<(), Error> {
+        let screen = PanicScreen::new(report);
+        let mut scroll = 0;
+        loop {
+            try!(self.clear());
+            let (page, max_scroll) = (self.rows / 2, screen.max_scroll(self.rows));
+            screen.draw(self, scroll);
+            try!(self.draw_frame());
+            scroll = match try!(self.get_event(None)) {
+                Some(Event::Key(Key::Up)) => scroll.saturating_sub(1),
+                Some(Event::Key(Key::Down)) => cmp::min(scroll + 1, max_scroll),
+                Some(Event::Key(Key::PageUp)) => scroll.saturating_sub(page),
+                Some(Event::Key(Key::PageDown)) => cmp::min(scroll + page, max_scroll),
+                Some(Event::Key(_)) | Some(Event::Mouse(_)) | Some(Event::Raw(_)) |
+                Some(Event::Unknown(_)) | Some(Event::Interrupt) => return Ok(()),
+                _ => scroll,
+            };
+        }
+    }
+
     /// Checks whether the underlying window size has changed and the buffers have not been
     /// resized yet. If this method returns `true` the next call to `swap_buffers()` or a `clear()`
     /// method is guaranteed to resize the buffers unless a call to a `try_resize()` method is
-    /// made.
+    /// made, or resizes are debounced and the interval has not passed yet.
     ///
     /// # Examples
     ///
@@ -329,7 +871,53 @@ impl Terminal {
     /// term.try_resize().unwrap();
     /// ```
     pub fn check_resize(&self) -> bool {
-        SIGWINCH_STATUS.load(Ordering::SeqCst)
+        SIGWINCH_STATUS.load(Ordering::SeqCst) || self.resize_seen.is_some()
+    }
+
+    /// Sets how long the window size has to stay the same before the buffers are resized, or
+    /// `None` to resize them as soon as possible, which is the default.
+    ///
+    /// Terminals send a burst of size changes while a window is being dragged. Debouncing them
+    /// reallocates and clears the buffers once when the dragging pauses, instead of for every
+    /// intermediate size, which avoids flicker. The resize happens in the first call to
+    /// `swap_buffers()`, a `clear()` method or a `try_resize()` method after the interval has
+    /// passed, so keep calling one of them regularly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_resize_debounce(Some(Duration::from_millis(100)));
+    /// ```
+    pub fn set_resize_debounce(&mut self, interval: Option<Duration>) {
+        self.resize_debounce = interval;
+    }
+
+    // Returns the time left before a pending resize is due, or `None` if there is none.
+    fn resize_in(&self) -> Option<Duration> {
+        if SIGWINCH_STATUS.load(Ordering::SeqCst) {
+            return Some(Duration::from_secs(0));
+        }
+        let debounce = self.resize_debounce.unwrap_or_default();
+        self.resize_seen.map(|seen| debounce.saturating_sub(seen.elapsed()))
+    }
+
+    // Returns `true` if the window size has changed and the buffers should be resized now, once
+    // no further change has been seen for the debounce interval.
+    fn resize_due(&mut self) -> bool {
+        if SIGWINCH_STATUS.swap(false, Ordering::SeqCst) {
+            self.resize_seen = Some(Instant::now());
+        }
+        match self.resize_seen {
+            Some(seen) if self.resize_debounce.is_none_or(|d| seen.elapsed() >= d) => {
+                self.resize_seen = None;
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Resizes the buffers if the underlying terminal window size has changed, using the default
@@ -339,10 +927,11 @@ impl Terminal {
     /// method.
     ///
     /// This method is guaranteed to resize the buffers if a call to `check_resize()` returns
-    /// `true` and neither `swap_buffers()` nor a `clear()` method has been called since.
+    /// `true` and neither `swap_buffers()` nor a `clear()` method has been called since, unless
+    /// resizes are debounced and the interval has not passed yet.
     ///
     /// Returns `Some((cols, rows))` if the buffers were resized and `None` if no resize was
-    /// performed.
+    /// performed. Like every resize, this also queues an `Event::Resize`.
     ///
     /// # Examples
     ///
@@ -394,8 +983,9 @@ impl Terminal {
     /// let new_size = term.try_resize_with_cell(cell).unwrap();
     /// ```
     pub fn try_resize_with_cell(&mut self, cell: Cell) -> Result<Option<(usize, usize)>, Error> {
-        if SIGWINCH_STATUS.compare_and_swap(true, false, Ordering::SeqCst) {
+        if self.resize_due() {
             try!(self.resize_with_cell(cell));
+            self.push_event(Event::Resize((self.cols, self.rows)));
             return Ok(Some((self.cols, self.rows)));
         }
         Ok(None)
@@ -439,96 +1029,1007 @@ impl Terminal {
         Ok(())
     }
 
-    /// Gets an event from the event stream, waiting at most the value specified in `timeout`.
+    /// Enables or disables reporting mouse motion while no button is held, as
+    /// `MouseAction::Moved` events. It is disabled by default, since it sends an event for every
+    /// cell the pointer crosses. Dragging with a button held is always reported.
     ///
-    /// Specifying a `timeout` of `None` causes `get_event()` to block indefinitely, while
-    /// specifying a `timeout` of zero causes `get_event()` to return immediately.
+    /// # Examples
     ///
-    /// Returns `Some(Event)` if an event was received within the specified timeout, or None
-    /// otherwise.
+    /// ```no_run
+    /// use rustty::{Event, MouseAction, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_mouse_motion(true).unwrap();
+    /// if let Some(Event::Mouse(evt)) = term.get_event(None).unwrap() {
+    ///     if evt.action == MouseAction::Moved {
+    ///         // Highlight the item under (evt.x, evt.y).
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// While mouse reporting is disabled, the setting is remembered and takes effect when it is
+    /// enabled again.
+    pub fn set_mouse_motion(&mut self, on: bool) -> Result<(), Error> {
+        if self.mouse && on {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouseMotion)));
+        } else if self.mouse {
+            // Leaving any-motion tracking switches off all mouse tracking in xterm, so the
+            // reporting of clicks and drags is switched back on.
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::ExitMouseMotion)));
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouse)));
+        }
+        self.mouse_motion = on;
+        self.flush()
+    }
+
+    /// Enables reporting of mouse events, which is the default.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// # use std::thread::sleep_ms;
-    /// use rustty::{Terminal, Event};
-    /// use std::time::Duration;
+    /// use rustty::Terminal;
     ///
     /// let mut term = Terminal::new().unwrap();
-    ///
-    /// let evt = term.get_event(Some(Duration::from_secs(1))).unwrap();
+    /// // Let the user select text with the terminal's own selection for a while.
+    /// term.disable_mouse().unwrap();
+    /// assert!(!term.mouse_enabled());
+    /// term.enable_mouse().unwrap();
     /// ```
-    pub fn get_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>, Error> {
-        // Check if the event buffer is empty.
-        if self.eventbuffer.is_empty() {
-            // Event buffer is empty, lets poll the terminal for events.
-            let nevts = try!(self.read_events(timeout));
-            if nevts == 0 {
-                // No events from the terminal either. Return none.
-                Ok(None)
-            } else {
-                // Got at least one event from the terminal. Pop from the front of the event queue.
-                Ok(self.eventbuffer.pop_front())
+    pub fn enable_mouse(&mut self) -> Result<(), Error> {
+        if !self.mouse {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouse)));
+            if self.mouse_motion {
+                try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterMouseMotion)));
             }
-        } else {
-            // There is at least one event in the buffer already. Pop and return it.
-            Ok(self.eventbuffer.pop_front())
+            self.mouse = true;
         }
+        self.flush()
     }
 
-    fn send_cursor(&mut self) -> Result<(), Error> {
-        if let Some((cx, cy)) = self.cursor.pos() {
-            try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetCursor(cx, cy))));
+    /// Disables reporting of mouse events, handing the mouse back to the terminal so that it can
+    /// select text natively. See `enable_mouse()`.
+    pub fn disable_mouse(&mut self) -> Result<(), Error> {
+        if self.mouse {
+            if self.mouse_motion {
+                try!(self.outbuffer.write_all(&self.driver.get(DevFn::ExitMouseMotion)));
+            }
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::ExitMouse)));
+            self.mouse = false;
         }
-        Ok(())
+        self.flush()
     }
 
-    fn send_char(&mut self, coord: Option<Pos>, ch: char) -> Result<(), Error> {
-        self.cursor.set_pos(coord);
-        if !self.cursor.is_seq() {
-            try!(self.send_cursor());
-        }
-        try!(write!(self.outbuffer, "{}", ch));
-        Ok(())
+    /// Returns `true` if mouse events are reported. See `enable_mouse()`.
+    pub fn mouse_enabled(&self) -> bool {
+        self.mouse
     }
 
-    fn send_clear(&mut self) -> Result<(), Error> {
-        try!(self.outbuffer.write_all(&self.driver.get(DevFn::Clear)));
-        try!(self.send_cursor());
-        try!(self.flush());
-        self.cursor.invalidate_last_pos();
-        Ok(())
+    /// Rings the terminal bell.
+    ///
+    /// The bell is sent along with the next call to `swap_buffers()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    ///
+    /// term.bell().unwrap();
+    /// term.swap_buffers().unwrap();
+    /// ```
+    pub fn bell(&mut self) -> Result<(), Error> {
+        self.outbuffer.write_all(&self.driver.get(DevFn::Bell))
     }
 
-    fn send_style(&mut self, cell: Cell) -> Result<(), Error> {
-        if cell.fg() != self.laststyle.fg() || cell.bg() != self.laststyle.bg() ||
-           cell.attrs() != self.laststyle.attrs() {
-            try!(self.outbuffer.write_all(&self.driver.get(DevFn::Reset)));
+    /// Shows `text` in the terminal's status line, or in the window title if the terminal has no
+    /// status line. Under screen and tmux this sets the title of the window or pane.
+    ///
+    /// Control characters in `text` are dropped, since they would end the status line early.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_status_line("editing notes.txt").unwrap();
+    /// ```
+    pub fn set_status_line(&mut self, text: &str) -> Result<(), Error> {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::ToStatusLine)));
+        try!(self.outbuffer.write_all(text.as_bytes()));
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::FromStatusLine)));
+        self.flush()
+    }
 
-            match cell.attrs() {
-                Attr::Bold => try!(self.outbuffer.write_all(&self.driver.get(DevFn::Bold))),
-                Attr::Underline => {
-                    try!(self.outbuffer.write_all(&self.driver.get(DevFn::Underline)))
-                }
-                Attr::Reverse => try!(self.outbuffer.write_all(&self.driver.get(DevFn::Reverse))),
-                _ => {}
-            }
+    /// Clears and hides the terminal's status line, or clears the window title if the terminal
+    /// has no status line.
+    pub fn clear_status_line(&mut self) -> Result<(), Error> {
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::DisableStatusLine)));
+        self.flush()
+    }
 
-            try!(self.write_sgr(cell.fg(), cell.bg()));
-            self.laststyle = cell;
+    /// Returns `true` if the terminal has a status line of its own, rather than falling back to
+    /// the window title.
+    pub fn has_status_line(&self) -> bool {
+        self.driver.has_status_line()
+    }
+
+    /// Copies `text` to the system clipboard with the OSC 52 escape sequence, which many terminals
+    /// support, including over SSH. Terminals without it ignore the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    /// use rustty::ui::{Clipboard, ClipboardBackend};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// let mut clipboard = Clipboard::with_backend(ClipboardBackend::Osc52);
+    ///
+    /// clipboard.copy("hello");
+    /// if let Some(text) = clipboard.take_export() {
+    ///     term.set_system_clipboard(text).unwrap();
+    /// }
+    /// ```
+    pub fn set_system_clipboard(&mut self, text: &str) -> Result<(), Error> {
+        try!(self.outbuffer.write_all(&osc52(text)));
+        self.flush()
+    }
+
+    /// Returns `true` if the terminal announces 24-bit colors, through `COLORTERM` or its terminfo
+    /// entry. Unlike `supports(Feature::Truecolor)`, which colors are downgraded by, this ignores
+    /// overrides made with `set_supported()`.
+    pub fn has_truecolor(&self) -> bool {
+        self.driver.has_truecolor()
+    }
+
+    /// Returns `true` if the terminal supports `feature`, for choosing fallbacks where it does not.
+    /// This is the same as `rustty::supported()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{Feature, Terminal};
+    ///
+    /// let term = Terminal::new().unwrap();
+    /// let emphasis = if term.supports(Feature::Italic) { "italic" } else { "underlined" };
+    /// ```
+    pub fn supports(&self, feature: Feature) -> bool {
+        features::supported(feature)
+    }
+
+    /// Redefines entry `index` of the terminal's own color palette, which `Color::Byte(index)` and,
+    /// for the first 16 entries, the named colors are displayed in, to the color `rgb`.
+    ///
+    /// Every cell on screen in that color changes at once. Redefined entries are restored to the
+    /// terminal's defaults when the `Terminal` is dropped. Terminals that cannot redefine their
+    /// palette ignore this.
+    ///
+    /// The entry is redefined along with the next call to `swap_buffers()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// // A softer red and green.
+    /// term.set_palette_color(1, (0xcc, 0x44, 0x44)).unwrap();
+    /// term.set_palette_color(2, (0x55, 0xaa, 0x55)).unwrap();
+    /// term.swap_buffers().unwrap();
+    /// ```
+    pub fn set_palette_color(&mut self, index: u8, rgb: (u8, u8, u8)) -> Result<(), Error> {
+        let (r, g, b) = rgb;
+        if !self.redefined_colors.contains(&index) {
+            self.redefined_colors.push(index);
+            self.update_watchdog();
+        }
+        self.outbuffer.write_all(&self.driver.get(DevFn::SetPaletteColor(index, r, g, b)))
+    }
+
+    /// Restores entry `index` of the terminal's color palette, redefined with
+    /// `set_palette_color()`, to the terminal's default.
+    pub fn reset_palette_color(&mut self, index: u8) -> Result<(), Error> {
+        self.redefined_colors.retain(|&i| i != index);
+        self.update_watchdog();
+        self.outbuffer.write_all(&self.driver.get(DevFn::ResetPaletteColor(index)))
+    }
+
+    /// Returns a report of what the terminal supports, for adapting the rendering to it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let term = Terminal::new().unwrap();
+    /// let caps = term.capabilities();
+    /// let scrollbar = if caps.line_drawing { '│' } else { '|' };
+    /// ```
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::detect(&self.driver, self.alt_screen)
+    }
+
+    /// Returns the palette that `Color::Slot(..)` colors are resolved through.
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Returns a mutable reference to the palette.
+    ///
+    /// Cells colored with palette slots or remapped base colors are redrawn in their new colors
+    /// on the next call to `swap_buffers()`, without having to be changed themselves. Use
+    /// `set_scheme()` to switch the whole display to a color-blind friendly scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{Terminal, Color};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.palette_mut().set(0, Color::Blue);
+    /// term.palette_mut().set(1, Color::Cyan);
+    /// term[(0, 0)].set_fg(Color::Slot(0));
+    /// term[(1, 0)].set_fg(Color::Slot(1));
+    /// term.swap_buffers().unwrap();
+    ///
+    /// // Swap the colors of the two cells.
+    /// term.palette_mut().cycle(0, 1);
+    /// term.swap_buffers().unwrap();
+    /// ```
+    pub fn palette_mut(&mut self) -> &mut Palette {
+        self.palette_dirty = true;
+        &mut self.palette
+    }
+
+    /// Gets an event from the event stream, waiting at most the value specified in `timeout`.
+    ///
+    /// Specifying a `timeout` of `None` causes `get_event()` to block indefinitely, while
+    /// specifying a `timeout` of zero causes `get_event()` to return immediately.
+    ///
+    /// Returns `Some(Event)` if an event was received within the specified timeout, or None
+    /// otherwise. When the window size changes the buffers are resized first, and an
+    /// `Event::Resize` with the new size is returned. Returns an error once after events were
+    /// discarded because the event buffer was full under `OverflowPolicy::Error`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::thread::sleep_ms;
+    /// use rustty::{Terminal, Event};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    ///
+    /// let evt = term.get_event(Some(Duration::from_secs(1))).unwrap();
+    /// ```
+    pub fn get_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>, Error> {
+        Ok(try!(self.get_timed_event(timeout)).map(|(evt, _)| evt))
+    }
+
+    /// Like `get_event()`, but also returns when the event was read from the terminal, or for
+    /// events that do not come from the terminal, when it was queued.
+    ///
+    /// The timestamps are taken as soon as input is read rather than when the application gets
+    /// to the event, which makes them suitable for detecting double presses and measuring input
+    /// latency.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::{Duration, Instant};
+    /// use rustty::{Event, Key, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// let mut last_esc: Option<Instant> = None;
+    /// while let Some((evt, at)) = term.get_timed_event(None).unwrap() {
+    ///     if evt == Event::Key(Key::Esc) {
+    ///         if last_esc.is_some_and(|last| at - last < Duration::from_millis(300)) {
+    ///             break; // Double press.
+    ///         }
+    ///         last_esc = Some(at);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_timed_event(&mut self,
+                           timeout: Option<Duration>)
+                           -> Result<Option<(Event, Instant)>, Error> {
+        if mem::replace(&mut self.overflowed, false) {
+            return Err(Error::other("event buffer overflowed"));
+        }
+        let deadline = timeout.map(|t| Instant::now() + t);
+        loop {
+            try!(self.feed_watchdog());
+            // Resize the buffers if the window size has changed, which queues a resize event.
+            if self.resize_due() {
+                try!(self.resize());
+            }
+            // Queue the ticks of timers and the replayed events that are due.
+            self.tick_timers();
+            self.queue_replayed();
+            // Return an event already in the buffer first.
+            if let Some(evt) = self.eventbuffer.pop_front() {
+                return Ok(Some(evt));
+            }
+            // Poll the terminal for events, waking up early if the idle threshold is reached, or
+            // held back input has to be decoded, a resize, a timer or a replayed event is due in
+            // the meantime.
+            let idle_in = self.idle_in();
+            let left = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let wait = [left,
+                        idle_in,
+                        self.parser.pending_in(),
+                        self.resize_in(),
+                        self.timer_in(),
+                        self.replayed_in()]
+                .iter()
+                .filter_map(|w| *w)
+                .min();
+            if let Some(ref watchdog) = self.watchdog {
+                watchdog.pause();
+            }
+            let nevts = try!(self.read_events(wait));
+            try!(self.feed_watchdog());
+            if nevts > 0 {
+                // Got at least one event from the terminal. Pop from the front of the event queue.
+                self.last_input = Instant::now();
+                self.idle_sent = false;
+                return Ok(self.eventbuffer.pop_front());
+            }
+            // No events from the terminal. Return an idle event if it is due, and none once the
+            // timeout has passed.
+            match self.idle_threshold {
+                Some(threshold) if idle_in.is_some() && self.idle_for() >= threshold => {
+                    self.idle_sent = true;
+                    return Ok(Some((Event::Idle(threshold), Instant::now())));
+                }
+                _ => {}
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return Ok(None);
+            }
         }
+    }
+
+    /// Appends an event to the event queue, to be returned by `get_event()` after any events
+    /// already queued.
+    ///
+    /// Injected events are indistinguishable from input, which makes them useful for replaying
+    /// recorded input and for driving an application from tests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{Event, Key, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.push_event(Event::Key(Key::Char('q')));
+    /// assert_eq!(term.get_event(None).unwrap(), Some(Event::Key(Key::Char('q'))));
+    /// ```
+    pub fn push_event(&mut self, evt: Event) {
+        self.eventbuffer.push_back((evt, Instant::now()));
+        self.limit_events();
+    }
+
+    /// Replays `events` with the timing they were originally received with, replacing any replay
+    /// in progress, to reproduce an interaction exactly, such as one a user reported a bug in.
+    ///
+    /// The events are typically collected with `get_timed_event()`. Each one is returned by
+    /// `get_event()` once the time between the first event and it has passed again, divided by
+    /// `speed`, so that `2.0` replays twice as fast. Input from the terminal is still read in the
+    /// meantime. `offscreen::replay()` replays without a terminal, such as in CI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `speed` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::{Duration, Instant};
+    /// use rustty::{Event, Key, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// let start = Instant::now();
+    /// let session = vec![(Event::Key(Key::Char('j')), start),
+    ///                    (Event::Key(Key::Char('j')), start + Duration::from_millis(400)),
+    ///                    (Event::Key(Key::Char('q')), start + Duration::from_secs(1))];
+    ///
+    /// term.replay(session, 2.0);
+    /// while let Some(evt) = term.get_event(None).unwrap() {
+    ///     if evt == Event::Key(Key::Char('q')) {
+    ///         break;
+    ///     }
+    ///     // Handle the event and redraw.
+    /// }
+    /// ```
+    pub fn replay<I>(&mut self, events: I, speed: f64)
+        where I: IntoIterator<Item = (Event, Instant)>
+    {
+        assert!(speed > 0.0, "replay speed must be positive");
+        let now = Instant::now();
+        let mut first = None;
+        self.replayed = events.into_iter()
+            .map(|(evt, at)| {
+                let offset = at.saturating_duration_since(*first.get_or_insert(at));
+                (evt, now + offset.div_f64(speed))
+            })
+            .collect();
+    }
+
+    /// Returns `true` while events passed to `replay()` are still due.
+    pub fn replaying(&self) -> bool {
+        !self.replayed.is_empty()
+    }
+
+    /// Returns a handle for sending events to the terminal from other threads.
+    pub fn event_sender(&self) -> EventSender {
+        EventQueue::sender(&self.sent)
+    }
+
+    /// Makes `get_event()` also wait for `fd` to become readable, returning `Event::Io(token)`
+    /// when it is. Registering a descriptor again replaces its token.
+    ///
+    /// The event is returned whenever the descriptor is readable, so the application should read
+    /// from it before waiting for the next event. Fails if `fd` is too large for `select()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    /// use std::os::unix::io::AsRawFd;
+    /// use rustty::{Event, Terminal};
+    ///
+    /// const SERVER: usize = 1;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    /// let mut term = Terminal::new().unwrap();
+    /// term.register_fd(listener.as_raw_fd(), SERVER).unwrap();
+    ///
+    /// if let Some(Event::Io(SERVER)) = term.get_event(None).unwrap() {
+    ///     let (mut stream, _) = listener.accept().unwrap();
+    ///     stream.write_all(b"hello\n").unwrap();
+    /// }
+    /// ```
+    pub fn register_fd(&mut self, fd: RawFd, token: usize) -> Result<(), Error> {
+        if fd < 0 || fd as usize >= libc::FD_SETSIZE {
+            return Err(Error::new(ErrorKind::InvalidInput, "file descriptor out of range"));
+        }
+        self.unregister_fd(fd);
+        self.watched.push((fd, token));
         Ok(())
     }
 
+    /// Stops waiting for `fd`. Returns `false` if it was not registered.
+    pub fn unregister_fd(&mut self, fd: RawFd) -> bool {
+        let len = self.watched.len();
+        self.watched.retain(|&(watched, _)| watched != fd);
+        self.watched.len() != len
+    }
+
+    /// Returns the number of events waiting in the event buffer.
+    ///
+    /// An application whose event buffer keeps growing is not handling events as fast as they
+    /// arrive.
+    pub fn pending_events(&self) -> usize {
+        self.eventbuffer.len()
+    }
+
+    /// Limits the event buffer to `capacity` events, handling any more according to `policy`,
+    /// or removes the limit if `capacity` is `None`, which is the default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{OverflowPolicy, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_event_capacity(Some(1024), OverflowPolicy::Coalesce);
+    /// ```
+    pub fn set_event_capacity(&mut self, capacity: Option<usize>, policy: OverflowPolicy) {
+        self.event_capacity = capacity.map(|c| (c, policy));
+        self.limit_events();
+    }
+
+    // Applies the overflow policy if the event buffer holds more events than its capacity.
+    fn limit_events(&mut self) {
+        let (capacity, policy) = match self.event_capacity {
+            Some((capacity, policy)) if self.eventbuffer.len() > capacity => (capacity, policy),
+            _ => return,
+        };
+        match policy {
+            OverflowPolicy::DropOldest => {}
+            OverflowPolicy::Coalesce => {
+                let mut last = None;
+                self.eventbuffer.retain(|(evt, _)| {
+                    let repeat = last.as_ref() == Some(evt);
+                    last = Some(evt.clone());
+                    !repeat
+                });
+            }
+            OverflowPolicy::Error => {
+                self.eventbuffer.truncate(capacity);
+                self.overflowed = true;
+            }
+        }
+        let excess = self.eventbuffer.len().saturating_sub(capacity);
+        self.eventbuffer.drain(..excess);
+    }
+
+    /// Returns the time since the last input was received from the terminal, or since the
+    /// terminal was created if there has been none.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// if term.idle_for() > Duration::from_secs(30) {
+    ///     // Autosave, dim the display, ...
+    /// }
+    /// ```
+    pub fn idle_for(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+
+    /// Returns how long the start of an escape sequence is held back waiting for the rest of it.
+    pub fn esc_timeout(&self) -> Duration {
+        self.parser.timeout()
+    }
+
+    /// Sets how long the start of an escape sequence is held back waiting for the rest of it,
+    /// 50 milliseconds by default.
+    ///
+    /// Pressing Esc sends the same byte that starts the sequences for keys such as the arrows,
+    /// so an Esc on its own is only reported as `Key::Esc` once nothing else has followed it for
+    /// the timeout. Longer timeouts suit slow connections, while shorter ones make Esc respond
+    /// faster.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_esc_timeout(Duration::from_millis(200));
+    /// ```
+    pub fn set_esc_timeout(&mut self, timeout: Duration) {
+        self.parser.set_timeout(timeout);
+    }
+
+    /// Enables or disables raw input, which is disabled by default.
+    ///
+    /// While enabled, input from the terminal is not decoded into keys, mouse reports and the
+    /// like, but returned by `get_event()` as `Event::Raw` with the bytes of each read, for
+    /// applications that parse a protocol of their own, such as replies to a custom query. The
+    /// terminal stays in the raw mode set up by rustty either way. Input held back waiting for
+    /// the rest of an escape sequence when raw input is enabled is returned as raw input first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::{Event, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_raw_input(true);
+    /// if let Some(Event::Raw(bytes)) = term.get_event(Some(Duration::from_secs(1))).unwrap() {
+    ///     // Parse the bytes.
+    /// }
+    /// term.set_raw_input(false);
+    /// ```
+    pub fn set_raw_input(&mut self, on: bool) {
+        if on && !self.raw_input && !self.inbuffer.is_empty() {
+            let held = self.inbuffer.take();
+            self.eventbuffer.push_back((Event::Raw(held), Instant::now()));
+            self.limit_events();
+        }
+        self.raw_input = on;
+    }
+
+    /// Sets whether the interrupt, quit and suspend keys, normally Ctrl-C, Ctrl-\ and Ctrl-Z,
+    /// generate signals, the way they do in cooked mode. Disabled by default, in which case they
+    /// are read as keys like any other.
+    ///
+    /// Everything else about the terminal stays raw. While this is enabled, the `Terminal` catches
+    /// `SIGINT`, `SIGTSTP` and `SIGCONT`, replacing any handlers of the program, and handles them
+    /// when events are next polled for: `SIGINT` is returned as `Event::Interrupt`, and on
+    /// `SIGTSTP` the terminal is restored and the program stopped. Once the program is continued,
+    /// the terminal is taken back and `Event::Resume` is returned, after which the buffers should
+    /// be swapped to redraw them. `SIGQUIT` keeps its default action of exiting without restoring
+    /// the terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{Event, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_signals(true).unwrap();
+    ///
+    /// loop {
+    ///     match term.get_event(None).unwrap() {
+    ///         Some(Event::Interrupt) => break,
+    ///         Some(Event::Resume) => term.swap_buffers().unwrap(),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn set_signals(&mut self, on: bool) -> Result<(), Error> {
+        let handler = if on {
+            // Wake up through the event queue, which is read along with the terminal.
+            SIGNAL_WAKE_FD.store(self.sent.wake_fd(), Ordering::SeqCst);
+            signal_handler as extern "C" fn(i32) as libc::sighandler_t
+        } else {
+            SIGNAL_WAKE_FD.store(-1, Ordering::SeqCst);
+            libc::SIG_DFL
+        };
+        for &signum in &CAUGHT_SIGNALS {
+            try!(set_signal_handler(signum, handler));
+        }
+        self.termctl.set_signals(on);
+        self.termctl.set()
+    }
+
+    /// Sets whether software flow control is left on, so that Ctrl-S and Ctrl-Q stop and
+    /// restart output as they do on serial terminals. Disabled by default, in which case they are
+    /// delivered as `Key::Char('\x13')` and `Key::Char('\x11')` like any other key.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// // Attached to a serial terminal that relies on XON/XOFF.
+    /// term.set_flow_control(true).unwrap();
+    /// ```
+    pub fn set_flow_control(&mut self, on: bool) -> Result<(), Error> {
+        self.termctl.set_flow_control(on);
+        self.termctl.set()
+    }
+
+    /// Returns `true` if raw input is enabled. See `set_raw_input()`.
+    pub fn raw_input(&self) -> bool {
+        self.raw_input
+    }
+
+    /// Reads input from `source` instead of the terminal, or from the terminal again if it is
+    /// `None`. Returns the previous source.
+    ///
+    /// Input typed into the terminal while another source is set is left unread until the
+    /// terminal is the source again. See `InputSource`.
+    ///
+    /// Returns an error, leaving the current source in place, if the file descriptor of
+    /// `source` is too large to wait for.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::net::TcpListener;
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// // Take the keys from a remote client, such as `nc localhost 7878`.
+    /// let (stream, _) = TcpListener::bind("127.0.0.1:7878").unwrap().accept().unwrap();
+    /// term.set_input_source(Some(Box::new(stream))).unwrap();
+    /// ```
+    pub fn set_input_source(&mut self,
+                            source: Option<Box<dyn InputSource>>)
+                            -> Result<Option<Box<dyn InputSource>>, Error> {
+        if let Some(ref source) = source {
+            let fd = source.fd();
+            if fd < 0 || fd as usize >= libc::FD_SETSIZE {
+                return Err(Error::new(ErrorKind::InvalidInput, "file descriptor out of range"));
+            }
+        }
+        Ok(mem::replace(&mut self.source, source))
+    }
+
+    /// Decodes the input sequence `seq` as `key`, overriding what the terminfo database and the
+    /// built-in xterm sequences say. Returns the key `seq` was decoded as before, if any.
+    ///
+    /// This lets an application fix the keys of terminals that send nonstandard sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::{Key, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.bind_key_sequence(b"\x1b[7~", Key::Home);
+    /// term.bind_key_sequence(b"\x1b[8~", Key::End);
+    /// ```
+    pub fn bind_key_sequence(&mut self, seq: &[u8], key: Key) -> Option<Key> {
+        self.parser.add_key(seq.to_vec(), key)
+    }
+
+    /// Stops decoding the input sequence `seq` as a key, returning the key it was decoded as.
+    pub fn unbind_key_sequence(&mut self, seq: &[u8]) -> Option<Key> {
+        self.parser.remove_key(seq)
+    }
+
+    /// Binds the key sequences listed in the file at `path` with `bind_key_sequence()`, returning
+    /// how many there are.
+    ///
+    /// Each line of the file holds a sequence and a key name separated by whitespace. Sequences
+    /// can contain `\e` for Esc, `\xHH` for any byte and `\\` for a backslash. Keys are named
+    /// as the variants of `Key`, as `F1` to `F255`, or as the character they stand for. Blank
+    /// lines and lines starting with `#` are ignored:
+    ///
+    /// ```text
+    /// # rxvt
+    /// \e[7~ Home
+    /// \e[8~ End
+    /// \e[3$ Delete
+    /// ```
+    ///
+    /// Returns an error of kind `InvalidData`, without binding anything, if a line is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustty::Terminal;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.load_key_sequences("keys.conf").unwrap();
+    /// ```
+    pub fn load_key_sequences<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Error> {
+        let mut table = String::new();
+        try!(try!(File::open(path)).read_to_string(&mut table));
+        self.parser.add_keys(&table)
+    }
+
+    /// Sets how long the terminal has to go without input before `get_event()` returns an
+    /// `Event::Idle`, or `None` to never send one, which is the default.
+    ///
+    /// One idle event is sent per idle period: after it, another is only sent once input has been
+    /// received and the terminal has gone idle again.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::{Event, Terminal};
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.set_idle_threshold(Some(Duration::from_secs(60)));
+    /// match term.get_event(None).unwrap() {
+    ///     Some(Event::Idle(_)) => { /* Blank the screen. */ }
+    ///     _ => {}
+    /// }
+    /// ```
+    pub fn set_idle_threshold(&mut self, threshold: Option<Duration>) {
+        self.idle_threshold = threshold;
+        self.idle_sent = false;
+    }
+
+    /// Starts a timer that makes `get_event()` return `Event::Tick(id)` every `interval`, the
+    /// first time one `interval` from now. Adding a timer with the id of an existing one replaces
+    /// it.
+    ///
+    /// Ticks are queued behind the events already waiting. A tick that falls due while an earlier
+    /// one is still waiting in the queue is skipped rather than queued twice, so a slow
+    /// application gets fewer ticks instead of a backlog.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use rustty::{Event, Key, Terminal};
+    ///
+    /// const STEP: usize = 0;
+    ///
+    /// let mut term = Terminal::new().unwrap();
+    /// term.add_timer(STEP, Duration::from_millis(100));
+    /// loop {
+    ///     match term.get_event(None).unwrap() {
+    ///         Some(Event::Tick(STEP)) => {
+    ///             // Advance the simulation and redraw.
+    ///             term.swap_buffers().unwrap();
+    ///         }
+    ///         Some(Event::Key(Key::Char('q'))) => break,
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn add_timer(&mut self, id: usize, interval: Duration) {
+        assert!(interval > Duration::from_secs(0), "timer interval must not be zero");
+        self.remove_timer(id);
+        self.timers.push((id, interval, Instant::now() + interval));
+    }
+
+    /// Stops the timer with the given id. Returns `false` if there was none.
+    pub fn remove_timer(&mut self, id: usize) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|&(timer, _, _)| timer != id);
+        self.timers.len() != len
+    }
+
+    // Queues a tick for each timer that is due and schedules its next one.
+    fn tick_timers(&mut self) {
+        let now = Instant::now();
+        for &mut (id, interval, ref mut next) in &mut self.timers {
+            if *next > now {
+                continue;
+            }
+            // Skip the ticks that were missed while the application was busy.
+            let missed = (now - *next).as_nanos() / interval.as_nanos() + 1;
+            *next += Duration::from_nanos((missed * interval.as_nanos()) as u64);
+            let tick = Event::Tick(id);
+            if !self.eventbuffer.iter().any(|(evt, _)| *evt == tick) {
+                self.eventbuffer.push_back((tick, now));
+            }
+        }
+        self.limit_events();
+    }
+
+    // Queues the replayed events that are due, stamped with the time they were due.
+    fn queue_replayed(&mut self) {
+        let now = Instant::now();
+        while self.replayed.front().is_some_and(|&(_, due)| due <= now) {
+            let replayed = self.replayed.pop_front().unwrap();
+            self.eventbuffer.push_back(replayed);
+        }
+        self.limit_events();
+    }
+
+    // Returns how long until the next replayed event is due, or `None` if there is none.
+    fn replayed_in(&self) -> Option<Duration> {
+        self.replayed.front().map(|&(_, due)| due.saturating_duration_since(Instant::now()))
+    }
+
+    // Returns how long until the next timer is due, or `None` if there are no timers.
+    fn timer_in(&self) -> Option<Duration> {
+        self.timers.iter().map(|&(_, _, next)| next.saturating_duration_since(Instant::now())).min()
+    }
+
+    // Returns how long until an idle event is due, or `None` if none is.
+    fn idle_in(&self) -> Option<Duration> {
+        match self.idle_threshold {
+            Some(threshold) if !self.idle_sent => Some(threshold.saturating_sub(self.idle_for())),
+            _ => None,
+        }
+    }
+
+    fn send_cursor(&mut self) -> Result<(), Error> {
+        if let Some((cx, cy)) = self.cursor.pos() {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetCursor(cx, cy))));
+        }
+        Ok(())
+    }
+
+    fn send_char(&mut self, coord: Option<Pos>, ch: char) -> Result<(), Error> {
+        self.cursor.set_pos(coord);
+        if !self.cursor.is_seq() {
+            try!(self.send_cursor());
+        }
+        let glyph = match self.acs {
+            Some(ref acs) if !ch.is_ascii() => acs.iter().find(|&&(c, _)| c == ch).map(|g| g.1),
+            _ => None,
+        };
+        match glyph {
+            Some(byte) => {
+                if !self.acs_on {
+                    try!(self.outbuffer.write_all(&self.driver.get(DevFn::EnterAcs)));
+                    self.acs_on = true;
+                }
+                try!(self.outbuffer.write_all(&[byte]));
+            }
+            None => {
+                try!(self.exit_acs());
+                try!(write!(self.outbuffer, "{}", ch));
+            }
+        }
+        Ok(())
+    }
+
+    // Switches back from the alternate character set if it is selected.
+    fn exit_acs(&mut self) -> Result<(), Error> {
+        if self.acs_on {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::ExitAcs)));
+            self.acs_on = false;
+        }
+        Ok(())
+    }
+
+    fn send_clear(&mut self) -> Result<(), Error> {
+        try!(self.outbuffer.write_all(&self.driver.get(DevFn::Clear)));
+        try!(self.send_cursor());
+        try!(self.flush());
+        self.cursor.invalidate_last_pos();
+        Ok(())
+    }
+
+    // Sends the style of `cell`, recording the attributes that cannot be displayed if it is drawn
+    // at `pos`.
+    fn send_style(&mut self, pos: Option<Pos>, mut cell: Cell) -> Result<(), Error> {
+        let (fg, bg) = (self.palette.resolve(cell.fg()), self.palette.resolve(cell.bg()));
+        let underline = self.palette.resolve(cell.underline_color());
+        cell.set_fg(fg).set_bg(bg).set_underline_color(underline);
+        let last = self.laststyle;
+        if cell.fg() != last.fg() || cell.bg() != last.bg() || cell.attrs() != last.attrs() ||
+           cell.underline_style() != last.underline_style() ||
+           cell.underline_color() != last.underline_color() {
+            // Resetting the style may also reset the character set.
+            try!(self.exit_acs());
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::Reset)));
+
+            let styles = [(Attr::Bold, DevFn::Bold),
+                          (Attr::Dim, DevFn::Dim),
+                          (Attr::Italic, DevFn::Italic),
+                          (Attr::Underline, DevFn::Underline),
+                          (Attr::Blink, DevFn::Blink),
+                          (Attr::Reverse, DevFn::Reverse),
+                          (Attr::Strikethrough, DevFn::Strikethrough)];
+            for &(attr, func) in &styles {
+                if !cell.attrs().contains(attr) {
+                    continue;
+                }
+                let seq = self.driver.get(func);
+                if seq.is_empty() {
+                    // Only the optional attributes can be missing, and are left out.
+                    if let Some(pos) = pos {
+                        self.diagnose(Diagnostic::MissingCapability { pos, attr });
+                    }
+                }
+                try!(self.outbuffer.write_all(&seq));
+            }
+
+            try!(self.write_sgr(cell.fg(), cell.bg()));
+            if cell.attrs().contains(Attr::Underline) {
+                try!(self.write_underline(cell.underline_style(), cell.underline_color()));
+            }
+            self.laststyle = cell;
+        }
+        Ok(())
+    }
+
+    // Writes the style and color of the underline, which terminals without
+    // `Feature::StyledUnderline` draw as a single underline in the foreground color.
+    fn write_underline(&mut self, style: UnderlineStyle, color: Color) -> Result<(), Error> {
+        if !features::supported(Feature::StyledUnderline) {
+            return Ok(());
+        }
+        if style != UnderlineStyle::Single {
+            try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetUnderlineStyle(style as u8))));
+        }
+        let func = match color {
+            Color::Default => return Ok(()),
+            Color::Rgb(r, g, b) if features::supported(Feature::Truecolor) => {
+                DevFn::SetUnderlineRgb(r, g, b)
+            }
+            color => DevFn::SetUnderlineColor(color.as_byte()),
+        };
+        self.outbuffer.write_all(&self.driver.get(func))
+    }
+
     fn write_sgr(&mut self, fgcol: Color, bgcol: Color) -> Result<(), Error> {
+        let truecolor = features::supported(Feature::Truecolor);
         match fgcol {
             Color::Default => {}
+            Color::Rgb(r, g, b) if truecolor => {
+                try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetFgRgb(r, g, b))));
+            }
             fgc @ _ => {
                 try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetFg(fgc.as_byte()))));
             }
         }
         match bgcol {
             Color::Default => {}
+            Color::Rgb(r, g, b) if truecolor => {
+                try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetBgRgb(r, g, b))));
+            }
             bgc @ _ => {
                 try!(self.outbuffer.write_all(&self.driver.get(DevFn::SetBg(bgc.as_byte()))));
             }
@@ -536,8 +2037,11 @@ impl Terminal {
         Ok(())
     }
 
+    // Resizes the buffers after a window size change and queues a resize event.
     fn resize(&mut self) -> Result<(), Error> {
-        self.resize_with_cell(Cell::default())
+        try!(self.resize_with_cell(Cell::default()));
+        self.push_event(Event::Resize((self.cols, self.rows)));
+        Ok(())
     }
 
     /// Updates the size of the Terminal object to reflect that of the underlying terminal.
@@ -546,9 +2050,10 @@ impl Terminal {
         self.cols = cols;
         self.rows = rows;
         self.backbuffer.resize(self.cols, self.rows, blank);
-        self.frontbuffer.resize(self.cols, self.rows, blank);
-        self.frontbuffer.clear(blank);
-        try!(self.send_style(blank));
+        let frontbuffer = Arc::make_mut(&mut self.frontbuffer);
+        frontbuffer.resize(self.cols, self.rows, blank);
+        frontbuffer.clear(blank);
+        try!(self.send_style(None, blank));
         try!(self.send_clear());
         Ok(())
     }
@@ -566,12 +2071,23 @@ impl Terminal {
                 tv_usec: (timeout.subsec_nanos() as libc::suseconds_t) / 1000,
             }
         };
-        let rawfd = self.tty.as_raw_fd();
-        let nfds = rawfd + 1;
+        let rawfd = match self.source {
+            Some(ref source) => source.fd(),
+            None => self.tty.as_raw_fd(),
+        };
+        let sentfd = self.sent.fd();
+        let mut nfds = cmp::max(rawfd, sentfd) + 1;
 
+        // Wait for input from the terminal, for events sent from other threads and for the
+        // registered file descriptors.
         let mut rfds: libc::fd_set = unsafe { mem::zeroed() };
         unsafe {
             libc::FD_SET(rawfd, &mut rfds);
+            libc::FD_SET(sentfd, &mut rfds);
+        }
+        for &(fd, _) in &self.watched {
+            unsafe { libc::FD_SET(fd, &mut rfds) };
+            nfds = cmp::max(nfds, fd + 1);
         }
 
         // Because the sigwinch handler will interrupt select, if select returns EINTR we loop
@@ -589,6 +2105,10 @@ impl Terminal {
                 let err = Error::last_os_error();
 
                 if err.kind() == ErrorKind::Interrupted {
+                    if SIGWINCH_STATUS.load(Ordering::SeqCst) {
+                        // The window has been resized. Return to the caller to handle it.
+                        return Ok(0);
+                    }
                     // Errno is EINTR, loop and try again.
                     continue;
                 } else {
@@ -601,32 +2121,65 @@ impl Terminal {
             }
         }
 
-        if nevts == 0 {
-            // No input available. Return None.
-            Ok(0)
+        // The time spent waiting is left out of the span.
+        trace_span!("read_events");
+        // Collect the events first so that they can all be stamped with the time they were read.
+        let mut events = VecDeque::new();
+        if nevts > 0 && unsafe { libc::FD_ISSET(sentfd, &rfds) } {
+            self.sent.receive(&mut events);
+        }
+        try!(self.handle_signals(&mut events));
+        if nevts > 0 {
+            for &(fd, token) in &self.watched {
+                if unsafe { libc::FD_ISSET(fd, &rfds) } {
+                    events.push_back(Event::Io(token));
+                }
+            }
+        }
+
+        if nevts == 0 || !unsafe { libc::FD_ISSET(rawfd, &rfds) } {
+            // No input available. Decode held back input if it has waited long enough.
+            self.parser.expire(&mut self.inbuffer, &mut events);
         } else {
-            // Input is available from the terminal.
-            // Get an iterator of chars over the input stream.
-            let mut buf = String::new();
-            try!(self.tty.read_to_string(&mut buf));
-            let mut n = 0;
-            for ch in buf.chars() {
-                // Push each character onto the event queue and increment the count.
-                self.eventbuffer.push_back(Event::Key(ch));
-                n += 1;
+            // Input is available from the terminal. Read all of it into the input buffer, after
+            // any incomplete sequence left over from the last read.
+            loop {
+                let (source, tty) = (&mut self.source, &mut self.tty);
+                let read = self.inbuffer.fill(|buf| {
+                    match *source {
+                        Some(ref mut source) => source.read(buf),
+                        None => tty.read(buf),
+                    }
+                });
+                match read {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            if self.raw_input {
+                if !self.inbuffer.is_empty() {
+                    events.push_back(Event::Raw(self.inbuffer.take()));
+                }
+            } else {
+                self.parser.parse(&mut self.inbuffer, &mut events);
             }
-            Ok(n)
         }
+
+        let now = Instant::now();
+        let n = events.len();
+        trace_count!("events_read", n);
+        self.eventbuffer.extend(events.into_iter().map(|evt| (evt, now)));
+        self.limit_events();
+        Ok(n)
     }
 
     fn flush(&mut self) -> Result<(), Error> {
+        trace_span!("flush");
+        trace_count!("bytes_written", self.outbuffer.len());
         try!(self.tty.write_all(&self.outbuffer));
         self.outbuffer.clear();
-        if thread::panicking() {
-            let mut error = String::new();
-            self.stderr_handle.read_to_string(&mut error).unwrap();
-            print!("{}", error);
-        }
         Ok(())
     }
 }
@@ -688,17 +2241,137 @@ impl IndexMut<Pos> for Terminal {
 
 impl Drop for Terminal {
     fn drop(&mut self) {
+        self.watchdog = None;
+        let mut report = String::new();
+        if thread::panicking() {
+            let _ = self.stderr_handle.read_to_string(&mut report);
+            // The report waits for a key, which only the terminal itself is known to deliver.
+            if self.panic_screen && self.source.is_none() {
+                // Failing to show the report must not panic again, which would abort.
+                let _ = self.show_panic(&report);
+            }
+        }
+        // Drawing over the screen, put back what was there.
+        let restore = !self.alt_screen && self.backdrop.is_some();
+        if restore {
+            // Without refresh hooks, and ignoring errors, since a panic now could abort.
+            let _ = self.clear_to_backdrop().and_then(|_| self.draw_frame());
+        }
+        self.outbuffer.write_all(&self.driver.get(DevFn::ExitPaste)).unwrap();
+        self.outbuffer.write_all(&self.driver.get(DevFn::ExitFocus)).unwrap();
+        if self.mouse {
+            if self.mouse_motion {
+                self.outbuffer.write_all(&self.driver.get(DevFn::ExitMouseMotion)).unwrap();
+            }
+            self.outbuffer.write_all(&self.driver.get(DevFn::ExitMouse)).unwrap();
+        }
         self.outbuffer.write_all(&self.driver.get(DevFn::ShowCursor)).unwrap();
+        for &index in &self.redefined_colors {
+            self.outbuffer.write_all(&self.driver.get(DevFn::ResetPaletteColor(index))).unwrap();
+        }
         self.outbuffer.write_all(&self.driver.get(DevFn::Reset)).unwrap();
-        self.outbuffer.write_all(&self.driver.get(DevFn::Clear)).unwrap();
-        self.outbuffer.write_all(&self.driver.get(DevFn::ExitCa)).unwrap();
+        if !restore {
+            self.outbuffer.write_all(&self.driver.get(DevFn::Clear)).unwrap();
+        }
+        if self.alt_screen {
+            self.outbuffer.write_all(&self.driver.get(DevFn::ExitCa)).unwrap();
+        }
         self.flush().unwrap();
         self.termctl.reset().unwrap();
+        for cells in &self.exit_prints {
+            self.tty.write_all(offscreen::ansi(cells).as_bytes()).unwrap();
+        }
+        print!("{}", report);
+        if SIGNAL_WAKE_FD.swap(-1, Ordering::SeqCst) >= 0 {
+            for &signum in &CAUGHT_SIGNALS {
+                let _ = set_signal_handler(signum, libc::SIG_DFL);
+            }
+            SIGNAL_STATUS.store(0, Ordering::SeqCst);
+        }
+        self.sent.close();
         SIGWINCH_STATUS.store(false, Ordering::SeqCst);
         RUSTTY_STATUS.store(false, Ordering::SeqCst);
     }
 }
 
+/// Returns `true` if a `Terminal` can be constructed: the process has a controlling terminal
+/// with the capabilities rustty needs, standard output goes to a terminal, and no other
+/// `Terminal` exists.
+///
+/// Programs that may run without a terminal, such as under cron or with their output piped to a
+/// file, can check this before `Terminal::new()` and fall back to drawing into a `CellBuffer` and
+/// printing the final frame as plain text with `offscreen::print()`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::{offscreen, CellBuffer, Cell, Terminal};
+/// use rustty::ui::{Painter, Widget};
+///
+/// let mut w = Widget::new(20, 1);
+/// w.printline(0, 0, "42 files processed");
+///
+/// if rustty::can_init() {
+///     let mut term = Terminal::new().unwrap();
+///     w.draw_into(&mut term);
+///     term.swap_buffers().unwrap();
+/// } else {
+///     let mut buf = CellBuffer::new(20, 1, Cell::default());
+///     w.draw_into(&mut buf);
+///     offscreen::print(&buf).unwrap();
+/// }
+/// ```
+pub fn can_init() -> bool {
+    !RUSTTY_STATUS.load(Ordering::SeqCst) && Terminal::is_tty() && Driver::new().is_ok()
+}
+
+/// Requests that the bell is rung on the next buffer swap of the current `Terminal`.
+pub fn request_bell() {
+    BELL_STATUS.store(true, Ordering::SeqCst);
+}
+
+// Returns the OSC 52 sequence setting the system clipboard to `text`.
+fn osc52(text: &str) -> Vec<u8> {
+    const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut seq = b"\x1b]52;c;".to_vec();
+    for chunk in text.as_bytes().chunks(3) {
+        let bits = chunk.iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &b)| bits | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                seq.push(BASE64[((bits >> (18 - 6 * i)) & 0x3f) as usize]);
+            } else {
+                seq.push(b'=');
+            }
+        }
+    }
+    seq.push(b'\x07');
+    seq
+}
+
+// Sets the handler of signal `signum`, or its action if `handler` is `SIG_DFL` or `SIG_IGN`.
+fn set_signal_handler(signum: libc::c_int, handler: libc::sighandler_t) -> Result<(), Error> {
+    let mut action: libc::sigaction = unsafe { mem::zeroed() };
+    action.sa_sigaction = handler;
+    if unsafe { libc::sigaction(signum, &action, ptr::null_mut()) } != 0 {
+        return Err(Error::last_os_error());
+    }
+    Ok(())
+}
+
+// Signal handler for the signals caught while enabled with `set_signals()`, which are handled
+// the next time events are read.
+extern "C" fn signal_handler(signum: i32) {
+    SIGNAL_STATUS.fetch_or(1 << signum, Ordering::SeqCst);
+    let fd = SIGNAL_WAKE_FD.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // The write can only fail if the pipe is full, in which case it is readable already.
+        let byte = [0u8];
+        unsafe { libc::write(fd, byte.as_ptr() as *const libc::c_void, 1) };
+    }
+}
+
 // Sigwinch handler to notify when window has resized.
 extern "C" fn sigwinch_handler(_: i32) {
     SIGWINCH_STATUS.store(true, Ordering::SeqCst);
@@ -0,0 +1,33 @@
+// Instrumentation of the hot paths, compiled to nothing unless the `tracing` feature is enabled.
+//
+// Spans are entered at the `trace` level under the `rustty` target and last until the end of the
+// enclosing block. Counters are events at the same level with `counter` and `value` fields.
+
+// Enters a span named `$name` for the rest of the enclosing block.
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($name:expr) => {
+        let _span = ::tracing::trace_span!(target: "rustty", $name).entered();
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($name:expr) => {};
+}
+
+// Adds `$value` to the counter `$name`.
+#[cfg(feature = "tracing")]
+macro_rules! trace_count {
+    ($name:expr, $value:expr) => {
+        let value = $value as u64;
+        ::tracing::trace!(target: "rustty", counter = $name, value = value);
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_count {
+    ($name:expr, $value:expr) => {
+        let _ = $value;
+    };
+}
@@ -0,0 +1,123 @@
+use std::io::{Error, ErrorKind};
+use std::os::unix::io::RawFd;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use libc;
+
+use core::termctl::TermCtl;
+
+// A thread restoring the terminal when a `Terminal` is neither refreshed nor polled for longer
+// than a timeout, see `Terminal::set_watchdog()`.
+pub struct Watchdog {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+struct State {
+    last: Instant, // When the watchdog was last fed.
+    waiting: bool, // Whether the `Terminal` is waiting for input, which is not hanging.
+    fired: bool, // Whether the terminal was restored since the watchdog was last fed.
+    stopped: bool,
+    restore: Arc<Vec<u8>>, // Written to the terminal when firing.
+}
+
+impl Watchdog {
+    // Starts a watchdog that resets the terminal mode with `termctl` and writes `restore` to
+    // `fd`, followed by a warning, once it has not been fed for `timeout`.
+    pub fn start(timeout: Duration, termctl: TermCtl, fd: RawFd, restore: Vec<u8>) -> Watchdog {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                last: Instant::now(),
+                waiting: false,
+                fired: false,
+                stopped: false,
+                restore: Arc::new(restore),
+            }),
+            wake: Condvar::new(),
+        });
+        let watched = shared.clone();
+        let thread = thread::spawn(move || {
+            let mut state = watched.state.lock().unwrap();
+            loop {
+                if state.stopped {
+                    return;
+                }
+                if state.waiting || state.fired {
+                    state = watched.wake.wait(state).unwrap();
+                    continue;
+                }
+                let elapsed = state.last.elapsed();
+                if elapsed < timeout {
+                    state = watched.wake.wait_timeout(state, timeout - elapsed).unwrap().0;
+                    continue;
+                }
+                // Release the lock before touching the terminal, so that a program recovering
+                // meanwhile is not blocked feeding the watchdog by a slow write.
+                state.fired = true;
+                let restore = state.restore.clone();
+                drop(state);
+                let _ = termctl.reset();
+                let warning = format!("\nrustty: the program has not responded for {:?}, the \
+                                       terminal was restored\n",
+                                      timeout);
+                write_all(fd, &restore);
+                write_all(fd, warning.as_bytes());
+                state = watched.state.lock().unwrap();
+            }
+        });
+        Watchdog {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    // Feeds the watchdog, returning `true` if it restored the terminal since it was last fed.
+    pub fn feed(&self) -> bool {
+        let mut state = self.shared.state.lock().unwrap();
+        state.last = Instant::now();
+        state.waiting = false;
+        let fired = state.fired;
+        state.fired = false;
+        self.shared.wake.notify_one();
+        fired
+    }
+
+    // Replaces what is written to the terminal when the watchdog fires, as its modes change.
+    pub fn set_restore(&self, restore: Vec<u8>) {
+        self.shared.state.lock().unwrap().restore = Arc::new(restore);
+    }
+
+    // Pauses the watchdog until it is fed again, while waiting for input.
+    pub fn pause(&self) {
+        self.shared.state.lock().unwrap().waiting = true;
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().stopped = true;
+        self.shared.wake.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Writes `buf` to `fd`, giving up on errors other than interruptions.
+fn write_all(fd: RawFd, mut buf: &[u8]) {
+    while !buf.is_empty() {
+        let res = unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) };
+        if res > 0 {
+            buf = &buf[res as usize..];
+        } else if res == 0 || Error::last_os_error().kind() != ErrorKind::Interrupted {
+            return;
+        }
+    }
+}
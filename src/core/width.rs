@@ -0,0 +1,188 @@
+use std::env;
+use std::str::CharIndices;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use unicode_width::UnicodeWidthChar;
+
+use core::cellbuffer::{Cell, CellAccessor};
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// The width policy in effect, `0` until it is set or detected from the locale.
+static WIDTH_POLICY: AtomicUsize = AtomicUsize::new(0);
+
+/// How wide characters of ambiguous East Asian width, such as `±` or `○`, are displayed.
+///
+/// Terminals disagree on this: most display them in one column, but terminals set up for
+/// Chinese, Japanese or Korean often use two. Layout goes wrong wherever rustty's idea differs from
+/// the terminal's, so the policy should match the terminal. It applies to printing, alignment and
+/// measuring throughout the crate.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::WidthPolicy;
+///
+/// assert_eq!(WidthPolicy::for_locale("ja_JP.UTF-8"), WidthPolicy::Wide);
+/// assert_eq!(WidthPolicy::for_locale("en_US.UTF-8"), WidthPolicy::Narrow);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum WidthPolicy {
+    /// Ambiguous characters take one column.
+    #[default]
+    Narrow,
+    /// Ambiguous characters take two columns.
+    Wide,
+}
+
+impl WidthPolicy {
+    /// Returns the policy usual for terminals in the given locale, such as `zh_CN.UTF-8`: `Wide`
+    /// for Chinese, Japanese and Korean and `Narrow` otherwise.
+    pub fn for_locale(locale: &str) -> WidthPolicy {
+        let lang = locale.split(['_', '.', '@']).next().unwrap_or("");
+        match lang {
+            "zh" | "ja" | "ko" => WidthPolicy::Wide,
+            _ => WidthPolicy::Narrow,
+        }
+    }
+
+    /// Returns the policy for the locale of the process, taken from the first of `LC_ALL`,
+    /// `LC_CTYPE` and `LANG` that is set.
+    pub fn from_env() -> WidthPolicy {
+        locale().map_or(WidthPolicy::Narrow, |locale| WidthPolicy::for_locale(&locale))
+    }
+}
+
+// Returns the locale of the process for character handling, taken from the first of `LC_ALL`,
+// `LC_CTYPE` and `LANG` that is set.
+pub fn locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+}
+
+/// Sets the width policy used by the whole crate.
+pub fn set_width_policy(policy: WidthPolicy) {
+    let value = match policy {
+        WidthPolicy::Narrow => 1,
+        WidthPolicy::Wide => 2,
+    };
+    WIDTH_POLICY.store(value, Ordering::SeqCst);
+}
+
+/// Returns the width policy used by the crate. Until one is set with `set_width_policy()` it is
+/// taken from the locale, see `WidthPolicy::from_env()`.
+pub fn width_policy() -> WidthPolicy {
+    match WIDTH_POLICY.load(Ordering::SeqCst) {
+        1 => WidthPolicy::Narrow,
+        2 => WidthPolicy::Wide,
+        _ => {
+            let policy = WidthPolicy::from_env();
+            set_width_policy(policy);
+            policy
+        }
+    }
+}
+
+/// Returns the number of columns `ch` takes up under the current width policy. Control
+/// characters take none.
+pub fn char_width(ch: char) -> usize {
+    let width = match width_policy() {
+        WidthPolicy::Narrow => ch.width(),
+        WidthPolicy::Wide => ch.width_cjk(),
+    };
+    width.unwrap_or(0)
+}
+
+/// Returns the cell of `cells` at (`x`, `y`) as it is displayed: the halves of double-width
+/// characters whose other half was overwritten are blank.
+pub fn display_cell<C: CellAccessor + ?Sized>(cells: &C, x: usize, y: usize) -> Cell {
+    let (cols, _) = cells.size();
+    let cell = *cells.get(x, y).unwrap();
+    let whole = if cell.is_continuation() {
+        x > 0 && char_width(cells.get(x - 1, y).unwrap().ch()) > 1
+    } else if char_width(cell.ch()) > 1 && x + 1 < cols {
+        cells.get(x + 1, y).unwrap().is_continuation()
+    } else {
+        true
+    };
+    if whole { cell } else { cell.with_ch(' ') }
+}
+
+/// Returns the number of columns `text` takes up under the current width policy.
+pub fn display_width(text: &str) -> usize {
+    graphemes(text).map(grapheme_width).sum()
+}
+
+/// Returns the longest start of `text` that fits in `cols` columns, without splitting
+/// characters.
+pub fn truncate_to_width(text: &str, cols: usize) -> &str {
+    let mut width = 0;
+    let mut end = 0;
+    for grapheme in graphemes(text) {
+        width += grapheme_width(grapheme);
+        if width > cols {
+            break;
+        }
+        end += grapheme.len();
+    }
+    &text[..end]
+}
+
+/// Returns the number of columns a user-perceived character from `graphemes()` takes up, which
+/// is the width of its first `char`.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.chars().next().map_or(0, char_width)
+}
+
+/// Returns an iterator over the user-perceived characters of `text`.
+///
+/// A character is a base character together with the combining marks and variation selectors
+/// following it, and any characters joined to it with zero width joiners, as in emoji sequences.
+/// This approximates Unicode grapheme clusters closely enough for measuring text, which counts
+/// the width of each character's first `char`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::text;
+///
+/// let coder = "\u{1F469}\u{200D}\u{1F4BB}";
+/// assert_eq!(text::graphemes(coder).count(), 1);
+/// assert_eq!(text::display_width(coder), 2);
+/// ```
+pub fn graphemes(text: &str) -> Graphemes<'_> {
+    Graphemes {
+        text,
+        chars: text.char_indices(),
+    }
+}
+
+/// An iterator over the user-perceived characters of a string, see `graphemes()`.
+pub struct Graphemes<'a> {
+    text: &'a str,
+    chars: CharIndices<'a>,
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let (start, first) = self.chars.next()?;
+        let mut end = start + first.len_utf8();
+        if !first.is_control() {
+            let mut joined = false;
+            let mut rest = self.chars.clone();
+            while let Some((i, ch)) = rest.next() {
+                if ch.is_control() || !(joined || char_width(ch) == 0) {
+                    break;
+                }
+                joined = ch == ZERO_WIDTH_JOINER;
+                end = i + ch.len_utf8();
+                self.chars = rest.clone();
+            }
+        }
+        Some(&self.text[start..end])
+    }
+}
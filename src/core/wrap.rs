@@ -0,0 +1,250 @@
+use core::cellbuffer::{Cell, CellAccessor};
+use core::width;
+
+/// Columns between tab stops, by default.
+const TAB_WIDTH: usize = 8;
+
+/// Maps positions in a soft-wrapped document between logical `(line, column)` positions and
+/// on-screen `(x, y)` cells.
+///
+/// Lines longer than the width continue on the following rows, breaking between characters. A
+/// column counts user-perceived characters, see `text::graphemes()`, and a line has one column
+/// more than it has characters, for the cursor at its end. Characters take up their display
+/// width, a double-width character that does not fit at the end of a row moves to the next one,
+/// and a tab advances to the next tab stop of the row, or to its end. The cursor at the end of a
+/// line that fills its last row goes on a row of its own, which counts as part of the line.
+///
+/// Editors keep the map up to date as lines change, and draw the text with `draw()` so that it
+/// lands exactly where the map says.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::text::WrapMap;
+///
+/// let mut map = WrapMap::new(vec!["hello world", "日本語"], 4);
+/// assert_eq!(map.rows(), 5);
+/// assert_eq!(map.to_screen(0, 6), Some((2, 1)));
+/// assert_eq!(map.to_logical(2, 1), Some((0, 6)));
+/// // The second line starts on the fourth row, and `語` does not fit after `日本`.
+/// assert_eq!(map.to_screen(1, 2), Some((0, 4)));
+///
+/// map.set_line(0, "hi");
+/// assert_eq!(map.to_screen(1, 0), Some((0, 1)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WrapMap {
+    width: usize,
+    tab_width: usize,
+    lines: Vec<Line>,
+}
+
+// A logical line and the screen position of each of its characters, relative to its first row.
+#[derive(Debug, Clone)]
+struct Line {
+    text: String,
+    // The `(x, row, width)` of each character, followed by the cursor position at the end.
+    cells: Vec<(usize, usize, usize)>,
+    rows: usize,
+}
+
+impl WrapMap {
+    /// Constructs a map of `lines` wrapped at `width` columns.
+    pub fn new<I, S>(lines: I, width: usize) -> WrapMap
+        where I: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        let mut map = WrapMap {
+            width: width.max(1),
+            tab_width: TAB_WIDTH,
+            lines: Vec::new(),
+        };
+        map.lines = lines.into_iter().map(|text| map.layout(text.into())).collect();
+        map
+    }
+
+    /// Returns the width the lines are wrapped at.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Wraps the lines at `width` columns instead.
+    pub fn set_width(&mut self, width: usize) {
+        self.width = width.max(1);
+        self.relayout();
+    }
+
+    /// Returns the number of columns between tab stops, 8 by default.
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// Sets the number of columns between tab stops.
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = tab_width.max(1);
+        self.relayout();
+    }
+
+    /// Returns the number of logical lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if there are no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the text of a line.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        self.lines.get(line).map(|l| &l.text[..])
+    }
+
+    /// Replaces the text of a line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is out of bounds.
+    pub fn set_line<S: Into<String>>(&mut self, line: usize, text: S) {
+        self.lines[line] = self.layout(text.into());
+    }
+
+    /// Inserts a line before `line`, which may be the number of lines to append it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is greater than the number of lines.
+    pub fn insert_line<S: Into<String>>(&mut self, line: usize, text: S) {
+        let layout = self.layout(text.into());
+        self.lines.insert(line, layout);
+    }
+
+    /// Removes a line and returns its text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is out of bounds.
+    pub fn remove_line(&mut self, line: usize) -> String {
+        self.lines.remove(line).text
+    }
+
+    /// Returns the total number of rows the lines take up on screen.
+    pub fn rows(&self) -> usize {
+        self.lines.iter().map(|l| l.rows).sum()
+    }
+
+    /// Returns the number of rows a line takes up on screen.
+    pub fn line_rows(&self, line: usize) -> Option<usize> {
+        self.lines.get(line).map(|l| l.rows)
+    }
+
+    /// Returns the screen row a line starts on.
+    pub fn first_row(&self, line: usize) -> Option<usize> {
+        if line < self.lines.len() {
+            Some(self.lines[..line].iter().map(|l| l.rows).sum())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the screen cell of the character at `column` of `line`, or of the cursor at the
+    /// end of the line if `column` is the number of characters. Returns `None` if either is out
+    /// of bounds.
+    pub fn to_screen(&self, line: usize, column: usize) -> Option<(usize, usize)> {
+        let first = self.first_row(line)?;
+        self.lines[line].cells.get(column).map(|&(x, row, _)| (x, first + row))
+    }
+
+    /// Returns the logical position of the character at a screen cell.
+    ///
+    /// A cell past the last character of a row maps to that character, or to the end of the line
+    /// on its last row, so that clicking to the right of the text puts the cursor there. Returns
+    /// `None` if `y` is past the last row.
+    pub fn to_logical(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let mut first = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            if y < first + line.rows {
+                let row = y - first;
+                let mut last = None;
+                for (column, &(cx, crow, width)) in line.cells.iter().enumerate() {
+                    if crow != row {
+                        continue;
+                    }
+                    if x < cx + width.max(1) {
+                        return Some((index, column));
+                    }
+                    last = Some(column);
+                }
+                return last.map(|column| (index, column));
+            }
+            first += line.rows;
+        }
+        None
+    }
+
+    /// Draws the rows from screen row `top` on into `cells`, starting at their top-left corner,
+    /// in the style of `cell`. Tabs are drawn as `cell` itself, which is normally blank.
+    pub fn draw(&self, cells: &mut dyn CellAccessor, top: usize, cell: Cell) {
+        let mut first = 0;
+        for line in &self.lines {
+            if first + line.rows > top {
+                for (grapheme, &(x, row, width)) in width::graphemes(&line.text).zip(&line.cells) {
+                    if width == 0 || first + row < top {
+                        continue;
+                    }
+                    let y = first + row - top;
+                    if grapheme == "\t" {
+                        for cx in x..x + width {
+                            if let Some(c) = cells.get_mut(cx, y) {
+                                *c = cell;
+                            }
+                        }
+                    } else if let Some(c) = cells.get_mut(x, y) {
+                        *c = cell;
+                        cells.put_char(x, y, grapheme.chars().next().unwrap());
+                    }
+                }
+            }
+            first += line.rows;
+        }
+    }
+
+    // Lays out all lines again after a change of width or tab width.
+    fn relayout(&mut self) {
+        let lines: Vec<_> = self.lines.drain(..).map(|l| l.text).collect();
+        self.lines = lines.into_iter().map(|text| self.layout(text)).collect();
+    }
+
+    // Works out where the characters of `text` go.
+    fn layout(&self, text: String) -> Line {
+        let mut cells = Vec::new();
+        let (mut x, mut row) = (0, 0);
+        for grapheme in width::graphemes(&text) {
+            let tab = grapheme == "\t";
+            let width = if tab { 1 } else { width::grapheme_width(grapheme) };
+            if x + width > self.width && x > 0 {
+                x = 0;
+                row += 1;
+            }
+            // A tab stops short at the end of the row, and a character wider than a whole row
+            // gets a row to itself.
+            let width = if tab {
+                (self.tab_width - x % self.tab_width).min(self.width - x)
+            } else {
+                width.min(self.width)
+            };
+            cells.push((x, row, width));
+            x += width;
+        }
+        if x == self.width {
+            x = 0;
+            row += 1;
+        }
+        cells.push((x, row, 0));
+        Line {
+            text,
+            cells,
+            rows: row + 1,
+        }
+    }
+}
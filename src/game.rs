@@ -0,0 +1,191 @@
+//! A game loop.
+//!
+//! `run()` owns the `Terminal` and drives the canonical fixed-update, variable-render loop: input
+//! is gathered while waiting for the next step, the update closure is called once per fixed step
+//! with the input received since the previous step, and the buffers are swapped once per frame.
+//! Slow frames are caught up on with extra steps, up to a limit, so the simulation runs at the
+//! same speed on any terminal.
+
+use std::io::Error;
+use std::slice;
+use std::time::Duration;
+
+use core::input::{Event, Key};
+use core::terminal::Terminal;
+use time::Clock;
+
+/// Configuration for `run()`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use rustty::game::Config;
+///
+/// let mut config = Config::new();
+/// config.set_step(Duration::from_millis(50));
+/// assert_eq!(config.step(), Duration::from_millis(50));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Config {
+    step: Duration,
+    max_delta: Duration,
+}
+
+impl Config {
+    /// Constructs a new `Config` running 30 steps a second.
+    pub fn new() -> Config {
+        Config {
+            step: Duration::from_millis(33),
+            max_delta: Duration::from_millis(250),
+        }
+    }
+
+    /// Returns the length of a simulation step.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Sets the length of a simulation step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn set_step(&mut self, step: Duration) {
+        assert!(step > Duration::from_secs(0), "step must be non-zero");
+        self.step = step;
+    }
+
+    /// Sets the most time a single frame is allowed to catch up on. The default is 250
+    /// milliseconds; see `Clock::set_max_delta()`.
+    pub fn set_max_delta(&mut self, max_delta: Duration) {
+        self.max_delta = max_delta;
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new()
+    }
+}
+
+/// The input received since the previous step.
+#[derive(Debug, Clone, Default)]
+pub struct Input {
+    events: Vec<Event>,
+    quit: bool,
+}
+
+impl Input {
+    /// Returns the events received since the previous step, oldest first.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Returns an iterator over the keys pressed since the previous step, oldest first.
+    pub fn keys(&self) -> Keys<'_> {
+        Keys { events: self.events.iter() }
+    }
+
+    /// Returns `true` if `key` was pressed since the previous step. Characters can be given
+    /// directly, as in `input.is_pressed('q')`.
+    pub fn is_pressed<K: Into<Key>>(&self, key: K) -> bool {
+        let key = key.into();
+        self.keys().any(|k| k == key)
+    }
+
+    /// Stops the game loop once the current step has finished.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+}
+
+/// An iterator over the keys in an `Input`.
+pub struct Keys<'a> {
+    events: slice::Iter<'a, Event>,
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        self.events.find_map(|evt| match *evt {
+            Event::Key(key) => Some(key),
+            _ => None,
+        })
+    }
+}
+
+/// Runs a game until the update closure calls `Input::quit()`.
+///
+/// `update` is called once per step with the input received since the previous step, the length
+/// of the step, and the terminal to draw into. The terminal's buffers are swapped after each frame
+/// in which at least one step ran.
+///
+/// The game pauses while the terminal window is unfocused: the clock stops and no steps run until
+/// focus returns, and the input received meanwhile is passed to the first step after it. On
+/// terminals without focus reporting the game never pauses.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::CellAccessor;
+/// use rustty::game::{self, Config};
+///
+/// let mut x = 0;
+/// game::run(Config::new(), |input, _dt, term| {
+///     if input.is_pressed('q') {
+///         input.quit();
+///     }
+///     term.clear_with_char(' ').unwrap();
+///     x = (x + 1) % term.cols();
+///     *term.get_mut(x, 0).unwrap() = rustty::Cell::with_char('@');
+/// }).unwrap();
+/// ```
+pub fn run<F>(config: Config, mut update: F) -> Result<(), Error>
+    where F: FnMut(&mut Input, Duration, &mut Terminal)
+{
+    let mut term = try!(Terminal::new());
+    let mut clock = Clock::new(config.step);
+    clock.set_max_delta(config.max_delta);
+    let mut input = Input::default();
+    let mut paused = false;
+
+    loop {
+        // Wait for input until the next step is due, or for as long as it takes while paused,
+        // then drain whatever else is pending.
+        let mut timeout = if paused { None } else { Some(clock.timeout()) };
+        while let Some(evt) = try!(term.get_event(timeout)) {
+            match evt {
+                Event::FocusLost if !paused => {
+                    clock.tick();
+                    paused = true;
+                }
+                Event::FocusGained if paused => {
+                    clock.resume();
+                    paused = false;
+                }
+                _ => {}
+            }
+            input.events.push(evt);
+            timeout = Some(Duration::from_secs(0));
+        }
+        if paused {
+            continue;
+        }
+
+        clock.tick();
+        let mut stepped = false;
+        while clock.consume_step() {
+            update(&mut input, config.step, &mut term);
+            input.events.clear();
+            stepped = true;
+            if input.quit {
+                return Ok(());
+            }
+        }
+        if stepped {
+            try!(term.swap_buffers());
+        }
+    }
+}
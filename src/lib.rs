@@ -16,11 +16,34 @@
 extern crate term;
 extern crate libc;
 extern crate gag;
+extern crate unicode_width;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
+#[macro_use]
 mod core;
 pub mod ui;
+pub mod prompt;
+pub mod quick;
+pub mod time;
+pub mod game;
+pub mod offscreen;
+pub mod recorder;
+pub mod text;
+pub mod wm;
+#[cfg(feature = "shm")]
+pub mod shm;
 
-pub use core::terminal::Terminal;
-pub use core::cellbuffer::{Cell, Color, Attr, CellAccessor};
-pub use core::position::{Pos, Size, HasSize, HasPosition};
-pub use core::input::Event;
+pub use core::terminal::{Terminal, can_init};
+pub use core::cellbuffer::{Cell, CellBuilder, CellBuffer, Color, Attr, UnderlineStyle,
+                          CellAccessor};
+pub use core::position::{Pos, Size, Rect, Coords, HasSize, HasPosition};
+pub use core::input::{Event, Key, OverflowPolicy, MouseEvent, MouseButton, MouseAction};
+pub use core::sender::{EventSender, CustomEvent};
+pub use core::source::{InputSource, ScriptSource, ScriptFeeder};
+pub use core::backdrop::capture_screen;
+pub use core::diagnostic::Diagnostic;
+pub use core::palette::{Palette, ColorScheme};
+pub use core::width::{WidthPolicy, set_width_policy, width_policy};
+pub use core::features::{Feature, supported, set_supported};
+pub use core::capabilities::{Capabilities, ColorDepth};
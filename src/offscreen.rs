@@ -0,0 +1,239 @@
+//! Rendering to strings, without a terminal.
+//!
+//! The functions in this module draw into an in-memory `CellBuffer` and turn the result into
+//! text, either plain or with ANSI escape sequences for colors and attributes. They need no tty,
+//! so crates building on rustty can render widgets in tests or generate screenshots for their
+//! documentation in CI.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustty::offscreen;
+//! use rustty::ui::{Painter, Widget};
+//!
+//! # rustty::set_supported(rustty::Feature::Unicode, true);
+//! # rustty::set_supported(rustty::Feature::LineDrawing, true);
+//! let text = offscreen::render(8, 3, |buf| {
+//!     let mut w = Widget::new(8, 3);
+//!     w.draw_box();
+//!     w.printline(2, 1, "ok");
+//!     w.draw_into(buf);
+//! });
+//! assert_eq!(text, "┌──────┐\n│ ok   │\n└──────┘\n");
+//! ```
+
+use std::io::{self, Error, Write};
+use std::thread;
+use std::time::Instant;
+
+use core::cellbuffer::{Attr, Cell, CellAccessor, CellBuffer, Color, UnderlineStyle};
+use core::input::Event;
+use core::width;
+
+/// Draws into a blank buffer of the given size with `f` and returns its contents as plain text,
+/// one line per row with trailing spaces removed.
+pub fn render<F: FnOnce(&mut CellBuffer)>(cols: usize, rows: usize, f: F) -> String {
+    plain(&draw(cols, rows, f))
+}
+
+/// Draws into a blank buffer of the given size with `f` and returns its contents as text with
+/// ANSI escape sequences for colors and attributes.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, Color, offscreen};
+///
+/// let text = offscreen::render_ansi(2, 1, |buf| {
+///     buf.get_mut(0, 0).unwrap().set_ch('a').set_fg(Color::Red);
+///     buf.get_mut(1, 0).unwrap().set_ch('b');
+/// });
+/// assert_eq!(text, "\x1b[0;31ma\x1b[0mb\n");
+/// ```
+pub fn render_ansi<F: FnOnce(&mut CellBuffer)>(cols: usize, rows: usize, f: F) -> String {
+    ansi(&draw(cols, rows, f))
+}
+
+/// Replays `events` into an application drawing offscreen, with the timing they were originally
+/// received with, and returns the frame drawn after each event as plain text.
+///
+/// This reproduces an interaction collected with `Terminal::get_timed_event()`, such as one a user
+/// reported a bug in, without a tty, so that it can be checked in CI. `f` handles each event once
+/// the time between the first event and it has passed again, divided by `speed`, and draws into a
+/// buffer of the given size that keeps its contents between events, like a terminal's backbuffer.
+/// A `speed` of `f64::INFINITY` replays without waiting. `Terminal::replay()` replays into a
+/// terminal instead.
+///
+/// # Panics
+///
+/// Panics if `speed` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use rustty::{CellAccessor, Event, Key, offscreen};
+///
+/// let start = Instant::now();
+/// let session = vec![(Event::Key(Key::Char('l')), start),
+///                    (Event::Key(Key::Char('l')), start + Duration::from_millis(400)),
+///                    (Event::Key(Key::Char('x')), start + Duration::from_secs(1))];
+///
+/// let mut col = 0;
+/// let frames = offscreen::replay(4, 1, session, 10.0, |buf, evt| {
+///     match *evt {
+///         Event::Key(Key::Char('l')) => col += 1,
+///         Event::Key(Key::Char(ch)) => {
+///             buf.get_mut(col, 0).unwrap().set_ch(ch);
+///         }
+///         _ => {}
+///     }
+/// });
+/// assert_eq!(frames, ["\n", "\n", "  x\n"]);
+/// ```
+pub fn replay<I, F>(cols: usize, rows: usize, events: I, speed: f64, mut f: F) -> Vec<String>
+    where I: IntoIterator<Item = (Event, Instant)>,
+          F: FnMut(&mut CellBuffer, &Event)
+{
+    assert!(speed > 0.0, "replay speed must be positive");
+    let mut buf = CellBuffer::new(cols, rows, Cell::default());
+    let start = Instant::now();
+    let mut first = None;
+    let mut frames = Vec::new();
+    for (evt, at) in events {
+        let offset = at.saturating_duration_since(*first.get_or_insert(at));
+        let due = start + offset.div_f64(speed);
+        let now = Instant::now();
+        if due > now {
+            thread::sleep(due - now);
+        }
+        f(&mut buf, &evt);
+        frames.push(plain(&buf));
+    }
+    frames
+}
+
+/// Returns the contents of `cells` as plain text, one line per row with trailing spaces removed.
+///
+/// Halves of double-width characters whose other half was overwritten are blank, as they are on
+/// a terminal.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{Cell, CellAccessor, CellBuffer, offscreen};
+///
+/// let mut buf = CellBuffer::new(3, 1, Cell::default());
+/// buf.put_char(0, 0, '中');
+/// buf.put_char(2, 0, 'b');
+/// assert_eq!(offscreen::plain(&buf), "中b\n");
+///
+/// buf.get_mut(0, 0).unwrap().set_ch('a');
+/// assert_eq!(offscreen::plain(&buf), "a b\n");
+/// ```
+pub fn plain(cells: &dyn CellAccessor) -> String {
+    let (cols, rows) = cells.size();
+    let mut out = String::new();
+    for y in 0..rows {
+        let line: String = (0..cols)
+            .map(|x| width::display_cell(cells, x, y))
+            .filter(|cell| !cell.is_continuation())
+            .map(|cell| cell.ch())
+            .collect();
+        out.push_str(line.trim_end_matches(' '));
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes the contents of `cells` to standard output as plain text, as `plain()` formats them.
+///
+/// This is how programs show their output when no terminal is available, see
+/// `rustty::can_init()`.
+pub fn print(cells: &dyn CellAccessor) -> Result<(), Error> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    try!(out.write_all(plain(cells).as_bytes()));
+    out.flush()
+}
+
+/// Returns the contents of `cells` as text with ANSI escape sequences, one line per row.
+///
+/// Styles are reset at the end of every line. Palette slots have no fixed color without a
+/// terminal and are written in the default color.
+pub fn ansi(cells: &dyn CellAccessor) -> String {
+    let (cols, rows) = cells.size();
+    let plain = Cell::default();
+    let mut out = String::new();
+    for y in 0..rows {
+        let mut last = plain;
+        for x in 0..cols {
+            let cell = width::display_cell(cells, x, y);
+            if !same_style(&cell, &last) {
+                out.push_str(&sgr(&cell));
+                last = cell;
+            }
+            if !cell.is_continuation() {
+                out.push(cell.ch());
+            }
+        }
+        if !same_style(&last, &plain) {
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn draw<F: FnOnce(&mut CellBuffer)>(cols: usize, rows: usize, f: F) -> CellBuffer {
+    let mut buf = CellBuffer::new(cols, rows, Cell::default());
+    f(&mut buf);
+    buf
+}
+
+fn same_style(a: &Cell, b: &Cell) -> bool {
+    a.fg() == b.fg() && a.bg() == b.bg() && a.attrs() == b.attrs() &&
+    (!a.attrs().contains(Attr::Underline) ||
+     (a.underline_style() == b.underline_style() && a.underline_color() == b.underline_color()))
+}
+
+// Returns the escape sequence that resets the style and switches to the cell's style.
+fn sgr(cell: &Cell) -> String {
+    let mut codes = vec!["0".to_owned()];
+    let attrs = cell.attrs();
+    let styles = [(Attr::Bold, "1"),
+                  (Attr::Dim, "2"),
+                  (Attr::Italic, "3"),
+                  (Attr::Underline, "4"),
+                  (Attr::Blink, "5"),
+                  (Attr::Reverse, "7"),
+                  (Attr::Strikethrough, "9")];
+    let underline = cell.underline_style();
+    for &(attr, code) in &styles {
+        if attr == Attr::Underline && attrs.contains(attr) && underline != UnderlineStyle::Single {
+            codes.push(format!("4:{}", underline as u8));
+        } else if attrs.contains(attr) {
+            codes.push(code.to_owned());
+        }
+    }
+    codes.extend(color(cell.fg(), 30, 38));
+    codes.extend(color(cell.bg(), 40, 48));
+    if attrs.contains(Attr::Underline) {
+        codes.extend(match cell.underline_color() {
+            Color::Default | Color::Slot(_) => None,
+            Color::Rgb(r, g, b) => Some(format!("58;2;{};{};{}", r, g, b)),
+            c => Some(format!("58;5;{}", c.as_byte())),
+        });
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+// Returns the SGR parameter for a color, given the bases for named and indexed colors.
+fn color(color: Color, named: u8, indexed: u8) -> Option<String> {
+    match color {
+        Color::Default | Color::Slot(_) => None,
+        Color::Byte(b) => Some(format!("{};5;{}", indexed, b)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", indexed, r, g, b)),
+        c => Some(format!("{}", named + c.as_byte())),
+    }
+}
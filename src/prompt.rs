@@ -0,0 +1,103 @@
+//! Standalone prompts that do not require a `Terminal`.
+//!
+//! The functions in this module talk to the controlling terminal directly and restore its
+//! original state before returning, so they can be used by command line tools that only need a
+//! single interaction rather than a full screen UI.
+
+use std::io::prelude::*;
+use std::io::{Error, ErrorKind};
+use std::fs::{File, OpenOptions};
+use std::os::unix::io::AsRawFd;
+use std::str;
+
+use core::termctl::TermCtl;
+use ui::{SecretText, Mask};
+
+// Restores the terminal to its original state when dropped, so the tty is left usable even if
+// reading the secret fails or panics.
+struct Restore<'a>(&'a TermCtl);
+
+impl<'a> Drop for Restore<'a> {
+    fn drop(&mut self) {
+        let _ = self.0.reset();
+    }
+}
+
+/// Prompts for a secret, such as a password, on the controlling terminal.
+///
+/// `prompt` is written to `/dev/tty` and a line is read back with echo turned off, much like
+/// `sudo` does. No `Terminal` needs to exist; the terminal settings are changed only for the
+/// duration of the call and are restored however it returns.
+///
+/// Signal generation is disabled while reading so that the terminal is never left without echo
+/// by an interrupted process. Instead, `Ctrl-C` makes this function return an error of kind
+/// `Interrupted`, and `Ctrl-D` on an empty line returns an error of kind `UnexpectedEof`.
+/// Backspace erases the last character.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustty::prompt;
+///
+/// let password = prompt::secret("Password: ").unwrap();
+/// println!("read {} characters", password.len());
+/// ```
+pub fn secret(prompt: &str) -> Result<SecretText, Error> {
+    let mut tty = try!(OpenOptions::new().read(true).write(true).open("/dev/tty"));
+    let termctl = try!(TermCtl::new(tty.as_raw_fd()));
+
+    try!(tty.write_all(prompt.as_bytes()));
+    try!(tty.flush());
+
+    let result = {
+        try!(termctl.set_noecho());
+        let _restore = Restore(&termctl);
+        read_secret(&mut tty)
+    };
+
+    try!(tty.write_all(b"\r\n"));
+    result
+}
+
+// Reads bytes up to the end of the line into a `SecretText`.
+fn read_secret(tty: &mut File) -> Result<SecretText, Error> {
+    let mut secret = SecretText::new(Mask::Hidden);
+    // Holds the bytes of a partially read multi-byte character.
+    let mut pending = [0u8; 4];
+    let mut npending = 0;
+    let mut byte = [0u8; 1];
+
+    loop {
+        if try!(tty.read(&mut byte)) == 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "end of input"));
+        }
+        match byte[0] {
+            b'\r' | b'\n' => return Ok(secret),
+            0x03 => return Err(Error::new(ErrorKind::Interrupted, "interrupted")),
+            0x04 if secret.is_empty() => {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "end of input"));
+            }
+            0x7f | 0x08 => {
+                secret.pop();
+            }
+            b => {
+                pending[npending] = b;
+                npending += 1;
+                match str::from_utf8(&pending[..npending]) {
+                    Ok(s) => {
+                        for ch in s.chars() {
+                            secret.push(ch);
+                        }
+                    }
+                    // Incomplete character, wait for the rest of its bytes.
+                    Err(ref e) if e.error_len().is_none() && npending < pending.len() => continue,
+                    Err(_) => {}
+                }
+                // Don't leave a copy of the last character lying around.
+                pending = [0; 4];
+                npending = 0;
+                byte[0] = 0;
+            }
+        }
+    }
+}
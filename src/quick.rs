@@ -0,0 +1,180 @@
+//! One-shot query helpers.
+//!
+//! Each function in this module constructs a transient `Terminal`, shows a centered dialog,
+//! waits for an answer and tears the terminal down again before returning it. They are meant
+//! for small command line utilities that need a single interaction and no event loop of their
+//! own; they fail if a `Terminal` already exists.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use rustty::quick;
+//!
+//! if quick::confirm("Delete all files?").unwrap() {
+//!     let name = quick::input("Type the name of the directory:").unwrap();
+//! }
+//! ```
+
+use std::cmp;
+use std::io::Error;
+
+use core::terminal::Terminal;
+use core::input::{Event, Key};
+use core::cellbuffer::{Attr, Cell, Color};
+use core::position::{HasSize, HasPosition};
+use core::width;
+use ui::{Alignable, Dialog, DialogResult, HorizontalAlign, Painter, VerticalAlign};
+
+/// Asks a yes/no question.
+///
+/// Returns `true` if the user answers with `y` and `false` if they answer with `n` or `Esc`.
+pub fn confirm(question: &str) -> Result<bool, Error> {
+    let mut term = try!(Terminal::new());
+    let mut dlg = Dialog::new(dialog_width(&term, width::display_width(question)), 6);
+    dlg.window_mut().printline(2, 1, question);
+    dlg.add_button("Yes", 'y', DialogResult::Ok);
+    dlg.add_button("No", 'n', DialogResult::Cancel);
+    dlg.draw_buttons();
+    dlg.window_mut().draw_box();
+
+    loop {
+        try!(show(&mut term, &mut dlg));
+        try!(term.swap_buffers());
+        match try!(term.get_event(None)) {
+            Some(Event::Key(Key::Esc)) => return Ok(false),
+            Some(Event::Key(Key::Char(ch))) => {
+                match dlg.result_for_key(ch) {
+                    Some(DialogResult::Ok) => return Ok(true),
+                    Some(_) => return Ok(false),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Asks the user to pick one of `options`.
+///
+/// The highlight is moved with `j` and `k` and `Enter` picks the highlighted option; the digits
+/// `1` to `9` pick the corresponding option directly. Returns the index of the chosen option, or
+/// `None` if the user cancels with `Esc` or there are no options.
+pub fn select_one(prompt: &str, options: &[&str]) -> Result<Option<usize>, Error> {
+    if options.is_empty() {
+        return Ok(None);
+    }
+    let mut term = try!(Terminal::new());
+    // Options are printed with a "N. " prefix.
+    let widest = options.iter().fold(width::display_width(prompt),
+                                     |w, o| cmp::max(w, width::display_width(o) + 4));
+    let mut dlg = Dialog::new(dialog_width(&term, widest), options.len() + 4);
+    let mut selected = 0;
+
+    loop {
+        {
+            let w = dlg.window_mut();
+            w.printline(2, 1, prompt);
+            for (i, opt) in options.iter().enumerate() {
+                let cell = if i == selected {
+                    Cell::with_style(Color::Default, Color::Default, Attr::Reverse)
+                } else {
+                    Cell::default()
+                };
+                w.printline_with_cell(2, i + 2, &format!("{}. {}", i + 1, opt), cell);
+            }
+            w.draw_box();
+        }
+        try!(show(&mut term, &mut dlg));
+        try!(term.swap_buffers());
+        if let Some(Event::Key(key)) = try!(term.get_event(None)) {
+            match key {
+                Key::Char('k') | Key::Up => selected = selected.saturating_sub(1),
+                Key::Char('j') | Key::Down => selected = cmp::min(selected + 1, options.len() - 1),
+                Key::Enter => return Ok(Some(selected)),
+                Key::Esc => return Ok(None),
+                Key::Char(c) => {
+                    if let Some(d) = c.to_digit(10) {
+                        let d = d as usize;
+                        if d >= 1 && d <= options.len() {
+                            return Ok(Some(d - 1));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Asks the user to type a line of text.
+///
+/// `Enter` finishes the input and `Backspace` erases the last character. Pasted text is added
+/// as a whole. Returns `None` if the user cancels with `Esc`.
+pub fn input(prompt: &str) -> Result<Option<String>, Error> {
+    let mut term = try!(Terminal::new());
+    let width = dialog_width(&term, width::display_width(prompt));
+    let mut dlg = Dialog::new(width, 5);
+    let field = width - 4;
+    let mut text = String::new();
+
+    loop {
+        {
+            let w = dlg.window_mut();
+            w.printline(2, 1, prompt);
+            // Show the tail of the text if it is wider than the field, leaving a column for the
+            // cursor.
+            let (start, shown) = tail(&text, field - 1);
+            let line = format!("{}{}", &text[start..], " ".repeat(field - shown));
+            let field_cell = Cell::with_style(Color::Default, Color::Default, Attr::Underline);
+            w.printline_with_cell(2, 2, &line, field_cell);
+            w.draw_box();
+        }
+        try!(show(&mut term, &mut dlg));
+        let (x, y) = dlg.window().origin();
+        let cursor_x = tail(&text, field - 1).1;
+        try!(term.set_cursor(x + 2 + cursor_x, y + 2));
+        try!(term.swap_buffers());
+
+        match try!(term.get_event(None)) {
+            Some(Event::Key(Key::Enter)) => return Ok(Some(text)),
+            Some(Event::Key(Key::Esc)) => return Ok(None),
+            Some(Event::Key(Key::Backspace)) => {
+                text.pop();
+            }
+            Some(Event::Key(Key::Char(c))) if !c.is_control() => text.push(c),
+            // Pasted line breaks and other control characters are left out, as when typed.
+            Some(Event::Paste(pasted)) => text.extend(pasted.chars().filter(|c| !c.is_control())),
+            _ => {}
+        }
+    }
+}
+
+// Returns a dialog width fitting `text_width` columns of text with a margin, bounded by the
+// terminal width.
+fn dialog_width(term: &Terminal, text_width: usize) -> usize {
+    let (cols, _) = term.size();
+    cmp::min(cmp::max(text_width + 4, 30), cmp::max(cols, 5))
+}
+
+// Returns where the longest end of `text` fitting in `cols` columns starts, and its width.
+fn tail(text: &str, cols: usize) -> (usize, usize) {
+    let mut start = text.len();
+    let mut shown = 0;
+    for (i, ch) in text.char_indices().rev() {
+        let w = width::char_width(ch);
+        if shown + w > cols {
+            break;
+        }
+        start = i;
+        shown += w;
+    }
+    (start, shown)
+}
+
+// Draws the dialog centered on a blank backbuffer.
+fn show(term: &mut Terminal, dlg: &mut Dialog) -> Result<(), Error> {
+    try!(term.clear());
+    dlg.window_mut().align(&*term, HorizontalAlign::Middle, VerticalAlign::Middle, 0);
+    dlg.window().draw_into(term);
+    Ok(())
+}
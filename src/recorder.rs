@@ -0,0 +1,157 @@
+//! Recording and playback of input macros.
+//!
+//! A `Recorder` captures sequences of events into registers named by characters and plays them
+//! back by injecting them into a terminal's event queue, in the style of vi's `q` and `@`
+//! commands. Played events are returned by `Terminal::get_event()` like any other input, so an
+//! application needs no special handling to support macros.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use rustty::{Event, Key, Terminal};
+//! use rustty::recorder::Recorder;
+//!
+//! let mut term = Terminal::new().unwrap();
+//! let mut recorder = Recorder::new();
+//! let mut awaiting = None;
+//! loop {
+//!     let evt = match term.get_event(None).unwrap() {
+//!         Some(evt) => evt,
+//!         None => continue,
+//!     };
+//!     match (awaiting.take(), evt) {
+//!         (Some('q'), Event::Key(Key::Char(reg))) => recorder.start(reg),
+//!         (Some('@'), Event::Key(Key::Char(reg))) => {
+//!             recorder.play(reg, &mut term);
+//!         }
+//!         (None, Event::Key(Key::Char('q'))) if recorder.is_recording() => {
+//!             recorder.stop();
+//!         }
+//!         (None, Event::Key(Key::Char(ch @ 'q'))) |
+//!         (None, Event::Key(Key::Char(ch @ '@'))) => awaiting = Some(ch),
+//!         (_, evt) => {
+//!             recorder.record(evt);
+//!             // Handle the event.
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use core::input::Event;
+use core::terminal::Terminal;
+
+/// Records events into registers and plays them back.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{Event, Key};
+/// use rustty::recorder::Recorder;
+///
+/// let mut recorder = Recorder::new();
+/// recorder.start('a');
+/// recorder.record(Event::Key(Key::Char('x')));
+/// recorder.record(Event::Key(Key::Up));
+/// assert_eq!(recorder.stop(), Some('a'));
+///
+/// // Events are only recorded between start() and stop().
+/// recorder.record(Event::Key(Key::Char('z')));
+/// assert_eq!(recorder.register('a'),
+///            Some(&[Event::Key(Key::Char('x')), Event::Key(Key::Up)][..]));
+/// ```
+pub struct Recorder {
+    registers: HashMap<char, Vec<Event>>,
+    recording: Option<(char, Vec<Event>)>,
+}
+
+impl Recorder {
+    /// Constructs a new `Recorder` with all registers empty.
+    pub fn new() -> Recorder {
+        Recorder {
+            registers: HashMap::new(),
+            recording: None,
+        }
+    }
+
+    /// Starts recording into `register`, finishing any recording in progress first.
+    pub fn start(&mut self, register: char) {
+        self.stop();
+        self.recording = Some((register, Vec::new()));
+    }
+
+    /// Stops recording and stores the recorded events in their register, replacing its previous
+    /// contents. Returns the register, or `None` if nothing was being recorded.
+    pub fn stop(&mut self) -> Option<char> {
+        self.recording.take().map(|(register, events)| {
+            self.registers.insert(register, events);
+            register
+        })
+    }
+
+    /// Returns `true` if events are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Returns the register being recorded into.
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|r| r.0)
+    }
+
+    /// Records an event if recording is in progress.
+    ///
+    /// Pass every event the application handles, but not the keys that start or stop recording.
+    /// Idle, focus, resize, I/O, timer and resume events are never recorded since they are not
+    /// input.
+    pub fn record(&mut self, evt: Event) {
+        if let Some((_, ref mut events)) = self.recording {
+            let input = !matches!(evt,
+                                  Event::Idle(_) | Event::FocusGained | Event::FocusLost |
+                                  Event::Resize(_) | Event::Io(_) | Event::Tick(_) |
+                                  Event::Resume);
+            if input {
+                events.push(evt);
+            }
+        }
+    }
+
+    /// Returns the events in a register.
+    pub fn register(&self, register: char) -> Option<&[Event]> {
+        self.registers.get(&register).map(|events| &events[..])
+    }
+
+    /// Replaces the events in a register.
+    pub fn set_register(&mut self, register: char, events: Vec<Event>) {
+        self.registers.insert(register, events);
+    }
+
+    /// Removes a register's events.
+    pub fn clear_register(&mut self, register: char) {
+        self.registers.remove(&register);
+    }
+
+    /// Injects the events in a register into the terminal's event queue, returning `false` if
+    /// the register is empty.
+    ///
+    /// Played events pass through the application again, so they are recorded again if a
+    /// recording is in progress.
+    pub fn play(&self, register: char, term: &mut Terminal) -> bool {
+        match self.registers.get(&register) {
+            Some(events) if !events.is_empty() => {
+                for evt in events {
+                    term.push_event(evt.clone());
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Recorder {
+        Recorder::new()
+    }
+}
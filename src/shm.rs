@@ -0,0 +1,342 @@
+//! Panels shared between processes.
+//!
+//! A `SharedPanel` is a grid of cells stored in a memory-mapped file, such as one under
+//! `/dev/shm`. One process creates the panel and writes cells into it, while any number of other
+//! processes open the same file and copy consistent snapshots of it into their own buffers for
+//! compositing. Writes are published with a sequence lock, so readers never block the writer
+//! and never observe a half-written frame.
+//!
+//! This module is only available with the `shm` feature.
+
+use std::fs::{File, OpenOptions};
+use std::hint;
+use std::io::{Error, ErrorKind};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{fence, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use libc;
+
+use core::cellbuffer::{Attr, Cell, CellAccessor, CellBuffer, Color, UnderlineStyle};
+use core::position::{Size, HasSize};
+
+// Identifies a shared panel file, "RTSP" in little-endian.
+const MAGIC: u32 = 0x5053_5452;
+const VERSION: u32 = 2;
+
+// Header layout: magic (u32), version (u32), sequence (u64), cols (u32), rows (u32), padding.
+const SEQ_OFFSET: usize = 8;
+const COLS_OFFSET: usize = 16;
+const ROWS_OFFSET: usize = 20;
+const HEADER_LEN: usize = 32;
+
+// Each cell is stored as five words: char, foreground, background, flags and underline color.
+const CELL_WORDS: usize = 5;
+const CELL_LEN: usize = CELL_WORDS * 4;
+
+// Color encoding: 0..=255 are `Color::Byte(..)`, 256..=263 the named colors, 512..=767
+// `Color::Slot(..)` and 0x1000000 plus the 24 bits of the color `Color::Rgb(..)`.
+const COLOR_NAMED: u32 = 0x100;
+const COLOR_SLOT: u32 = 0x200;
+const COLOR_RGB: u32 = 0x100_0000;
+const COLOR_DEFAULT: u32 = 0xffff_ffff;
+// Flags: the attribute bits, the protected bit and the underline style from bit 9 on.
+const FLAG_PROTECTED: u32 = 0x100;
+const UNDERLINE_SHIFT: u32 = 9;
+// How long readers wait for a write in progress, after which the writer is taken to have died.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A grid of cells in memory shared with other processes.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{Cell, CellAccessor, HasSize};
+/// use rustty::shm::SharedPanel;
+/// use rustty::ui::Widget;
+///
+/// let path = std::env::temp_dir().join("rustty_shm_doctest");
+///
+/// // In the producing process.
+/// let producer = SharedPanel::create(&path, 10, 2).unwrap();
+/// let mut frame = Widget::new(10, 2);
+/// *frame.get_mut(3, 1).unwrap() = Cell::with_char('x');
+/// producer.write(&frame);
+///
+/// // In the rendering process.
+/// let consumer = SharedPanel::open(&path).unwrap();
+/// assert_eq!(consumer.size(), (10, 2));
+/// let snapshot = consumer.snapshot().unwrap();
+/// assert_eq!(snapshot.get(3, 1).unwrap().ch(), 'x');
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct SharedPanel {
+    map: *mut u8,
+    len: usize,
+    cols: usize,
+    rows: usize,
+}
+
+// The mapping is only accessed through atomics, and the sequence lock makes the contents safe to
+// read concurrently with a writer.
+unsafe impl Send for SharedPanel {}
+unsafe impl Sync for SharedPanel {}
+
+impl SharedPanel {
+    /// Creates a shared panel of the given size backed by the file at `path`, replacing the
+    /// file if it exists. All cells start out as the default `Cell`.
+    pub fn create<P>(path: P, cols: usize, rows: usize) -> Result<SharedPanel, Error>
+        where P: AsRef<Path>
+    {
+        if cols > u32::MAX as usize || rows > u32::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "shared panel too large"));
+        }
+        let file = try!(OpenOptions::new()
+                       .read(true)
+                       .write(true)
+                       .create(true)
+                       .truncate(true)
+                       .open(path));
+        let len = match panel_len(cols, rows) {
+            Some(len) => len,
+            None => return Err(Error::new(ErrorKind::InvalidInput, "shared panel too large")),
+        };
+        try!(file.set_len(len as u64));
+
+        let panel = SharedPanel {
+            map: try!(map(&file, len)),
+            len,
+            cols,
+            rows,
+        };
+        panel.word(COLS_OFFSET).store(cols as u32, Ordering::Relaxed);
+        panel.word(ROWS_OFFSET).store(rows as u32, Ordering::Relaxed);
+        panel.word(4).store(VERSION, Ordering::Relaxed);
+        panel.write(&CellBuffer::new(cols, rows, Cell::default()));
+        // Publishing the magic number last marks the panel as ready to be opened.
+        panel.word(0).store(MAGIC, Ordering::Release);
+        Ok(panel)
+    }
+
+    /// Opens an existing shared panel backed by the file at `path`.
+    ///
+    /// Returns an error of kind `InvalidData` if the file is not a shared panel.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SharedPanel, Error> {
+        let file = try!(OpenOptions::new().read(true).write(true).open(path));
+        let len = try!(file.metadata()).len() as usize;
+        if len < HEADER_LEN {
+            return Err(invalid_data());
+        }
+        let mut panel = SharedPanel {
+            map: try!(map(&file, len)),
+            len,
+            cols: 0,
+            rows: 0,
+        };
+        if panel.word(0).load(Ordering::Acquire) != MAGIC ||
+           panel.word(4).load(Ordering::Relaxed) != VERSION {
+            return Err(invalid_data());
+        }
+        panel.cols = panel.word(COLS_OFFSET).load(Ordering::Relaxed) as usize;
+        panel.rows = panel.word(ROWS_OFFSET).load(Ordering::Relaxed) as usize;
+        // The size comes from the file, so a corrupt one must not wrap around to a small length.
+        match panel_len(panel.cols, panel.rows) {
+            Some(needed) if needed <= len => Ok(panel),
+            _ => Err(invalid_data()),
+        }
+    }
+
+    /// Publishes the contents of `cells` to the panel.
+    ///
+    /// Cells outside the panel are ignored and panel cells not covered by `cells` are left
+    /// unchanged. Only one process should write to a panel at a time.
+    pub fn write(&self, cells: &dyn CellAccessor) {
+        let seq = self.seq();
+        let start = seq.load(Ordering::Relaxed);
+        // An odd sequence number tells readers a write is in progress.
+        seq.store(start.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let words = self.cell_words();
+        let (cols, rows) = cells.size();
+        for y in 0..rows.min(self.rows) {
+            for x in 0..cols.min(self.cols) {
+                let cell = cells.get(x, y).unwrap();
+                let i = (y * self.cols + x) * CELL_WORDS;
+                let flags = cell.attrs().bits() as u32 |
+                            if cell.is_protected() { FLAG_PROTECTED } else { 0 } |
+                            (cell.underline_style() as u32) << UNDERLINE_SHIFT;
+                words[i].store(cell.ch() as u32, Ordering::Relaxed);
+                words[i + 1].store(encode_color(cell.fg()), Ordering::Relaxed);
+                words[i + 2].store(encode_color(cell.bg()), Ordering::Relaxed);
+                words[i + 3].store(flags, Ordering::Relaxed);
+                words[i + 4].store(encode_color(cell.underline_color()), Ordering::Relaxed);
+            }
+        }
+
+        seq.store(start.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Copies a consistent snapshot of the panel into `cells`, starting at its origin.
+    ///
+    /// Panel cells outside `cells` are skipped. Returns the sequence number of the copied
+    /// snapshot, which increases with every write and so can be used to skip redundant copies.
+    ///
+    /// Fails with `ErrorKind::TimedOut` if no consistent snapshot could be copied within a
+    /// second, as happens when the writer died in the middle of a write.
+    pub fn read_into(&self, cells: &mut dyn CellAccessor) -> Result<u64, Error> {
+        let seq = self.seq();
+        let words = self.cell_words();
+        let (cols, rows) = cells.size();
+        let deadline = Instant::now() + READ_TIMEOUT;
+        loop {
+            if Instant::now() >= deadline {
+                return Err(Error::new(ErrorKind::TimedOut, "shared panel write never finished"));
+            }
+            let start = seq.load(Ordering::Acquire);
+            if start % 2 == 1 {
+                hint::spin_loop();
+                continue;
+            }
+            for y in 0..rows.min(self.rows) {
+                for x in 0..cols.min(self.cols) {
+                    let i = (y * self.cols + x) * CELL_WORDS;
+                    let cell = cells.get_mut(x, y).unwrap();
+                    *cell = decode_cell(words[i].load(Ordering::Relaxed),
+                                        words[i + 1].load(Ordering::Relaxed),
+                                        words[i + 2].load(Ordering::Relaxed),
+                                        words[i + 3].load(Ordering::Relaxed),
+                                        words[i + 4].load(Ordering::Relaxed));
+                }
+            }
+            fence(Ordering::Acquire);
+            if seq.load(Ordering::Relaxed) == start {
+                return Ok(start / 2);
+            }
+        }
+    }
+
+    /// Returns a consistent snapshot of the panel, failing like `read_into()`.
+    pub fn snapshot(&self) -> Result<CellBuffer, Error> {
+        let mut buf = CellBuffer::new(self.cols, self.rows, Cell::default());
+        try!(self.read_into(&mut buf));
+        Ok(buf)
+    }
+
+    /// Returns the sequence number of the last completed write.
+    pub fn generation(&self) -> u64 {
+        self.seq().load(Ordering::Acquire) / 2
+    }
+
+    fn word(&self, offset: usize) -> &AtomicU32 {
+        unsafe { &*(self.map.add(offset) as *const AtomicU32) }
+    }
+
+    fn seq(&self) -> &AtomicU64 {
+        unsafe { &*(self.map.add(SEQ_OFFSET) as *const AtomicU64) }
+    }
+
+    fn cell_words(&self) -> &[AtomicU32] {
+        unsafe {
+            slice::from_raw_parts(self.map.add(HEADER_LEN) as *const AtomicU32,
+                                  self.cols * self.rows * CELL_WORDS)
+        }
+    }
+}
+
+impl HasSize for SharedPanel {
+    fn size(&self) -> Size {
+        (self.cols, self.rows)
+    }
+}
+
+impl Drop for SharedPanel {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.map as *mut libc::c_void, self.len);
+        }
+    }
+}
+
+// Maps the first `len` bytes of `file` into memory, shared with other processes.
+fn map(file: &File, len: usize) -> Result<*mut u8, Error> {
+    let ptr = unsafe {
+        libc::mmap(ptr::null_mut(),
+                   len,
+                   libc::PROT_READ | libc::PROT_WRITE,
+                   libc::MAP_SHARED,
+                   file.as_raw_fd(),
+                   0)
+    };
+    if ptr == libc::MAP_FAILED {
+        Err(Error::last_os_error())
+    } else {
+        Ok(ptr as *mut u8)
+    }
+}
+
+// Length of the file backing a panel of the given size, or `None` if it does not fit a `usize`.
+fn panel_len(cols: usize, rows: usize) -> Option<usize> {
+    cols.checked_mul(rows)
+        .and_then(|cells| cells.checked_mul(CELL_LEN))
+        .and_then(|bytes| bytes.checked_add(HEADER_LEN))
+}
+
+fn invalid_data() -> Error {
+    Error::new(ErrorKind::InvalidData, "not a shared panel")
+}
+
+fn encode_color(color: Color) -> u32 {
+    match color {
+        Color::Default => COLOR_DEFAULT,
+        Color::Byte(b) => b as u32,
+        Color::Slot(n) => COLOR_SLOT + n as u32,
+        Color::Rgb(r, g, b) => COLOR_RGB + ((r as u32) << 16 | (g as u32) << 8 | b as u32),
+        named => COLOR_NAMED + named.as_byte() as u32,
+    }
+}
+
+fn decode_color(word: u32) -> Color {
+    match word {
+        0..=0xff => Color::Byte(word as u8),
+        0x100 => Color::Black,
+        0x101 => Color::Red,
+        0x102 => Color::Green,
+        0x103 => Color::Yellow,
+        0x104 => Color::Blue,
+        0x105 => Color::Magenta,
+        0x106 => Color::Cyan,
+        0x107 => Color::White,
+        0x200..=0x2ff => Color::Slot((word - COLOR_SLOT) as u8),
+        0x100_0000..=0x1ff_ffff => Color::Rgb((word >> 16) as u8, (word >> 8) as u8, word as u8),
+        _ => Color::Default,
+    }
+}
+
+fn decode_attr(bits: u32) -> Attr {
+    Attr::from_bits(bits as u8)
+}
+
+fn decode_underline(flags: u32) -> UnderlineStyle {
+    match flags >> UNDERLINE_SHIFT & 0b111 {
+        2 => UnderlineStyle::Double,
+        3 => UnderlineStyle::Curly,
+        4 => UnderlineStyle::Dotted,
+        5 => UnderlineStyle::Dashed,
+        _ => UnderlineStyle::Single,
+    }
+}
+
+fn decode_cell(ch: u32, fg: u32, bg: u32, flags: u32, underline: u32) -> Cell {
+    // A torn read can't happen thanks to the sequence lock, but the file may have been written by
+    // anyone, so don't trust the character to be valid.
+    let ch = ::std::char::from_u32(ch).unwrap_or(' ');
+    let mut cell = Cell::new(ch, decode_color(fg), decode_color(bg), decode_attr(flags));
+    cell.set_protected(flags & FLAG_PROTECTED != 0)
+        .set_underline_style(decode_underline(flags))
+        .set_underline_color(decode_color(underline));
+    cell
+}
@@ -0,0 +1,21 @@
+//! Measuring text the way rustty lays it out.
+//!
+//! Widgets that place text themselves should measure it with these functions rather than
+//! counting `char`s, so that their geometry agrees with `Painter::printline()` and the alignment
+//! helpers. Widths follow the current `WidthPolicy`. Editors that soft-wrap their text can map
+//! cursor positions to the screen and back with a `WrapMap`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustty::text;
+//!
+//! assert_eq!(text::display_width("日本語"), 6);
+//! assert_eq!(text::truncate_to_width("日本語", 5), "日本");
+//! let chars: Vec<_> = text::graphemes("cafe\u{301}!").collect();
+//! assert_eq!(chars, ["c", "a", "f", "e\u{301}", "!"]);
+//! ```
+
+pub use core::width::{char_width, display_width, truncate_to_width, grapheme_width, graphemes,
+                      Graphemes};
+pub use core::wrap::WrapMap;
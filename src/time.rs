@@ -0,0 +1,170 @@
+//! Timing for animations and games.
+//!
+//! A `Clock` measures the time between frames and accumulates it into fixed-size simulation
+//! steps, so that the state of an animation advances at the same rate however quickly frames are
+//! drawn. The time until the next step doubles as the timeout for `Terminal::get_event()`, which
+//! turns the event loop into a tick source.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use rustty::Terminal;
+//! use rustty::time::Clock;
+//!
+//! let mut term = Terminal::new().unwrap();
+//! let mut clock = Clock::new(Duration::from_millis(100));
+//! loop {
+//!     if let Some(_evt) = term.get_event(Some(clock.timeout())).unwrap() {
+//!         // Handle input.
+//!     }
+//!     clock.tick();
+//!     while clock.consume_step() {
+//!         // Advance the simulation by `clock.step()`.
+//!     }
+//!     // Render, optionally interpolating by `clock.alpha()`.
+//!     term.swap_buffers().unwrap();
+//! }
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// A clock that splits elapsed time into fixed steps.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use rustty::time::Clock;
+///
+/// let mut clock = Clock::new(Duration::from_millis(100));
+///
+/// clock.advance(Duration::from_millis(250));
+/// let mut steps = 0;
+/// while clock.consume_step() {
+///     steps += 1;
+/// }
+/// assert_eq!(steps, 2);
+/// assert_eq!(clock.alpha(), 0.5);
+/// assert_eq!(clock.timeout(), Duration::from_millis(50));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clock {
+    last: Instant,
+    step: Duration,
+    max_delta: Duration,
+    accumulator: Duration,
+    elapsed: Duration,
+}
+
+impl Clock {
+    /// Constructs a new `Clock` starting now, with the given fixed step length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn new(step: Duration) -> Clock {
+        assert!(step > Duration::from_secs(0), "step must be non-zero");
+        Clock {
+            last: Instant::now(),
+            step,
+            max_delta: Duration::from_millis(250),
+            accumulator: Duration::from_secs(0),
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    /// Returns the length of a fixed step.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Sets the length of a fixed step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn set_step(&mut self, step: Duration) {
+        assert!(step > Duration::from_secs(0), "step must be non-zero");
+        self.step = step;
+    }
+
+    /// Sets the longest time a single tick may account for. The default is 250 milliseconds.
+    ///
+    /// Longer pauses, such as the process being suspended, are cut short so that the simulation
+    /// doesn't have to run a burst of steps to catch up.
+    pub fn set_max_delta(&mut self, max_delta: Duration) {
+        self.max_delta = max_delta;
+    }
+
+    /// Measures the time since the previous tick, or since the clock was created or reset, and
+    /// accumulates it. Returns the measured time, capped at the maximum delta.
+    pub fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last);
+        self.last = now;
+        self.advance(delta)
+    }
+
+    /// Accumulates `delta` as if it had passed between two ticks, capped at the maximum delta.
+    /// Returns the accumulated time.
+    pub fn advance(&mut self, delta: Duration) -> Duration {
+        let delta = if delta > self.max_delta { self.max_delta } else { delta };
+        self.accumulator += delta;
+        self.elapsed += delta;
+        delta
+    }
+
+    /// Consumes one fixed step from the accumulated time, returning `false` once less than a
+    /// step remains.
+    pub fn consume_step(&mut self) -> bool {
+        if self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns how far the accumulated time is into the next step, from `0.0` to `1.0`, for
+    /// interpolating between the previous and current simulation states.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.step.as_secs_f64()
+    }
+
+    /// Returns the time left until the next step is due, measured from the last tick.
+    pub fn timeout(&self) -> Duration {
+        self.step.checked_sub(self.accumulator).unwrap_or_default()
+    }
+
+    /// Returns the total time accumulated since the clock was created or reset.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Continues measuring from now, so that the time since the last tick isn't counted, while
+    /// keeping the accumulated time. Call this after a pause.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use rustty::time::Clock;
+    ///
+    /// let mut clock = Clock::new(Duration::from_millis(10));
+    /// thread::sleep(Duration::from_millis(20));
+    /// clock.resume();
+    /// assert!(clock.tick() < Duration::from_millis(10));
+    /// ```
+    pub fn resume(&mut self) {
+        self.last = Instant::now();
+    }
+
+    /// Restarts the clock from now, discarding any accumulated time, such as when starting over.
+    pub fn reset(&mut self) {
+        self.last = Instant::now();
+        self.accumulator = Duration::from_secs(0);
+        self.elapsed = Duration::from_secs(0);
+    }
+}
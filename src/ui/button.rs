@@ -1,12 +1,14 @@
 use core::cellbuffer::{Attr, CellAccessor};
+use core::width;
 use ui::widget::Widget;
 use ui::painter::Painter;
 
+// Returns the column of the first occurrence of `accel` in `s`, ignoring case.
 fn find_accel_char_index(s: &str, accel: char) -> Option<usize> {
     let lower_accel = accel.to_lowercase().next().unwrap_or(accel);
-    for (i, c) in s.chars().enumerate() {
+    for (i, c) in s.char_indices() {
         if c.to_lowercase().next().unwrap_or(c) == lower_accel {
-            return Some(i);
+            return Some(width::display_width(&s[..i]));
         }
     }
     None
@@ -14,7 +16,7 @@ fn find_accel_char_index(s: &str, accel: char) -> Option<usize> {
 
 pub fn create_button(text: &str, accel: Option<char>) -> Widget {
     let s = format!("< {} >", text);
-    let width = s.chars().count();
+    let width = width::display_width(&s);
     let mut widget = Widget::new(width, 1);
     widget.printline(0, 0, &s[..]);
     match accel {
@@ -0,0 +1,389 @@
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::{Attr, Cell, CellAccessor, Color};
+use core::features::{self, Feature};
+use core::width;
+use ui::format::{DefaultFormatter, Formatter};
+use ui::layout::Alignable;
+use ui::painter::Painter;
+use ui::widget::Widget;
+
+/// How data points are rendered by a `Chart`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Marker {
+    /// Braille patterns, giving 2×4 dots per cell.
+    Braille,
+    /// Half blocks, giving 1×2 dots per cell.
+    Block,
+}
+
+impl Marker {
+    fn resolution(self) -> Size {
+        match self {
+            Marker::Braille => (2, 4),
+            Marker::Block => (1, 2),
+        }
+    }
+
+    fn bit(self, x: usize, y: usize) -> u8 {
+        match self {
+            Marker::Braille => {
+                match (x, y) {
+                    (0, 3) => 0x40,
+                    (1, 3) => 0x80,
+                    (0, y) => 1 << y,
+                    (_, y) => 1 << (y + 3),
+                }
+            }
+            Marker::Block => 1 << y,
+        }
+    }
+
+    fn glyph(self, bits: u8) -> char {
+        match self {
+            Marker::Braille => ::std::char::from_u32(0x2800 + bits as u32).unwrap(),
+            Marker::Block => {
+                match bits {
+                    1 => '▀',
+                    2 => '▄',
+                    _ => '█',
+                }
+            }
+        }
+    }
+}
+
+/// How the points of a series are connected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeriesStyle {
+    /// Only the points themselves are drawn.
+    Scatter,
+    /// Consecutive points are joined by straight lines.
+    Line,
+}
+
+/// A named, colored set of data points plotted by a `Chart`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub color: Color,
+    pub style: SeriesStyle,
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Series {
+    /// Constructs a new, empty `Series`.
+    pub fn new(name: &str, color: Color, style: SeriesStyle) -> Series {
+        Series {
+            name: name.to_owned(),
+            color,
+            style,
+            points: Vec::new(),
+        }
+    }
+}
+
+/// A plot of one or more data series, with axes, tick labels and a legend.
+///
+/// The domain of each axis is computed from the data unless fixed with `set_x_bounds()` or
+/// `set_y_bounds()`. The y axis is labelled with its minimum, middle and maximum values on the
+/// left, and the x axis with its minimum and maximum values below. When any series is named, a
+/// legend is drawn in the top right corner of the plot area.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, Color};
+/// use rustty::ui::{Chart, Series, SeriesStyle};
+///
+/// let mut load = Series::new("load", Color::Green, SeriesStyle::Line);
+/// load.points = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 2.0)];
+///
+/// let mut chart = Chart::new(30, 8);
+/// chart.add_series(load);
+/// chart.draw();
+///
+/// assert_eq!(chart.bounds(), ((0.0, 2.0), (1.0, 3.0)));
+/// // The y axis labels.
+/// assert_eq!(chart.window().get(0, 0).unwrap().ch(), '3');
+/// assert_eq!(chart.window().get(0, 5).unwrap().ch(), '1');
+/// ```
+pub struct Chart {
+    window: Widget,
+    series: Vec<Series>,
+    marker: Marker,
+    x_bounds: Option<(f64, f64)>,
+    y_bounds: Option<(f64, f64)>,
+    formatter: Box<dyn Formatter>,
+}
+
+impl Chart {
+    /// Constructs a new, empty `Chart` of the given size, plotted with braille markers.
+    pub fn new(cols: usize, rows: usize) -> Chart {
+        Chart {
+            window: Widget::new(cols, rows),
+            series: Vec::new(),
+            marker: Marker::Braille,
+            x_bounds: None,
+            y_bounds: None,
+            formatter: Box::new(DefaultFormatter),
+        }
+    }
+
+    pub fn window(&self) -> &Widget {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut Widget {
+        &mut self.window
+    }
+
+    /// Sets how data points are rendered.
+    pub fn set_marker(&mut self, marker: Marker) {
+        self.marker = marker;
+    }
+
+    /// Sets how the axis labels are formatted, with `Formatter::number()`.
+    pub fn set_formatter<F: Formatter + 'static>(&mut self, formatter: F) {
+        self.formatter = Box::new(formatter);
+    }
+
+    /// Adds a series, drawn above the series already added.
+    pub fn add_series(&mut self, series: Series) {
+        self.series.push(series);
+    }
+
+    /// Returns the series of the chart.
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Returns the series of the chart mutably, for example to append new data points.
+    pub fn series_mut(&mut self) -> &mut Vec<Series> {
+        &mut self.series
+    }
+
+    /// Fixes the domain of the x axis, or scales it to the data if `None`.
+    pub fn set_x_bounds(&mut self, bounds: Option<(f64, f64)>) {
+        self.x_bounds = bounds;
+    }
+
+    /// Fixes the domain of the y axis, or scales it to the data if `None`.
+    ///
+    /// Lines leading out of fixed bounds are cut at the edge of the plot, and points outside them
+    /// are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{CellAccessor, Color};
+    /// use rustty::ui::{Chart, Series, SeriesStyle};
+    ///
+    /// let mut spike = Series::new("", Color::Red, SeriesStyle::Line);
+    /// spike.points = vec![(0.0, 0.0), (1.0, 1e300), (2.0, 0.0)];
+    ///
+    /// let mut chart = Chart::new(20, 6);
+    /// chart.add_series(spike);
+    /// chart.set_y_bounds(Some((0.0, 1.0)));
+    /// chart.draw();
+    /// // The lines run up to the top of the plot.
+    /// assert!((0..20).any(|x| chart.window().get(x, 0).unwrap().fg() == Color::Red));
+    /// ```
+    pub fn set_y_bounds(&mut self, bounds: Option<(f64, f64)>) {
+        self.y_bounds = bounds;
+    }
+
+    /// Returns the `((min_x, max_x), (min_y, max_y))` domain of the plot.
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points = || self.series.iter().flat_map(|s| s.points.iter());
+        let x = self.x_bounds.unwrap_or_else(|| extent(points().map(|p| p.0)));
+        let y = self.y_bounds.unwrap_or_else(|| extent(points().map(|p| p.1)));
+        (x, y)
+    }
+
+    /// Redraws the axes, data and legend into the chart's window.
+    pub fn draw(&mut self) {
+        let (cols, rows) = self.window.size();
+        self.window.clear(Cell::default());
+
+        let ((x0, x1), (y0, y1)) = self.bounds();
+        let formatter = &self.formatter;
+        let label = |v| formatter.number(v);
+        let ylabels = [label(y1), label((y0 + y1) / 2.0), label(y0)];
+        let lw = ylabels.iter().map(|l| width::display_width(l)).max().unwrap();
+        if rows < 3 || cols < lw + 3 {
+            return;
+        }
+        let (plot_x, plot_w, plot_h) = (lw + 1, cols - lw - 1, rows - 2);
+
+        // Axes and tick labels.
+        let (v, corner, h) = if features::supported(Feature::LineDrawing) {
+            ('│', '└', '─')
+        } else {
+            ('|', '+', '-')
+        };
+        for y in 0..plot_h {
+            self.window.get_mut(lw, y).unwrap().set_ch(v);
+        }
+        self.window.get_mut(lw, plot_h).unwrap().set_ch(corner);
+        for x in plot_x..cols {
+            self.window.get_mut(x, plot_h).unwrap().set_ch(h);
+        }
+        for (&row, text) in [0, (plot_h - 1) / 2, plot_h - 1].iter().zip(ylabels.iter()) {
+            self.window.printline(lw - width::display_width(text), row, text);
+        }
+        let (left, right) = (label(x0), label(x1));
+        self.window.printline(plot_x, rows - 1, &left);
+        let rlen = width::display_width(&right);
+        if plot_w > width::display_width(&left) + rlen {
+            self.window.printline(cols - rlen, rows - 1, &right);
+        }
+
+        // Plot the series onto a grid of dots, coloring each cell by the last series in it.
+        let (sx, sy) = self.marker.resolution();
+        let (w, h) = (plot_w * sx, plot_h * sy);
+        let mut grid = vec![(0u8, Color::Default); plot_w * plot_h];
+        for series in &self.series {
+            let dots = series.points.iter().map(|&(x, y)| {
+                (scale(x, x0, x1, w), (h - 1) as f64 - scale(y, y0, y1, h))
+            });
+            let mut prev: Option<(f64, f64)> = None;
+            for dot in dots {
+                let mut plot = |(dx, dy): (isize, isize)| {
+                    if dx < 0 || dy < 0 || dx as usize >= w || dy as usize >= h {
+                        return;
+                    }
+                    let (dx, dy) = (dx as usize, dy as usize);
+                    let cell = &mut grid[(dy / sy) * plot_w + dx / sx];
+                    cell.0 |= self.marker.bit(dx % sx, dy % sy);
+                    cell.1 = series.color;
+                };
+                // Only the part of the line inside the plot is drawn, which keeps the number of
+                // dots bounded however far out of the domain the points are.
+                let segment = match prev {
+                    Some(from) if series.style == SeriesStyle::Line => clip(from, dot, w, h),
+                    _ => clip(dot, dot, w, h),
+                };
+                if let Some((from, to)) = segment {
+                    line(round(from), round(to), &mut plot);
+                }
+                prev = Some(dot);
+            }
+        }
+        for (i, &(bits, color)) in grid.iter().enumerate() {
+            if bits != 0 {
+                let cell = self.window.get_mut(plot_x + i % plot_w, i / plot_w).unwrap();
+                cell.set_ch(self.marker.glyph(bits)).set_fg(color);
+            }
+        }
+
+        // The legend, right-aligned in the top corner of the plot area.
+        let named = self.series.iter().filter(|s| !s.name.is_empty());
+        for (row, series) in named.take(plot_h).enumerate() {
+            let len = width::display_width(&series.name) + 2;
+            if len > plot_w {
+                continue;
+            }
+            let x = cols - len;
+            let swatch = Cell::with_style(series.color, Color::Default, Attr::Default);
+            self.window.printline_with_cell(x, row, "■", swatch);
+            self.window.printline(x + 1, row, &format!(" {}", series.name));
+        }
+    }
+}
+
+impl HasSize for Chart {
+    fn size(&self) -> Size {
+        self.window.size()
+    }
+}
+
+impl HasPosition for Chart {
+    fn origin(&self) -> Pos {
+        self.window.origin()
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.window.set_origin(new_origin);
+    }
+}
+
+impl Alignable for Chart {}
+
+// Returns the smallest and largest values, widened if they are equal so the domain isn't empty.
+fn extent<I: Iterator<Item = f64>>(values: I) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY),
+                                 |(min, max), v| (min.min(v), max.max(v)));
+    if min > max {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+// Maps `v` from the domain `min..max` onto `0..steps`, unrounded.
+fn scale(v: f64, min: f64, max: f64, steps: usize) -> f64 {
+    if max <= min {
+        return 0.0;
+    }
+    (v - min) / (max - min) * (steps as f64 - 1.0)
+}
+
+// Returns the nearest dot to `p`.
+fn round(p: (f64, f64)) -> (isize, isize) {
+    (p.0.round() as isize, p.1.round() as isize)
+}
+
+// Returns the part of the segment from `from` to `to` inside a grid of `w` by `h` dots, or `None`
+// if it lies wholly outside, using the Liang-Barsky algorithm.
+fn clip(from: (f64, f64), to: (f64, f64), w: usize, h: usize) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let (max_x, max_y) = ((w - 1) as f64, (h - 1) as f64);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for &(p, q) in &[(-dx, from.0), (dx, max_x - from.0), (-dy, from.1), (dy, max_y - from.1)] {
+        if !q.is_finite() || !p.is_finite() {
+            return None;
+        }
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let at = |t: f64| (from.0 + t * dx, from.1 + t * dy);
+    Some((at(t0), at(t1)))
+}
+
+// Calls `plot` for each dot on the line between `from` and `to`, using Bresenham's algorithm.
+fn line<F: FnMut((isize, isize))>(from: (isize, isize), to: (isize, isize), mut plot: F) {
+    let (mut x, mut y) = from;
+    let (dx, dy) = ((to.0 - x).abs(), -(to.1 - y).abs());
+    let (step_x, step_y) = (if x < to.0 { 1 } else { -1 }, if y < to.1 { 1 } else { -1 });
+    let mut err = dx + dy;
+    loop {
+        plot((x, y));
+        if (x, y) == to {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+
+/// Number of entries a `Clipboard` keeps by default.
+const DEFAULT_CAPACITY: usize = 32;
+
+/// Where copied text goes besides the ring of a `Clipboard`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ClipboardBackend {
+    /// Text stays within the application.
+    #[default]
+    Internal,
+    /// Text is also meant for the system clipboard: `Clipboard::take_export()` returns it for
+    /// `Terminal::set_system_clipboard()`, which many terminals support, including over SSH.
+    /// Pasting still reads the ring, since terminals rarely let applications read the system
+    /// clipboard.
+    Osc52,
+}
+
+/// Cut, copy and paste shared by editing widgets, kept as a ring of recent entries like the
+/// Emacs kill ring.
+///
+/// Editing widgets holding the same `Clipboard` behave consistently: `EditAction::KillToEnd`,
+/// `EditAction::KillToStart` and the like `copy()` the text they remove, and `EditAction::Yank`
+/// inserts what `paste()` returns. Repeated kills can `append()` to the latest entry, and
+/// `rotate()` steps back through older entries after a paste.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::Clipboard;
+///
+/// let mut clipboard = Clipboard::new();
+/// clipboard.copy("first");
+/// clipboard.copy("second");
+/// clipboard.append(" half");
+///
+/// assert_eq!(clipboard.paste(), Some("second half"));
+/// assert_eq!(clipboard.rotate(), Some("first"));
+/// assert_eq!(clipboard.rotate(), Some("second half"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clipboard {
+    ring: VecDeque<String>,
+    capacity: usize,
+    backend: ClipboardBackend,
+    yanked: usize, // Index of the entry last pasted or rotated to.
+    exported: bool, // Whether the latest entry has been taken by `take_export()`.
+}
+
+impl Clipboard {
+    /// Constructs an empty clipboard with the internal backend.
+    pub fn new() -> Clipboard {
+        Clipboard::with_backend(ClipboardBackend::Internal)
+    }
+
+    /// Constructs an empty clipboard with the given backend.
+    pub fn with_backend(backend: ClipboardBackend) -> Clipboard {
+        Clipboard {
+            ring: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            backend,
+            yanked: 0,
+            exported: true,
+        }
+    }
+
+    /// Returns the backend.
+    pub fn backend(&self) -> ClipboardBackend {
+        self.backend
+    }
+
+    /// Sets the backend.
+    pub fn set_backend(&mut self, backend: ClipboardBackend) {
+        self.backend = backend;
+    }
+
+    /// Sets the number of entries kept, dropping the oldest ones beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.ring.truncate(self.capacity);
+        self.yanked = 0;
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns `true` if nothing has been copied.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.ring.clear();
+        self.yanked = 0;
+    }
+
+    /// Adds `text` as the latest entry. Empty text is ignored.
+    pub fn copy(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.ring.push_front(text.to_owned());
+        self.ring.truncate(self.capacity);
+        self.yanked = 0;
+        self.exported = false;
+    }
+
+    /// Appends `text` to the latest entry, as when killing several words in a row, or adds it as
+    /// a new entry if there is none.
+    pub fn append(&mut self, text: &str) {
+        match self.ring.front_mut() {
+            Some(latest) => latest.push_str(text),
+            None => return self.copy(text),
+        }
+        self.yanked = 0;
+        self.exported &= text.is_empty();
+    }
+
+    /// Returns the latest entry.
+    pub fn paste(&mut self) -> Option<&str> {
+        self.yanked = 0;
+        self.ring.front().map(|text| &text[..])
+    }
+
+    /// Returns the entry before the one last pasted or rotated to, wrapping around to the latest
+    /// after the oldest. An editor replaces the text it just pasted with it.
+    pub fn rotate(&mut self) -> Option<&str> {
+        if self.ring.is_empty() {
+            return None;
+        }
+        self.yanked = (self.yanked + 1) % self.ring.len();
+        self.ring.get(self.yanked).map(|text| &text[..])
+    }
+
+    /// Returns the latest entry if the backend is `ClipboardBackend::Osc52` and the entry changed
+    /// since the last call, for handing to `Terminal::set_system_clipboard()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{Clipboard, ClipboardBackend};
+    ///
+    /// let mut clipboard = Clipboard::with_backend(ClipboardBackend::Osc52);
+    /// clipboard.copy("hello");
+    ///
+    /// assert_eq!(clipboard.take_export(), Some("hello"));
+    /// assert_eq!(clipboard.take_export(), None);
+    /// ```
+    pub fn take_export(&mut self) -> Option<&str> {
+        if self.exported || self.backend != ClipboardBackend::Osc52 {
+            return None;
+        }
+        self.exported = true;
+        self.ring.front().map(|text| &text[..])
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Clipboard {
+        Clipboard::new()
+    }
+}
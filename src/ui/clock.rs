@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::{Cell, CellAccessor};
+use ui::layout::Alignable;
+use ui::painter::Painter;
+use ui::widget::Widget;
+
+/// What a `Clock` shows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClockMode {
+    /// The time the clock has been running, like a stopwatch.
+    Elapsed,
+    /// The current wall time, in UTC.
+    Wall,
+}
+
+// The values substituted into a format string.
+struct Fields {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u64,
+    minute: u64,
+    second: u64,
+    millis: u32,
+}
+
+/// A single-line widget showing a stopwatch or the wall time.
+///
+/// The time is formatted with a format string where `%H`, `%M` and `%S` are replaced by hours,
+/// minutes and seconds, `%f` by milliseconds, `%Y`, `%m` and `%d` by the year, month and day, and
+/// `%%` by a percent sign. For elapsed time `%H` counts all hours rather than wrapping at 24, and
+/// the date fields are zero. Wall time is always UTC, so no timezone database is needed.
+///
+/// Call `tick()` on every tick event; it redraws the clock when the displayed text changes.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use rustty::ui::{Clock, ClockMode};
+///
+/// let d = Duration::from_millis(3_723_045);
+/// assert_eq!(Clock::format_elapsed("%H:%M:%S.%f", d), "01:02:03.045");
+///
+/// let t = UNIX_EPOCH + Duration::from_secs(951_827_696);
+/// assert_eq!(Clock::format_wall("%Y-%m-%d %H:%M", t), "2000-02-29 12:34");
+///
+/// let mut stopwatch = Clock::new(ClockMode::Elapsed, 8);
+/// assert!(!stopwatch.is_running());
+/// assert_eq!(stopwatch.text(), "00:00:00");
+/// stopwatch.start();
+/// assert!(stopwatch.is_running());
+/// ```
+pub struct Clock {
+    window: Widget,
+    mode: ClockMode,
+    format: String,
+    started: Option<Instant>,
+    accumulated: Duration,
+    shown: String,
+}
+
+impl Clock {
+    /// Constructs a new `Clock` of the given width.
+    ///
+    /// Elapsed clocks start stopped and use the format `%H:%M:%S`; wall clocks use
+    /// `%Y-%m-%d %H:%M:%S`.
+    pub fn new(mode: ClockMode, cols: usize) -> Clock {
+        let format = match mode {
+            ClockMode::Elapsed => "%H:%M:%S",
+            ClockMode::Wall => "%Y-%m-%d %H:%M:%S",
+        };
+        let mut clock = Clock {
+            window: Widget::new(cols, 1),
+            mode,
+            format: format.to_owned(),
+            started: None,
+            accumulated: Duration::from_secs(0),
+            shown: String::new(),
+        };
+        clock.draw();
+        clock
+    }
+
+    pub fn window(&self) -> &Widget {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut Widget {
+        &mut self.window
+    }
+
+    /// Sets the format string and redraws the clock.
+    pub fn set_format(&mut self, format: &str) {
+        self.format = format.to_owned();
+        self.draw();
+    }
+
+    /// Starts or resumes the stopwatch.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Pauses the stopwatch, keeping the time elapsed so far.
+    pub fn stop(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.accumulated += started.elapsed();
+        }
+    }
+
+    /// Resets the stopwatch to zero, leaving it running if it was.
+    pub fn reset(&mut self) {
+        self.accumulated = Duration::from_secs(0);
+        if self.started.is_some() {
+            self.started = Some(Instant::now());
+        }
+        self.draw();
+    }
+
+    /// Returns `true` if the stopwatch is running.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Returns the time the stopwatch has been running.
+    pub fn elapsed(&self) -> Duration {
+        self.accumulated + self.started.map_or(Duration::from_secs(0), |s| s.elapsed())
+    }
+
+    /// Returns the text the clock shows at this moment.
+    pub fn text(&self) -> String {
+        match self.mode {
+            ClockMode::Elapsed => Clock::format_elapsed(&self.format, self.elapsed()),
+            ClockMode::Wall => Clock::format_wall(&self.format, SystemTime::now()),
+        }
+    }
+
+    /// Redraws the clock if its text has changed, returning `true` if it did.
+    pub fn tick(&mut self) -> bool {
+        if self.text() == self.shown {
+            false
+        } else {
+            self.draw();
+            true
+        }
+    }
+
+    /// Redraws the clock into its window.
+    pub fn draw(&mut self) {
+        self.shown = self.text();
+        self.window.clear(Cell::default());
+        let text: String = self.shown.chars().take(self.window.size().0).collect();
+        self.window.printline(0, 0, &text);
+    }
+
+    /// Formats a duration as elapsed time.
+    pub fn format_elapsed(format: &str, d: Duration) -> String {
+        let secs = d.as_secs();
+        render(format,
+               &Fields {
+                   year: 0,
+                   month: 0,
+                   day: 0,
+                   hour: secs / 3600,
+                   minute: secs / 60 % 60,
+                   second: secs % 60,
+                   millis: d.subsec_millis(),
+               })
+    }
+
+    /// Formats a point in time as UTC wall time. Times before 1970 are formatted as 1970.
+    pub fn format_wall(format: &str, t: SystemTime) -> String {
+        let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = d.as_secs();
+        let (year, month, day) = civil_from_days((secs / 86400) as i64);
+        render(format,
+               &Fields {
+                   year,
+                   month,
+                   day,
+                   hour: secs / 3600 % 24,
+                   minute: secs / 60 % 60,
+                   second: secs % 60,
+                   millis: d.subsec_millis(),
+               })
+    }
+}
+
+impl HasSize for Clock {
+    fn size(&self) -> Size {
+        self.window.size()
+    }
+}
+
+impl HasPosition for Clock {
+    fn origin(&self) -> Pos {
+        self.window.origin()
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.window.set_origin(new_origin);
+    }
+}
+
+impl Alignable for Clock {}
+
+fn render(format: &str, f: &Fields) -> String {
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&format!("{:04}", f.year)),
+            Some('m') => out.push_str(&format!("{:02}", f.month)),
+            Some('d') => out.push_str(&format!("{:02}", f.day)),
+            Some('H') => out.push_str(&format!("{:02}", f.hour)),
+            Some('M') => out.push_str(&format!("{:02}", f.minute)),
+            Some('S') => out.push_str(&format!("{:02}", f.second)),
+            Some('f') => out.push_str(&format!("{:03}", f.millis)),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+// Converts days since 1970-01-01 to a (year, month, day) date in the proleptic Gregorian
+// calendar, after Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,200 @@
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::{Attr, Cell, CellAccessor, Color};
+use ui::layout::Alignable;
+use ui::painter::{Painter, Orientation};
+use ui::widget::Widget;
+
+// Each color swatch is two cells wide so it looks roughly square.
+const SWATCH_WIDTH: usize = 2;
+
+/// The colors offered by a `ColorPicker`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorGrid {
+    /// The 16 basic and bright colors, in a grid of 8 columns and 2 rows.
+    Basic,
+    /// The full 8-bit color range, in a grid of 16 columns and 16 rows.
+    Extended,
+}
+
+impl ColorGrid {
+    fn dims(&self) -> Size {
+        match *self {
+            ColorGrid::Basic => (8, 2),
+            ColorGrid::Extended => (16, 16),
+        }
+    }
+}
+
+/// A keyboard driven color chooser.
+///
+/// A `ColorPicker` displays a grid of color swatches inside a box, with the index of the
+/// highlighted color on the bottom line. The highlight is moved with `h`, `j`, `k` and `l`, and
+/// `Enter` chooses the highlighted color.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::Color;
+/// use rustty::ui::{ColorPicker, ColorGrid};
+///
+/// let mut picker = ColorPicker::new(ColorGrid::Basic);
+/// assert_eq!(picker.selected(), Color::Black);
+///
+/// picker.handle_key('l');
+/// picker.handle_key('j');
+/// assert_eq!(picker.selected(), Color::Byte(9));
+///
+/// assert_eq!(picker.handle_key('\r'), Some(Color::Byte(9)));
+/// ```
+pub struct ColorPicker {
+    window: Widget,
+    grid: ColorGrid,
+    cursor: Pos,
+}
+
+impl ColorPicker {
+    /// Constructs a new `ColorPicker` showing the given `ColorGrid`, with the first color
+    /// highlighted.
+    pub fn new(grid: ColorGrid) -> ColorPicker {
+        let (gcols, grows) = grid.dims();
+        let mut picker = ColorPicker {
+            window: Widget::new(gcols * SWATCH_WIDTH + 2, grows + 3),
+            grid,
+            cursor: (0, 0),
+        };
+        picker.draw();
+        picker
+    }
+
+    pub fn window(&self) -> &Widget {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut Widget {
+        &mut self.window
+    }
+
+    /// Returns the highlighted `Color`.
+    ///
+    /// The eight basic colors are returned as their named `Color` variants, all others as
+    /// `Color::Byte(..)`.
+    pub fn selected(&self) -> Color {
+        let (gcols, _) = self.grid.dims();
+        let (x, y) = self.cursor;
+        color_at(y * gcols + x)
+    }
+
+    /// Highlights the given `Color`, if it is part of the picker's grid.
+    ///
+    /// Returns `false` if the color is not offered by the picker.
+    pub fn select(&mut self, color: Color) -> bool {
+        let (gcols, grows) = self.grid.dims();
+        let index = match color {
+            Color::Default | Color::Slot(_) | Color::Rgb(..) => return false,
+            c => c.as_byte() as usize,
+        };
+        if index >= gcols * grows {
+            return false;
+        }
+        self.cursor = (index % gcols, index / gcols);
+        self.draw();
+        true
+    }
+
+    /// Handles a key press, moving the highlight with `h`, `j`, `k` and `l`.
+    ///
+    /// Returns `Some(Color)` when `Enter` chooses the highlighted color, and `None` otherwise.
+    pub fn handle_key(&mut self, key: char) -> Option<Color> {
+        let (gcols, grows) = self.grid.dims();
+        let (x, y) = self.cursor;
+        self.cursor = match key {
+            'h' => (x.saturating_sub(1), y),
+            'l' if x + 1 < gcols => (x + 1, y),
+            'k' => (x, y.saturating_sub(1)),
+            'j' if y + 1 < grows => (x, y + 1),
+            '\r' | '\n' => return Some(self.selected()),
+            _ => return None,
+        };
+        self.draw();
+        None
+    }
+
+    /// Redraws the swatches, highlight and status line into the picker's window.
+    pub fn draw(&mut self) {
+        let (gcols, grows) = self.grid.dims();
+        self.window.clear(Cell::default());
+        for gy in 0..grows {
+            for gx in 0..gcols {
+                let color = color_at(gy * gcols + gx);
+                let mut swatch = Cell::with_style(Color::Default, color, Attr::Default);
+                if (gx, gy) == self.cursor {
+                    // Mark the highlighted swatch with brackets in a contrasting color.
+                    swatch.set_fg(contrast(color)).set_attrs(Attr::Bold);
+                    self.window.printline_with_cell(1 + gx * SWATCH_WIDTH, 1 + gy, "[]", swatch);
+                } else {
+                    self.window.repeat_cell(1 + gx * SWATCH_WIDTH,
+                                            1 + gy,
+                                            Orientation::Horizontal,
+                                            SWATCH_WIDTH,
+                                            swatch);
+                }
+            }
+        }
+        let status = format!("color {}", self.selected().as_byte());
+        self.window.printline(1, grows + 1, &status);
+        self.window.draw_box();
+    }
+}
+
+impl HasSize for ColorPicker {
+    fn size(&self) -> Size {
+        self.window.size()
+    }
+}
+
+impl HasPosition for ColorPicker {
+    fn origin(&self) -> Pos {
+        self.window.origin()
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.window.set_origin(new_origin);
+    }
+}
+
+impl Alignable for ColorPicker {}
+
+// Returns the color at `index` in the 8-bit color range.
+fn color_at(index: usize) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::White,
+        i => Color::Byte(i as u8),
+    }
+}
+
+// Picks black or white, whichever stands out more against `color`.
+fn contrast(color: Color) -> Color {
+    let byte = color.as_byte();
+    let light = match byte {
+        0..=15 => [3, 6, 7, 10, 11, 14, 15].contains(&byte),
+        16..=231 => {
+            // Color cube: weight the green component highest, as perceived brightness does.
+            let i = byte - 16;
+            let (r, g, b) = (i / 36, (i / 6) % 6, i % 6);
+            2 * r as u16 + 4 * g as u16 + b as u16 > 17
+        }
+        _ => byte >= 244,
+    };
+    if light {
+        Color::Black
+    } else {
+        Color::White
+    }
+}
@@ -0,0 +1,203 @@
+use std::cmp;
+
+use core::position::{Pos, Size, HasPosition};
+use core::cellbuffer::{Attr, Cell, Color};
+use core::width;
+use ui::painter::Painter;
+use ui::widget::Widget;
+
+/// Callback returning the completion candidates for a token.
+type Provider = Box<dyn Fn(&str) -> Vec<String>>;
+
+/// A completion engine with a popup list of candidates.
+///
+/// A `Completer` asks its provider callback for the candidates matching the token under the
+/// cursor, then lets the user cycle through them (typically bound to Tab) and accept one. The
+/// candidate list can be rendered as a popup `Widget` anchored at the cursor cell, which is
+/// moved above the cursor or to the left whenever it would otherwise run off screen.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::Completer;
+///
+/// let commands = vec!["open", "options", "quit"];
+/// let mut completer = Completer::new(move |token| {
+///     commands.iter().filter(|c| c.starts_with(token)).map(|c| c.to_string()).collect()
+/// });
+///
+/// assert_eq!(completer.complete("op"), 2);
+/// assert_eq!(completer.cycle(), Some("open"));
+/// assert_eq!(completer.cycle(), Some("options"));
+/// assert_eq!(completer.cycle(), Some("open"));
+/// assert_eq!(completer.accept(), Some("open".to_string()));
+/// assert!(!completer.is_active());
+/// ```
+pub struct Completer {
+    provider: Provider,
+    candidates: Vec<String>,
+    selected: Option<usize>,
+    max_rows: usize,
+}
+
+impl Completer {
+    /// Constructs a new `Completer` using `provider` to look up the candidates for a token.
+    pub fn new<F>(provider: F) -> Completer
+        where F: Fn(&str) -> Vec<String> + 'static
+    {
+        Completer {
+            provider: Box::new(provider),
+            candidates: Vec::new(),
+            selected: None,
+            max_rows: 8,
+        }
+    }
+
+    /// Sets the maximum number of candidates shown in the popup at once.
+    pub fn set_max_rows(&mut self, rows: usize) {
+        self.max_rows = cmp::max(rows, 1);
+    }
+
+    /// Queries the provider for `token` and returns the number of candidates found.
+    ///
+    /// Any previous selection is discarded.
+    pub fn complete(&mut self, token: &str) -> usize {
+        self.candidates = (self.provider)(token);
+        self.selected = None;
+        self.candidates.len()
+    }
+
+    /// Returns `true` if there are candidates to choose from.
+    pub fn is_active(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+
+    /// Returns the current candidates.
+    pub fn candidates(&self) -> &[String] {
+        &self.candidates
+    }
+
+    /// Returns the selected candidate, if any.
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.map(|i| &self.candidates[i][..])
+    }
+
+    /// Selects the next candidate, wrapping around after the last one, and returns it.
+    pub fn cycle(&mut self) -> Option<&str> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        let next = match self.selected {
+            Some(i) => (i + 1) % self.candidates.len(),
+            None => 0,
+        };
+        self.selected = Some(next);
+        self.selected()
+    }
+
+    /// Selects the previous candidate, wrapping around before the first one, and returns it.
+    pub fn cycle_back(&mut self) -> Option<&str> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        let prev = match self.selected {
+            Some(0) | None => self.candidates.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.selected = Some(prev);
+        self.selected()
+    }
+
+    /// Accepts the selected candidate and closes the completion.
+    ///
+    /// If nothing is selected but there is exactly one candidate, that candidate is accepted.
+    pub fn accept(&mut self) -> Option<String> {
+        let accepted = match self.selected {
+            Some(i) => Some(self.candidates.swap_remove(i)),
+            None if self.candidates.len() == 1 => self.candidates.pop(),
+            None => None,
+        };
+        if accepted.is_some() {
+            self.dismiss();
+        }
+        accepted
+    }
+
+    /// Closes the completion without accepting a candidate.
+    pub fn dismiss(&mut self) {
+        self.candidates.clear();
+        self.selected = None;
+    }
+
+    /// Renders the candidates as a popup `Widget` for a cursor at `anchor` on a screen of size
+    /// `screen`.
+    ///
+    /// The popup is placed on the row below the anchor, or above it if there is not enough room
+    /// below, and shifted left as needed to stay on screen. The selected candidate is drawn in
+    /// reverse video. Returns `None` if there are no candidates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{HasSize, HasPosition};
+    /// use rustty::ui::Completer;
+    ///
+    /// let mut completer = Completer::new(|_| vec!["alpha".to_string(), "beta".to_string()]);
+    /// completer.complete("");
+    ///
+    /// // Near the bottom-right corner, the popup opens above and to the left of the cursor.
+    /// let popup = completer.popup((78, 23), (80, 24)).unwrap();
+    /// assert_eq!(popup.size(), (7, 2));
+    /// assert_eq!(popup.origin(), (73, 21));
+    ///
+    /// // Candidates are measured in columns, so wide characters count twice.
+    /// let mut completer = Completer::new(|_| vec!["漢字".to_string()]);
+    /// completer.complete("");
+    /// assert_eq!(completer.popup((0, 0), (80, 24)).unwrap().size(), (6, 1));
+    /// ```
+    pub fn popup(&self, anchor: Pos, screen: Size) -> Option<Widget> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        let (ax, ay) = anchor;
+        let (cols, rows) = screen;
+
+        let nrows = cmp::min(self.candidates.len(), self.max_rows);
+        let width = self.candidates.iter().map(|c| width::display_width(c)).max().unwrap_or(0) + 2;
+
+        // Scroll the visible window so the selected candidate is always shown.
+        let first = match self.selected {
+            Some(i) if i >= nrows => i + 1 - nrows,
+            _ => 0,
+        };
+
+        let mut popup = Widget::new(width, nrows);
+        let normal = Cell::with_style(Color::Default, Color::Default, Attr::Default);
+        let highlight = Cell::with_style(Color::Default, Color::Default, Attr::Reverse);
+        for (row, index) in (first..first + nrows).enumerate() {
+            let cell = if Some(index) == self.selected {
+                highlight
+            } else {
+                normal
+            };
+            // Padded by display width, so that wide candidates are highlighted to the same edge.
+            let candidate = &self.candidates[index];
+            let pad = width - 1 - width::display_width(candidate);
+            let line = format!(" {}{}", candidate, " ".repeat(pad));
+            popup.printline_with_cell(0, row, &line, cell);
+        }
+
+        let x = if ax + width > cols {
+            cols.saturating_sub(width)
+        } else {
+            ax
+        };
+        let y = if ay + 1 + nrows <= rows {
+            ay + 1
+        } else {
+            ay.saturating_sub(nrows)
+        };
+        popup.set_origin((x, y));
+        Some(popup)
+    }
+}
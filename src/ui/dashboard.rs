@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::{Attr, Cell, CellAccessor, Color};
+use ui::format::{DefaultFormatter, Formatter};
+use ui::layout::{Alignable, HorizontalAlign};
+use ui::painter::{Painter, Orientation};
+use ui::widget::Widget;
+
+// Eighths of a block, from empty to full, used by sparklines.
+const SPARKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// What a `Dashboard` slot displays.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SlotView {
+    /// A horizontal bar showing the latest value within `min..max`.
+    Gauge { min: f64, max: f64 },
+    /// A bar graph of the most recent values, scaled to their range.
+    Sparkline,
+    /// The most recent lines of text, newest at the bottom.
+    Log,
+}
+
+/// A value pushed into a `Dashboard` slot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotValue {
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for SlotValue {
+    fn from(v: f64) -> SlotValue {
+        SlotValue::Number(v)
+    }
+}
+
+impl<'a> From<&'a str> for SlotValue {
+    fn from(s: &'a str) -> SlotValue {
+        SlotValue::Text(s.to_owned())
+    }
+}
+
+impl From<String> for SlotValue {
+    fn from(s: String) -> SlotValue {
+        SlotValue::Text(s)
+    }
+}
+
+struct Slot {
+    name: String,
+    view: SlotView,
+    pos: Pos,
+    span: Size,
+    numbers: VecDeque<f64>,
+    lines: VecDeque<String>,
+}
+
+/// A grid of named, boxed slots showing live values.
+///
+/// The dashboard's window is divided into a grid of equally sized cells, with leftover columns
+/// and rows going to the last cells, and each slot covers a rectangle of grid cells. The slots
+/// are resized with the window, and values are pushed into them by name from the update loop.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::CellAccessor;
+/// use rustty::ui::{Dashboard, SlotView};
+///
+/// let mut dash = Dashboard::new(40, 12, (2, 2));
+/// dash.add_slot("cpu", SlotView::Gauge { min: 0.0, max: 100.0 }, (0, 0), (1, 1));
+/// dash.add_slot("net", SlotView::Sparkline, (1, 0), (1, 1));
+/// dash.add_slot("log", SlotView::Log, (0, 1), (2, 1));
+///
+/// assert!(dash.push("cpu", 50.0));
+/// assert!(dash.push("net", 1.0));
+/// assert!(dash.push("log", "started"));
+/// assert!(!dash.push("disk", 1.0));
+/// dash.draw();
+///
+/// assert_eq!(dash.slot_rect("log"), Some(((0, 6), (40, 6))));
+/// // The slot name is shown in its border.
+/// assert_eq!(dash.window().get(4, 0).unwrap().ch(), 'c');
+/// ```
+pub struct Dashboard {
+    window: Widget,
+    grid: Size,
+    slots: Vec<Slot>,
+    history: usize,
+    formatter: Box<dyn Formatter>,
+}
+
+impl Dashboard {
+    /// Constructs a new, empty `Dashboard` of the given size, divided into a grid of
+    /// `(grid_cols, grid_rows)` cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either grid dimension is zero.
+    pub fn new(cols: usize, rows: usize, grid: Size) -> Dashboard {
+        assert!(grid.0 > 0 && grid.1 > 0, "empty dashboard grid");
+        Dashboard {
+            window: Widget::new(cols, rows),
+            grid,
+            slots: Vec::new(),
+            history: 256,
+            formatter: Box::new(DefaultFormatter),
+        }
+    }
+
+    pub fn window(&self) -> &Widget {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut Widget {
+        &mut self.window
+    }
+
+    /// Resizes the dashboard, keeping its position, slots and values.
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let origin = self.window.origin();
+        self.window = Widget::new(cols, rows);
+        self.window.set_origin(origin);
+    }
+
+    /// Sets how many values or lines each slot keeps. The default is 256.
+    pub fn set_history(&mut self, history: usize) {
+        self.history = history;
+    }
+
+    /// Sets how gauge readings are formatted, with `Formatter::number()`.
+    pub fn set_formatter<F: Formatter + 'static>(&mut self, formatter: F) {
+        self.formatter = Box::new(formatter);
+    }
+
+    /// Adds a slot covering `span` grid cells from grid cell `pos`.
+    ///
+    /// Returns `false` if a slot with the same name exists or the slot doesn't fit in the grid.
+    pub fn add_slot(&mut self, name: &str, view: SlotView, pos: Pos, span: Size) -> bool {
+        let fits = span.0 > 0 && span.1 > 0 && pos.0 + span.0 <= self.grid.0 &&
+                   pos.1 + span.1 <= self.grid.1;
+        if !fits || self.slots.iter().any(|s| s.name == name) {
+            return false;
+        }
+        self.slots.push(Slot {
+            name: name.to_owned(),
+            view,
+            pos,
+            span,
+            numbers: VecDeque::new(),
+            lines: VecDeque::new(),
+        });
+        true
+    }
+
+    /// Removes a slot and its values. Returns `false` if there is no such slot.
+    pub fn remove_slot(&mut self, name: &str) -> bool {
+        let len = self.slots.len();
+        self.slots.retain(|s| s.name != name);
+        self.slots.len() != len
+    }
+
+    /// Pushes a value into the named slot.
+    ///
+    /// Gauges and sparklines take numbers and logs take text. Returns `false` if there is no
+    /// such slot or it doesn't take that kind of value.
+    pub fn push<V: Into<SlotValue>>(&mut self, name: &str, value: V) -> bool {
+        let history = self.history;
+        let slot = match self.slots.iter_mut().find(|s| s.name == name) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        match (slot.view, value.into()) {
+            (SlotView::Log, SlotValue::Text(line)) => {
+                slot.lines.push_back(line);
+                while slot.lines.len() > history {
+                    slot.lines.pop_front();
+                }
+            }
+            (SlotView::Gauge { .. }, SlotValue::Number(v)) |
+            (SlotView::Sparkline, SlotValue::Number(v)) => {
+                slot.numbers.push_back(v);
+                while slot.numbers.len() > history {
+                    slot.numbers.pop_front();
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns the position and size of the named slot within the dashboard's window.
+    pub fn slot_rect(&self, name: &str) -> Option<(Pos, Size)> {
+        self.slots.iter().find(|s| s.name == name).map(|s| self.rect(s))
+    }
+
+    /// Redraws all slots into the dashboard's window.
+    pub fn draw(&mut self) {
+        self.window.clear(Cell::default());
+        for slot in &self.slots {
+            let ((x, y), (cols, rows)) = self.rect(slot);
+            if cols < 3 || rows < 3 {
+                continue;
+            }
+            let mut frame = Widget::new(cols, rows);
+            frame.draw_box();
+            frame.draw_title(&slot.name, HorizontalAlign::Left);
+            draw_view(slot, &*self.formatter, &mut frame, (cols - 2, rows - 2));
+            frame.set_origin((x, y));
+            frame.draw_into(&mut self.window);
+        }
+    }
+
+    fn rect(&self, slot: &Slot) -> (Pos, Size) {
+        let (cols, rows) = self.window.size();
+        let (x0, _) = split(cols, self.grid.0, slot.pos.0);
+        let (y0, _) = split(rows, self.grid.1, slot.pos.1);
+        let (x1, w1) = split(cols, self.grid.0, slot.pos.0 + slot.span.0 - 1);
+        let (y1, h1) = split(rows, self.grid.1, slot.pos.1 + slot.span.1 - 1);
+        ((x0, y0), (x1 + w1 - x0, y1 + h1 - y0))
+    }
+}
+
+impl HasSize for Dashboard {
+    fn size(&self) -> Size {
+        self.window.size()
+    }
+}
+
+impl HasPosition for Dashboard {
+    fn origin(&self) -> Pos {
+        self.window.origin()
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.window.set_origin(new_origin);
+    }
+}
+
+impl Alignable for Dashboard {}
+
+// Returns the start and length of grid cell `i` when `total` is divided into `n` cells, with the
+// remainder going to the last cell.
+fn split(total: usize, n: usize, i: usize) -> (usize, usize) {
+    let len = total / n;
+    if i + 1 == n {
+        (len * i, total - len * i)
+    } else {
+        (len * i, len)
+    }
+}
+
+// Draws a slot's values inside the border of `frame`, in an area of the given size.
+fn draw_view(slot: &Slot, formatter: &dyn Formatter, frame: &mut Widget, (cols, rows): Size) {
+    match slot.view {
+        SlotView::Gauge { min, max } => {
+            let v = match slot.numbers.back() {
+                Some(&v) => v,
+                None => return,
+            };
+            let frac = if max > min { ((v - min) / (max - min)).clamp(0.0, 1.0) } else { 0.0 };
+            let filled = (frac * cols as f64).round() as usize;
+            let bar = Cell::with_style(Color::Default, Color::Default, Attr::Reverse);
+            frame.repeat_cell(1, 1, Orientation::Horizontal, filled, bar);
+            let text = formatter.number(v);
+            if rows > 1 {
+                frame.printline(1, 2, &text);
+            }
+        }
+        SlotView::Sparkline => {
+            let skip = slot.numbers.len().saturating_sub(cols);
+            let values: Vec<f64> = slot.numbers.iter().skip(skip).cloned().collect();
+            let lo = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let hi = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let levels = rows * 8;
+            for (i, &v) in values.iter().enumerate() {
+                let frac = if hi > lo { (v - lo) / (hi - lo) } else { 1.0 };
+                let height = ((frac * levels as f64).round() as usize).max(1);
+                for row in 0..rows {
+                    let eighths = height.saturating_sub(row * 8).min(8);
+                    frame.get_mut(1 + i, rows - row).unwrap().set_ch(SPARKS[eighths]);
+                }
+            }
+        }
+        SlotView::Log => {
+            let skip = slot.lines.len().saturating_sub(rows);
+            for (row, line) in slot.lines.iter().skip(skip).enumerate() {
+                let line: String = line.chars().take(cols).collect();
+                frame.printline(1, 1 + row, &line);
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::{Cell, CellAccessor};
+use core::features::{self, Feature};
+use core::width;
+use ui::draw::Draw;
+use ui::layout::{Alignable, HorizontalAlign, Measure};
+
+/// The glyphs a border is drawn with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    /// `┌─┐` light lines.
+    #[default]
+    Single,
+    /// `╔═╗` double lines.
+    Double,
+    /// `╭─╮` light lines with rounded corners.
+    Rounded,
+    /// `┏━┓` heavy lines.
+    Heavy,
+    /// `+-+` plain ASCII, for terminals without box drawing characters. The other styles fall
+    /// back to this one when `Feature::LineDrawing` is not supported.
+    Ascii,
+}
+
+impl BorderStyle {
+    // Returns the style borders are actually drawn in.
+    fn resolve(self) -> BorderStyle {
+        if features::supported(Feature::LineDrawing) {
+            self
+        } else {
+            BorderStyle::Ascii
+        }
+    }
+
+    // Returns the top-left, top-right, bottom-right and bottom-left corners followed by the
+    // horizontal and vertical lines.
+    fn glyphs(self) -> [char; 6] {
+        match self {
+            BorderStyle::Single => ['┌', '┐', '┘', '└', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╝', '╚', '═', '║'],
+            BorderStyle::Rounded => ['╭', '╮', '╯', '╰', '─', '│'],
+            BorderStyle::Heavy => ['┏', '┓', '┛', '┗', '━', '┃'],
+            BorderStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+
+    // Returns the glyphs placed before and after a title in the top edge.
+    fn separators(self) -> (char, char) {
+        match self {
+            BorderStyle::Single | BorderStyle::Rounded => ('┤', '├'),
+            BorderStyle::Double => ('╡', '╞'),
+            BorderStyle::Heavy => ('┫', '┣'),
+            BorderStyle::Ascii => ('|', '|'),
+        }
+    }
+}
+
+/// Draws a border of the given size with its top-left corner at `(x, y)`, clipped to `cells`,
+/// with an optional title in the top edge.
+///
+/// Degenerate sizes never panic: a border one row high or one column wide collapses to a single
+/// line, and an empty one draws nothing.
+pub fn draw_border<C>(cells: &mut C,
+                      x: isize,
+                      y: isize,
+                      size: Size,
+                      style: BorderStyle,
+                      title: Option<(&str, HorizontalAlign)>)
+    where C: CellAccessor + ?Sized
+{
+    let (cols, rows) = size;
+    if cols == 0 || rows == 0 {
+        return;
+    }
+    let style = style.resolve();
+    let [tl, tr, br, bl, h, v] = style.glyphs();
+    let (right, bottom) = (x + cols as isize - 1, y + rows as isize - 1);
+    let mut put = |px: isize, py: isize, ch: char| {
+        if px >= 0 && py >= 0 {
+            if let Some(cell) = cells.get_mut(px as usize, py as usize) {
+                cell.set_ch(ch);
+            }
+        }
+    };
+    if rows == 1 {
+        for px in x..right + 1 {
+            put(px, y, h);
+        }
+        return;
+    }
+    if cols == 1 {
+        for py in y..bottom + 1 {
+            put(x, py, v);
+        }
+        return;
+    }
+    put(x, y, tl);
+    put(right, y, tr);
+    put(right, bottom, br);
+    put(x, bottom, bl);
+    for px in x + 1..right {
+        put(px, y, h);
+        put(px, bottom, h);
+    }
+    for py in y + 1..bottom {
+        put(x, py, v);
+        put(right, py, v);
+    }
+    if let Some((title, align)) = title {
+        draw_title(cells, x, y, cols, style, title, align);
+    }
+}
+
+/// Draws `title` between separators, as in `┤ title ├`, into the top edge of a border of width
+/// `cols` whose top-left corner is at `(x, y)`.
+///
+/// Titles too long for the border are cut short with an ellipsis, and nothing is drawn if not
+/// even one character fits.
+pub fn draw_title<C>(cells: &mut C,
+                     x: isize,
+                     y: isize,
+                     cols: usize,
+                     style: BorderStyle,
+                     title: &str,
+                     align: HorizontalAlign)
+    where C: CellAccessor + ?Sized
+{
+    // The corners and the separators with their padding take up six columns, or more if the
+    // width policy makes the separators wide.
+    let style = style.resolve();
+    let (open, close) = style.separators();
+    let separators = width::char_width(open) + width::char_width(close);
+    let ellipsis = if features::supported(Feature::Unicode) { "…" } else { "..." };
+    let ellipsis_width = width::display_width(ellipsis);
+    let room = match cols.checked_sub(4 + separators) {
+        Some(room) if room >= ellipsis_width => room,
+        _ => return,
+    };
+    let text = if width::display_width(title) > room {
+        format!("{}{}",
+                width::truncate_to_width(title, room - ellipsis_width),
+                ellipsis)
+    } else {
+        title.to_owned()
+    };
+    let label = format!("{} {} {}", open, text, close);
+    let label_width = width::display_width(&label);
+
+    // Leave a line segment between the label and the corners when there is space for it.
+    let inner = cols - 2;
+    let margin = if inner >= label_width + 2 { 1 } else { 0 };
+    let start = match align {
+        HorizontalAlign::Left => 1 + margin,
+        HorizontalAlign::Middle => 1 + (inner - label_width) / 2,
+        HorizontalAlign::Right => cols - 1 - margin - label_width,
+    };
+    let mut offset = start;
+    for grapheme in width::graphemes(&label).filter(|g| width::grapheme_width(g) > 0) {
+        let ch = grapheme.chars().next().unwrap();
+        let px = x + offset as isize;
+        offset += width::char_width(ch);
+        if px >= 0 && y >= 0 {
+            cells.put_char(px as usize, y as usize, ch);
+        }
+    }
+}
+
+/// A decorator drawing a border, with an optional title, around a `Draw` item.
+///
+/// `Bordered` has its own position and is `Alignable`, so a decorated item can be placed with
+/// layouts like any other widget.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, HasSize};
+/// use rustty::ui::{Bordered, BorderStyle, HorizontalAlign, Painter, Widget};
+///
+/// # rustty::set_supported(rustty::Feature::Unicode, true);
+/// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+/// let mut body = Widget::new(10, 1);
+/// body.printline(0, 0, "status: ok");
+///
+/// let mut panel = Bordered::new(body);
+/// panel.set_style(BorderStyle::Rounded);
+/// panel.set_title("log", HorizontalAlign::Left);
+/// assert_eq!(panel.size(), (12, 3));
+///
+/// let mut screen = Widget::new(12, 3);
+/// panel.draw_into(&mut screen);
+/// assert_eq!(screen.get(0, 0).unwrap().ch(), '╭');
+/// assert_eq!(screen.get(2, 0).unwrap().ch(), '┤');
+/// assert_eq!(screen.get(4, 0).unwrap().ch(), 'l');
+/// assert_eq!(screen.get(1, 1).unwrap().ch(), 's');
+/// ```
+pub struct Bordered<W> {
+    inner: W,
+    style: BorderStyle,
+    title: Option<(String, HorizontalAlign)>,
+    origin: Pos,
+}
+
+impl<W> Bordered<W> {
+    /// Wraps `inner` in a single-line border without a title.
+    pub fn new(inner: W) -> Bordered<W> {
+        Bordered {
+            inner,
+            style: BorderStyle::default(),
+            title: None,
+            origin: (0, 0),
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the decorated item.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Sets the glyphs the border is drawn with.
+    pub fn set_style(&mut self, style: BorderStyle) {
+        self.style = style;
+    }
+
+    /// Sets the title shown in the top edge of the border, and where along the edge it goes.
+    pub fn set_title(&mut self, title: &str, align: HorizontalAlign) {
+        self.title = Some((title.to_owned(), align));
+    }
+
+    /// Removes the title.
+    pub fn clear_title(&mut self) {
+        self.title = None;
+    }
+}
+
+impl<W: Draw> Bordered<W> {
+    /// Draws the border and the decorated item into `cells` at the decorator's origin.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        let (x, y) = self.origin;
+        self.draw(cells, x as isize, y as isize);
+    }
+}
+
+impl<W: HasSize> HasSize for Bordered<W> {
+    fn size(&self) -> Size {
+        let (cols, rows) = self.inner.size();
+        (cols + 2, rows + 2)
+    }
+}
+
+impl<W> HasPosition for Bordered<W> {
+    fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.origin = new_origin;
+    }
+}
+
+impl<W: HasSize> Alignable for Bordered<W> {}
+
+impl<W: Measure> Measure for Bordered<W> {
+    fn preferred_size(&self) -> Size {
+        let (cols, rows) = self.inner.preferred_size();
+        (cols + 2, rows + 2)
+    }
+
+    fn minimum_size(&self) -> Size {
+        let (cols, rows) = self.inner.minimum_size();
+        (cols + 2, rows + 2)
+    }
+}
+
+impl<W: Draw> Draw for Bordered<W> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        self.inner.draw(cells, x + 1, y + 1);
+        let title = self.title.as_ref().map(|&(ref t, align)| (&t[..], align));
+        draw_border(cells, x, y, self.size(), self.style, title);
+    }
+}
+
+/// A decorator surrounding a `Draw` item with blank space.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, HasSize};
+/// use rustty::ui::{Bordered, Padded, Painter, Widget};
+///
+/// let mut body = Widget::new(2, 1);
+/// body.printline(0, 0, "ok");
+///
+/// let panel = Bordered::new(Padded::new(body, 2, 1));
+/// assert_eq!(panel.size(), (8, 5));
+///
+/// let mut screen = Widget::new(8, 5);
+/// panel.draw_into(&mut screen);
+/// assert_eq!(screen.get(3, 2).unwrap().ch(), 'o');
+/// ```
+pub struct Padded<W> {
+    inner: W,
+    horizontal: usize,
+    vertical: usize,
+    blank: Cell,
+    origin: Pos,
+}
+
+impl<W> Padded<W> {
+    /// Wraps `inner` with `horizontal` blank columns on each side and `vertical` blank rows above
+    /// and below.
+    pub fn new(inner: W, horizontal: usize, vertical: usize) -> Padded<W> {
+        Padded {
+            inner,
+            horizontal,
+            vertical,
+            blank: Cell::default(),
+            origin: (0, 0),
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the decorated item.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Sets the cell the padding is filled with.
+    pub fn set_blank(&mut self, blank: Cell) {
+        self.blank = blank;
+    }
+}
+
+impl<W: Draw> Padded<W> {
+    /// Draws the padding and the decorated item into `cells` at the decorator's origin.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        let (x, y) = self.origin;
+        self.draw(cells, x as isize, y as isize);
+    }
+}
+
+impl<W: HasSize> HasSize for Padded<W> {
+    fn size(&self) -> Size {
+        let (cols, rows) = self.inner.size();
+        (cols + 2 * self.horizontal, rows + 2 * self.vertical)
+    }
+}
+
+impl<W> HasPosition for Padded<W> {
+    fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.origin = new_origin;
+    }
+}
+
+impl<W: HasSize> Alignable for Padded<W> {}
+
+impl<W: Measure> Measure for Padded<W> {
+    fn preferred_size(&self) -> Size {
+        let (cols, rows) = self.inner.preferred_size();
+        (cols + 2 * self.horizontal, rows + 2 * self.vertical)
+    }
+
+    /// Returns the inner item's minimum size; the padding is dropped when space is short.
+    fn minimum_size(&self) -> Size {
+        self.inner.minimum_size()
+    }
+}
+
+impl<W: Draw> Draw for Padded<W> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        let (cols, rows) = self.size();
+        for py in y..y + rows as isize {
+            for px in x..x + cols as isize {
+                if px < 0 || py < 0 {
+                    continue;
+                }
+                if let Some(cell) = cells.get_mut(px as usize, py as usize) {
+                    *cell = self.blank;
+                }
+            }
+        }
+        self.inner.draw(cells, x + self.horizontal as isize, y + self.vertical as isize);
+    }
+}
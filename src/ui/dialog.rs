@@ -1,8 +1,15 @@
+use std::cmp;
 use std::collections::HashMap;
 
-use ui::layout::{Alignable, HorizontalLayout, HorizontalAlign, VerticalAlign};
+use core::position::{Size, HasSize};
+use core::cellbuffer::{Cell, CellAccessor};
+use core::width;
+use ui::layout::{Alignable, HorizontalLayout, HorizontalAlign, VerticalAlign, SizeSpec,
+                 Measure, Relayout};
 use ui::widget::Widget;
 use ui::button::create_button;
+use ui::keymap::KeyTranslation;
+use ui::painter::Painter;
 
 #[derive(Clone, Copy)]
 pub enum DialogResult {
@@ -15,6 +22,10 @@ pub struct Dialog {
     window: Widget,
     buttons: Vec<Widget>,
     accel2result: HashMap<char, DialogResult>,
+    translation: Option<KeyTranslation>,
+    title: Option<(String, HorizontalAlign)>,
+    size_spec: Option<SizeSpec>,
+    alignment: Option<(HorizontalAlign, VerticalAlign, usize)>,
 }
 
 impl Dialog {
@@ -23,9 +34,170 @@ impl Dialog {
             window: Widget::new(cols, rows),
             buttons: Vec::new(),
             accel2result: HashMap::new(),
+            translation: None,
+            title: None,
+            size_spec: None,
+            alignment: None,
         }
     }
 
+    /// Constructs a new `Dialog` whose size is given by `spec`, resolved against `parent`.
+    ///
+    /// Call `resolve_size()` with the new parent when it is resized to scale the dialog with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::HasSize;
+    /// use rustty::ui::{Dialog, SizeSpec, Widget};
+    ///
+    /// let mut screen = Widget::new(80, 24);
+    /// let mut dlg = Dialog::with_size(SizeSpec::percent(50, 50), &screen);
+    /// assert_eq!(dlg.window().size(), (40, 12));
+    ///
+    /// screen = Widget::new(100, 30);
+    /// assert!(dlg.resolve_size(&screen));
+    /// assert_eq!(dlg.window().size(), (50, 15));
+    /// ```
+    pub fn with_size(spec: SizeSpec, parent: &dyn HasSize) -> Dialog {
+        let (cols, rows) = spec.resolve(parent);
+        let mut dialog = Dialog::new(cols, rows);
+        dialog.size_spec = Some(spec);
+        dialog
+    }
+
+    /// Returns the spec the dialog's size is resolved from, if it was given one.
+    pub fn size_spec(&self) -> Option<SizeSpec> {
+        self.size_spec
+    }
+
+    /// Resizes the dialog's window to its size spec resolved against `parent`, clearing it.
+    ///
+    /// Returns `true` if the size changed, in which case the dialog's contents need to be drawn
+    /// again. Dialogs constructed with a fixed size are never resized.
+    pub fn resolve_size(&mut self, parent: &dyn HasSize) -> bool {
+        let (cols, rows) = match self.size_spec {
+            Some(spec) => spec.resolve(parent),
+            None => return false,
+        };
+        if (cols, rows) == self.window.size() {
+            return false;
+        }
+        self.window.resize(cols, rows);
+        self.window.clear(Cell::default());
+        true
+    }
+
+    /// Sets the title shown in the dialog's top border and draws it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::{Dialog, HorizontalAlign};
+    ///
+    /// # rustty::set_supported(rustty::Feature::Unicode, true);
+    /// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+    /// let mut dlg = Dialog::new(20, 5);
+    /// dlg.draw_box();
+    /// dlg.set_title("Save changes?", HorizontalAlign::Middle);
+    /// assert_eq!(dlg.title(), Some("Save changes?"));
+    /// assert_eq!(dlg.window().get(1, 0).unwrap().ch(), '┤');
+    /// ```
+    pub fn set_title(&mut self, title: &str, align: HorizontalAlign) {
+        self.title = Some((title.to_owned(), align));
+        self.window.draw_title(title, align);
+    }
+
+    /// Returns the dialog's title.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_ref().map(|t| &t.0[..])
+    }
+
+    /// Draws a border around the dialog's window, with the title if it has one.
+    pub fn draw_box(&mut self) {
+        self.window.draw_box();
+        if let Some((ref title, align)) = self.title {
+            self.window.draw_title(title, align);
+        }
+    }
+
+    /// Aligns the dialog's window within `parent` and remembers the alignment, so that
+    /// `relayout()` can align it again after the parent is resized.
+    ///
+    /// Returns `false` if the dialog doesn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{HasPosition, HasSize};
+    /// use rustty::ui::{Dialog, HorizontalAlign, Relayout, SizeSpec, VerticalAlign, Widget};
+    ///
+    /// let mut screen = Widget::new(80, 24);
+    /// let mut dlg = Dialog::with_size(SizeSpec::percent(50, 50), &screen);
+    /// dlg.set_alignment(&screen, HorizontalAlign::Right, VerticalAlign::Bottom, 0);
+    /// assert_eq!(dlg.window().origin(), (40, 12));
+    ///
+    /// screen = Widget::new(100, 30);
+    /// assert!(dlg.relayout(&screen));
+    /// assert_eq!(dlg.window().size(), (50, 15));
+    /// assert_eq!(dlg.window().origin(), (50, 15));
+    /// ```
+    pub fn set_alignment(&mut self,
+                         parent: &dyn HasSize,
+                         halign: HorizontalAlign,
+                         valign: VerticalAlign,
+                         margin: usize)
+                         -> bool {
+        self.alignment = Some((halign, valign, margin));
+        self.window.align(parent, halign, valign, margin)
+    }
+
+    /// Resizes the dialog to fit its contents, its buttons and its title, keeping `padding`
+    /// cells between the contents and the window's edges.
+    ///
+    /// The contents are whatever has been drawn into the dialog's window, so call this after
+    /// drawing them and before drawing the border. Room is left for a row of buttons below the
+    /// padding, as placed by `draw_buttons()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::HasSize;
+    /// use rustty::ui::{Dialog, DialogResult, Painter};
+    ///
+    /// let mut dlg = Dialog::new(80, 24);
+    /// dlg.window_mut().printline(2, 2, "Overwrite the file?");
+    /// dlg.add_button("Yes", 'y', DialogResult::Ok);
+    /// dlg.add_button("No", 'n', DialogResult::Cancel);
+    /// dlg.fit_to_content(2);
+    /// assert_eq!(dlg.window().size(), (23, 7));
+    /// ```
+    pub fn fit_to_content(&mut self, padding: usize) {
+        let (cols, rows) = self.measure(padding);
+        self.window.resize(cols, rows);
+    }
+
+    // Returns the size that fits the contents, buttons and title with `padding` cells around the
+    // contents.
+    fn measure(&self, padding: usize) -> Size {
+        let (content_cols, content_rows) = self.window.preferred_size();
+        let mut cols = content_cols + padding;
+        let mut rows = content_rows + padding;
+        if !self.buttons.is_empty() {
+            let buttons = self.buttons.iter().map(|b| b.size().0).sum::<usize>() +
+                          2 * (self.buttons.len() - 1);
+            cols = cmp::max(cols, buttons + 2 * padding);
+            // The padding and the buttons, which sit one row above the bottom edge.
+            rows = content_rows + padding + 2;
+        }
+        if let Some((ref title, _)) = self.title {
+            // The corners and the separators with their padding take up six columns.
+            cols = cmp::max(cols, width::display_width(title) + 6);
+        }
+        (cols, rows)
+    }
+
     pub fn window(&self) -> &Widget {
         &self.window
     }
@@ -41,11 +213,37 @@ impl Dialog {
         self.buttons.last_mut().unwrap()
     }
 
+    /// Returns the result of the button whose accelerator is `key`, ignoring case.
+    ///
+    /// Keys that match no accelerator are translated with the dialog's `KeyTranslation`, if it
+    /// has one, and matched again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{Dialog, DialogResult, KeyTranslation};
+    ///
+    /// let mut dlg = Dialog::new(20, 5);
+    /// dlg.add_button("Yes", 'y', DialogResult::Ok);
+    /// assert!(dlg.result_for_key('Y').is_some());
+    /// assert!(dlg.result_for_key('н').is_none());
+    ///
+    /// dlg.set_key_translation(Some(KeyTranslation::russian()));
+    /// assert!(dlg.result_for_key('Н').is_some());
+    /// ```
     pub fn result_for_key(&self, key: char) -> Option<DialogResult> {
-        match self.accel2result.get(&key.to_lowercase().next().unwrap_or(key)) {
-            Some(r) => Some(*r),
-            None => None,
-        }
+        let key = key.to_lowercase().next().unwrap_or(key);
+        self.accel2result.get(&key).or_else(|| {
+            let translated = self.translation.as_ref()?.translate(key)?;
+            self.accel2result.get(&translated)
+        }).cloned()
+    }
+
+    /// Sets the translation applied to keys that match no accelerator, so that accelerators
+    /// work on other keyboard layouts, or `None` to match keys as they are, which is the
+    /// default.
+    pub fn set_key_translation(&mut self, translation: Option<KeyTranslation>) {
+        self.translation = translation;
     }
 
     pub fn draw_buttons(&mut self) {
@@ -66,3 +264,22 @@ impl Dialog {
         }
     }
 }
+
+impl Relayout for Dialog {
+    /// Resolves the dialog's size spec, if it has one, and re-applies its alignment, if it was
+    /// set with `set_alignment()`.
+    fn relayout(&mut self, parent: &dyn HasSize) -> bool {
+        let resized = self.resolve_size(parent);
+        if let Some((halign, valign, margin)) = self.alignment {
+            self.window.align(parent, halign, valign, margin);
+        }
+        resized
+    }
+}
+
+impl Measure for Dialog {
+    /// Returns the size that fits the dialog's contents, buttons and title inside a border.
+    fn preferred_size(&self) -> Size {
+        self.measure(1)
+    }
+}
@@ -0,0 +1,229 @@
+use std::cmp;
+
+use core::position::{HasSize, Rect, Size};
+use core::cellbuffer::{Cell, CellAccessor, CellBuffer};
+
+/// Something that can be drawn into a grid of cells at an arbitrary position.
+///
+/// Positions are signed so that objects can be placed partly off-screen, for example while
+/// scrolling; whatever falls outside the target cells is clipped.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::CellAccessor;
+/// use rustty::ui::{Draw, Painter, Widget};
+///
+/// let mut sprite = Widget::new(2, 1);
+/// sprite.printline(0, 0, "<>");
+///
+/// let mut screen = Widget::new(4, 1);
+/// sprite.draw(&mut screen, -1, 0);
+/// assert_eq!(screen.get(0, 0).unwrap().ch(), '>');
+/// ```
+pub trait Draw: HasSize {
+    /// Draws `self` into `cells` with its top-left corner at `(x, y)`.
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize);
+
+    /// Returns `self` moved `dx` columns to the right and `dy` rows down.
+    ///
+    /// The size of the result covers the gap left by moving right or down.
+    fn offset(self, dx: isize, dy: isize) -> Offset<Self>
+        where Self: Sized
+    {
+        Offset {
+            inner: self,
+            dx,
+            dy,
+        }
+    }
+
+    /// Returns `self` drawn with the colors and attributes of `style`.
+    ///
+    /// Every cell in the rectangle covered by `self` takes on the style, whether `self` wrote
+    /// to it or not.
+    fn styled(self, style: Cell) -> Styled<Self>
+        where Self: Sized
+    {
+        Styled {
+            inner: self,
+            style,
+        }
+    }
+
+    /// Returns `self` drawn only inside `rect`, which is relative to its top-left corner.
+    fn clipped(self, rect: Rect) -> Clipped<Self>
+        where Self: Sized
+    {
+        Clipped {
+            inner: self,
+            rect,
+        }
+    }
+
+    /// Returns `self` with `other` drawn on top of it at the same position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Attr, Cell, CellAccessor, Color, Rect};
+    /// use rustty::ui::{Draw, Painter, Widget};
+    ///
+    /// let mut label = Widget::new(5, 1);
+    /// label.printline(0, 0, "hello");
+    /// let mut marker = Widget::new(1, 1);
+    /// marker.printline(0, 0, ">");
+    ///
+    /// let bold = Cell::with_style(Color::Default, Color::Default, Attr::Bold);
+    /// let scene = label.styled(bold)
+    ///                  .clipped(Rect::new((0, 0), (4, 1)))
+    ///                  .offset(2, 0)
+    ///                  .then(marker);
+    ///
+    /// let mut screen = Widget::new(8, 1);
+    /// scene.draw(&mut screen, 0, 0);
+    /// assert_eq!(screen.get(0, 0).unwrap().ch(), '>');
+    /// assert_eq!(screen.get(2, 0).unwrap().ch(), 'h');
+    /// assert_eq!(screen.get(2, 0).unwrap().attrs(), Attr::Bold);
+    /// assert_eq!(screen.get(6, 0).unwrap().ch(), ' ');
+    /// ```
+    fn then<D: Draw>(self, other: D) -> Then<Self, D>
+        where Self: Sized
+    {
+        Then {
+            first: self,
+            second: other,
+        }
+    }
+}
+
+impl<T: Draw + ?Sized> Draw for Box<T> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        (**self).draw(cells, x, y)
+    }
+}
+
+impl<T: Draw + ?Sized> Draw for &T {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        (**self).draw(cells, x, y)
+    }
+}
+
+/// A `Draw` item moved by an offset, see `Draw::offset()`.
+pub struct Offset<D> {
+    inner: D,
+    dx: isize,
+    dy: isize,
+}
+
+impl<D: Draw> HasSize for Offset<D> {
+    fn size(&self) -> Size {
+        let (cols, rows) = self.inner.size();
+        (cols + cmp::max(self.dx, 0) as usize, rows + cmp::max(self.dy, 0) as usize)
+    }
+}
+
+impl<D: Draw> Draw for Offset<D> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        self.inner.draw(cells, x + self.dx, y + self.dy);
+    }
+}
+
+/// A `Draw` item drawn in a style, see `Draw::styled()`.
+pub struct Styled<D> {
+    inner: D,
+    style: Cell,
+}
+
+impl<D: Draw> HasSize for Styled<D> {
+    fn size(&self) -> Size {
+        self.inner.size()
+    }
+}
+
+impl<D: Draw> Draw for Styled<D> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        self.inner.draw(cells, x, y);
+        let (cols, rows) = self.size();
+        for iy in 0..rows as isize {
+            for ix in 0..cols as isize {
+                let (px, py) = (x + ix, y + iy);
+                if px < 0 || py < 0 {
+                    continue;
+                }
+                if let Some(cell) = cells.get_mut(px as usize, py as usize) {
+                    cell.set_fg(self.style.fg());
+                    cell.set_bg(self.style.bg());
+                    cell.set_attrs(self.style.attrs());
+                }
+            }
+        }
+    }
+}
+
+/// A `Draw` item limited to a rectangle, see `Draw::clipped()`.
+pub struct Clipped<D> {
+    inner: D,
+    rect: Rect,
+}
+
+impl<D: Draw> HasSize for Clipped<D> {
+    fn size(&self) -> Size {
+        self.inner.size()
+    }
+}
+
+impl<D: Draw> Draw for Clipped<D> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        // Draw into a copy of the covered cells and copy back what falls inside the rectangle.
+        let (cols, rows) = self.size();
+        let mut scratch = CellBuffer::new(cols, rows, Cell::default());
+        let target = |ix: usize, iy: usize| {
+            let (px, py) = (x + ix as isize, y + iy as isize);
+            if px < 0 || py < 0 {
+                None
+            } else {
+                Some((px as usize, py as usize))
+            }
+        };
+        for iy in 0..rows {
+            for ix in 0..cols {
+                if let Some(cell) = target(ix, iy).and_then(|(px, py)| cells.get(px, py)) {
+                    *scratch.get_mut(ix, iy).unwrap() = *cell;
+                }
+            }
+        }
+        self.inner.draw(&mut scratch, 0, 0);
+        let visible = match self.rect.intersection(&Rect::new((0, 0), (cols, rows))) {
+            Some(visible) => visible,
+            None => return,
+        };
+        for iy in visible.origin.1..visible.bottom() {
+            for ix in visible.origin.0..visible.right() {
+                if let Some(cell) = target(ix, iy).and_then(|(px, py)| cells.get_mut(px, py)) {
+                    *cell = *scratch.get(ix, iy).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// Two `Draw` items drawn one on top of the other, see `Draw::then()`.
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Draw, B: Draw> HasSize for Then<A, B> {
+    fn size(&self) -> Size {
+        let (a, b) = (self.first.size(), self.second.size());
+        (cmp::max(a.0, b.0), cmp::max(a.1, b.1))
+    }
+}
+
+impl<A: Draw, B: Draw> Draw for Then<A, B> {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        self.first.draw(cells, x, y);
+        self.second.draw(cells, x, y);
+    }
+}
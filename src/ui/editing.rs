@@ -0,0 +1,228 @@
+use ui::keymap::Keymap;
+
+const ESC: char = '\x1b';
+
+/// An editing operation on a line of text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EditAction {
+    MoveLeft,
+    MoveRight,
+    MoveWordLeft,
+    MoveWordRight,
+    MoveToStart,
+    MoveToEnd,
+    DeleteBackward,
+    DeleteForward,
+    DeleteWordBackward,
+    /// Deletes from the cursor to the end of the line, copying it to the `Clipboard`.
+    KillToEnd,
+    /// Deletes from the start of the line to the cursor, copying it to the `Clipboard`.
+    KillToStart,
+    /// Inserts the most recently killed text, see `Clipboard::paste()`.
+    Yank,
+    /// Reverts the last change, see `UndoStack`.
+    Undo,
+    /// Reapplies the last undone change.
+    Redo,
+    HistoryPrev,
+    HistoryNext,
+    Submit,
+    Cancel,
+    /// Switches from vi insert mode to normal mode.
+    EnterNormal,
+    /// Switches to vi insert mode before the cursor.
+    EnterInsert,
+    /// Switches to vi insert mode after the cursor.
+    Append,
+    /// Switches to vi insert mode at the end of the line.
+    AppendAtEnd,
+    /// Switches to vi insert mode at the start of the line.
+    InsertAtStart,
+}
+
+/// A set of default key bindings for text editing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum EditPreset {
+    /// Emacs style `Ctrl` chords, as found in readline and most shells. `C-y` yanks, so redo has
+    /// no default binding.
+    #[default]
+    Emacs,
+    /// Modal vi style editing, starting in insert mode.
+    Vi,
+}
+
+/// The current mode of a modal editing preset.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EditMode {
+    /// Printable keys insert text.
+    Insert,
+    /// Keys are commands (vi normal mode).
+    Normal,
+}
+
+/// The meaning of a key press to a text editing widget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Insert(char),
+    Action(EditAction),
+}
+
+/// Translates key presses into edits according to an `EditPreset`.
+///
+/// `EditBindings` holds the keymaps of a preset and, for vi, the current mode. An editing widget
+/// passes each key to `handle_key()` and applies the resulting `Edit` to its buffer. The keymaps
+/// can be modified to customize a preset.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::{EditBindings, EditPreset, EditMode, Edit, EditAction};
+///
+/// let mut emacs = EditBindings::new(EditPreset::Emacs);
+/// assert_eq!(emacs.handle_key('a'), Some(Edit::Insert('a')));
+/// assert_eq!(emacs.handle_key('\x01'), Some(Edit::Action(EditAction::MoveToStart)));
+///
+/// let mut vi = EditBindings::new(EditPreset::Vi);
+/// assert_eq!(vi.handle_key('h'), Some(Edit::Insert('h')));
+/// assert_eq!(vi.handle_key('\x1b'), Some(Edit::Action(EditAction::EnterNormal)));
+/// assert_eq!(vi.mode(), EditMode::Normal);
+/// assert_eq!(vi.handle_key('h'), Some(Edit::Action(EditAction::MoveLeft)));
+/// assert_eq!(vi.handle_key('A'), Some(Edit::Action(EditAction::AppendAtEnd)));
+/// assert_eq!(vi.mode(), EditMode::Insert);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EditBindings {
+    preset: EditPreset,
+    mode: EditMode,
+    insert: Keymap<EditAction>,
+    normal: Keymap<EditAction>,
+}
+
+impl EditBindings {
+    /// Constructs the bindings of the given `EditPreset`.
+    pub fn new(preset: EditPreset) -> EditBindings {
+        let mut insert = Keymap::new();
+        let mut normal = Keymap::new();
+
+        // Bindings shared by every preset.
+        insert.bind('\r', EditAction::Submit);
+        insert.bind('\n', EditAction::Submit);
+        insert.bind('\x7f', EditAction::DeleteBackward);
+        insert.bind('\x08', EditAction::DeleteBackward);
+
+        match preset {
+            EditPreset::Emacs => {
+                insert.bind('\x01', EditAction::MoveToStart); // C-a
+                insert.bind('\x05', EditAction::MoveToEnd); // C-e
+                insert.bind('\x02', EditAction::MoveLeft); // C-b
+                insert.bind('\x06', EditAction::MoveRight); // C-f
+                insert.bind('\x04', EditAction::DeleteForward); // C-d
+                insert.bind('\x17', EditAction::DeleteWordBackward); // C-w
+                insert.bind('\x0b', EditAction::KillToEnd); // C-k
+                insert.bind('\x15', EditAction::KillToStart); // C-u
+                insert.bind('\x19', EditAction::Yank); // C-y
+                insert.bind('\x1a', EditAction::Undo); // C-z
+                insert.bind('\x1f', EditAction::Undo); // C-_
+                insert.bind('\x10', EditAction::HistoryPrev); // C-p
+                insert.bind('\x0e', EditAction::HistoryNext); // C-n
+                insert.bind('\x07', EditAction::Cancel); // C-g
+                insert.bind(ESC, EditAction::Cancel);
+            }
+            EditPreset::Vi => {
+                insert.bind(ESC, EditAction::EnterNormal);
+                insert.bind('\x17', EditAction::DeleteWordBackward); // C-w
+                insert.bind('\x15', EditAction::KillToStart); // C-u
+                insert.bind('\x1a', EditAction::Undo); // C-z
+                insert.bind('\x19', EditAction::Redo); // C-y
+
+                normal.bind('\r', EditAction::Submit);
+                normal.bind('\n', EditAction::Submit);
+                normal.bind(ESC, EditAction::Cancel);
+                normal.bind('h', EditAction::MoveLeft);
+                normal.bind('l', EditAction::MoveRight);
+                normal.bind('b', EditAction::MoveWordLeft);
+                normal.bind('w', EditAction::MoveWordRight);
+                normal.bind('0', EditAction::MoveToStart);
+                normal.bind('^', EditAction::MoveToStart);
+                normal.bind('$', EditAction::MoveToEnd);
+                normal.bind('x', EditAction::DeleteForward);
+                normal.bind('X', EditAction::DeleteBackward);
+                normal.bind('D', EditAction::KillToEnd);
+                normal.bind('p', EditAction::Yank);
+                normal.bind('u', EditAction::Undo);
+                normal.bind('\x12', EditAction::Redo); // C-r
+                normal.bind('k', EditAction::HistoryPrev);
+                normal.bind('j', EditAction::HistoryNext);
+                normal.bind('i', EditAction::EnterInsert);
+                normal.bind('a', EditAction::Append);
+                normal.bind('A', EditAction::AppendAtEnd);
+                normal.bind('I', EditAction::InsertAtStart);
+            }
+        }
+
+        EditBindings {
+            preset,
+            mode: EditMode::Insert,
+            insert,
+            normal,
+        }
+    }
+
+    /// Returns the preset the bindings were constructed from.
+    pub fn preset(&self) -> EditPreset {
+        self.preset
+    }
+
+    /// Returns the current mode.
+    pub fn mode(&self) -> EditMode {
+        self.mode
+    }
+
+    /// Sets the current mode.
+    pub fn set_mode(&mut self, mode: EditMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the keymap of the given mode.
+    pub fn keymap(&self, mode: EditMode) -> &Keymap<EditAction> {
+        match mode {
+            EditMode::Insert => &self.insert,
+            EditMode::Normal => &self.normal,
+        }
+    }
+
+    /// Returns the keymap of the given mode for modification.
+    pub fn keymap_mut(&mut self, mode: EditMode) -> &mut Keymap<EditAction> {
+        match mode {
+            EditMode::Insert => &mut self.insert,
+            EditMode::Normal => &mut self.normal,
+        }
+    }
+
+    /// Translates a key press into an `Edit`, switching modes as needed.
+    ///
+    /// Returns `None` for keys that have no meaning in the current mode.
+    pub fn handle_key(&mut self, key: char) -> Option<Edit> {
+        if let Some(&action) = self.keymap(self.mode).get(key) {
+            self.mode = match action {
+                EditAction::EnterNormal => EditMode::Normal,
+                EditAction::EnterInsert |
+                EditAction::Append |
+                EditAction::AppendAtEnd |
+                EditAction::InsertAtStart => EditMode::Insert,
+                _ => self.mode,
+            };
+            return Some(Edit::Action(action));
+        }
+        match self.mode {
+            EditMode::Insert if !key.is_control() => Some(Edit::Insert(key)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for EditBindings {
+    fn default() -> EditBindings {
+        EditBindings::new(EditPreset::default())
+    }
+}
@@ -0,0 +1,102 @@
+//! Feedback for invalid input.
+//!
+//! Widgets that reject a key press call `invalid()` rather than implementing their own error
+//! indication. Depending on the configured `Style`, the widget is flashed in reverse video for a
+//! moment, the terminal bell is rung on the next buffer swap, or both. Feedback is rate-limited
+//! globally, so holding down an invalid key doesn't produce a storm of bells.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use rustty::ui::{feedback, Widget};
+//!
+//! feedback::set_style(feedback::Style::Flash);
+//! feedback::set_min_interval(Duration::from_secs(60));
+//!
+//! let mut input = Widget::new(20, 3);
+//! assert!(feedback::invalid(&mut input));
+//! assert!(input.is_flashing());
+//!
+//! // Throttled until a minute has passed.
+//! assert!(!feedback::invalid(&mut input));
+//! ```
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use core::terminal;
+use ui::widget::Widget;
+
+/// How invalid input is signalled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Style {
+    /// Flash the widget in reverse video.
+    Flash,
+    /// Ring the terminal bell.
+    Bell,
+    /// Flash the widget and ring the bell.
+    Both,
+    /// Give no feedback.
+    Silent,
+}
+
+struct Config {
+    style: Style,
+    min_interval: Duration,
+    flash_duration: Duration,
+    last: Option<Instant>,
+}
+
+static CONFIG: Mutex<Config> = Mutex::new(Config {
+    style: Style::Flash,
+    min_interval: Duration::from_millis(250),
+    flash_duration: Duration::from_millis(100),
+    last: None,
+});
+
+fn with_config<T, F: FnOnce(&mut Config) -> T>(f: F) -> T {
+    // The configuration is always left consistent, so a poisoned lock can be recovered.
+    let mut config = match CONFIG.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    f(&mut config)
+}
+
+/// Sets how invalid input is signalled. The default is `Style::Flash`.
+pub fn set_style(style: Style) {
+    with_config(|c| c.style = style);
+}
+
+/// Sets the minimum time between two signals. The default is 250 milliseconds.
+pub fn set_min_interval(interval: Duration) {
+    with_config(|c| c.min_interval = interval);
+}
+
+/// Sets how long a widget flashes for. The default is 100 milliseconds.
+pub fn set_flash_duration(duration: Duration) {
+    with_config(|c| c.flash_duration = duration);
+}
+
+/// Signals that `widget` received invalid input.
+///
+/// Returns `false` if the signal was suppressed, either by rate limiting or because the style
+/// is `Style::Silent`.
+pub fn invalid(widget: &mut Widget) -> bool {
+    with_config(|c| {
+        let now = Instant::now();
+        let throttled = c.last.is_some_and(|last| now.duration_since(last) < c.min_interval);
+        if throttled || c.style == Style::Silent {
+            return false;
+        }
+        c.last = Some(now);
+        if c.style == Style::Flash || c.style == Style::Both {
+            widget.flash(c.flash_duration);
+        }
+        if c.style == Style::Bell || c.style == Style::Both {
+            terminal::request_bell();
+        }
+        true
+    })
+}
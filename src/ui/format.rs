@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+/// Formats the numbers widgets display, so that applications can localize them.
+///
+/// Every method has a default, and `DefaultFormatter` uses the defaults throughout. A
+/// `Formatter` is installed with the `set_formatter()` method of `Chart`, `Dashboard` and
+/// `MultiProgress`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::{DefaultFormatter, Formatter};
+///
+/// // German number formatting.
+/// struct German;
+///
+/// impl Formatter for German {
+///     fn number(&self, value: f64) -> String {
+///         DefaultFormatter.number(value).replace('.', ",")
+///     }
+/// }
+///
+/// assert_eq!(German.number(2.5), "2,5");
+/// assert_eq!(German.number(3.0), "3");
+/// assert_eq!(German.percent(0.25), "25%");
+/// ```
+pub trait Formatter {
+    /// Formats a measurement, such as a reading or an axis label. Rounds to one decimal place,
+    /// dropping the fraction of whole numbers, by default.
+    fn number(&self, value: f64) -> String {
+        // Adding zero turns a rounded -0.0 into 0.0.
+        let value = (value * 10.0).round() / 10.0 + 0.0;
+        if value.fract() == 0.0 && value.abs() < 1e15 {
+            format!("{}", value as i64)
+        } else {
+            format!("{:.1}", value)
+        }
+    }
+
+    /// Formats a count of items.
+    fn count(&self, value: u64) -> String {
+        value.to_string()
+    }
+
+    /// Formats a fraction, where `1.0` is the whole, as a percentage.
+    fn percent(&self, ratio: f64) -> String {
+        format!("{:.0}%", ratio * 100.0)
+    }
+
+    /// Formats a duration as `m:ss`, or `h:mm:ss` from an hour on.
+    fn duration(&self, d: Duration) -> String {
+        let secs = d.as_secs();
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if h > 0 {
+            format!("{}:{:02}:{:02}", h, m, s)
+        } else {
+            format!("{}:{:02}", m, s)
+        }
+    }
+}
+
+/// The `Formatter` widgets use unless given another.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct DefaultFormatter;
+
+impl Formatter for DefaultFormatter {}
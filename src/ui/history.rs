@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::path::Path;
+
+/// A bounded history of input lines, navigable like a shell history.
+///
+/// `History` keeps at most `capacity` entries, oldest first. Navigation starts past the newest
+/// entry (the line currently being edited); `older()` walks towards older entries and
+/// `newer()` back towards the newest, returning `None` once navigation leaves the history again.
+///
+/// When de-duplication is enabled (the default), pushing an entry that already exists moves it
+/// to the most recent position instead of storing it twice.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::History;
+///
+/// let mut hist = History::new(100);
+/// hist.push("ls");
+/// hist.push("cd src");
+/// hist.push("ls");
+///
+/// assert_eq!(hist.len(), 2);
+/// assert_eq!(hist.older(), Some("ls"));
+/// assert_eq!(hist.older(), Some("cd src"));
+/// assert_eq!(hist.older(), Some("cd src"));
+/// assert_eq!(hist.newer(), Some("ls"));
+/// assert_eq!(hist.newer(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct History {
+    entries: VecDeque<String>,
+    capacity: usize,
+    dedup: bool,
+    cursor: Option<usize>,
+}
+
+impl History {
+    /// Constructs a new, empty `History` holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> History {
+        History {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            dedup: true,
+            cursor: None,
+        }
+    }
+
+    /// Enables or disables de-duplication of entries.
+    pub fn set_dedup(&mut self, dedup: bool) {
+        self.dedup = dedup;
+    }
+
+    /// Returns the maximum number of entries kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entry at `index`, where `0` is the oldest entry.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|s| &s[..])
+    }
+
+    /// Returns an iterator over the entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|s| &s[..])
+    }
+
+    /// Appends `line` as the newest entry and resets navigation.
+    ///
+    /// Empty lines, and lines spanning several lines of text, are ignored. If the history is
+    /// full the oldest entry is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::History;
+    ///
+    /// let mut hist = History::new(2);
+    /// hist.push("one");
+    /// hist.push("two");
+    /// hist.push("three");
+    /// hist.push("");
+    ///
+    /// assert_eq!(hist.iter().collect::<Vec<_>>(), vec!["two", "three"]);
+    /// ```
+    pub fn push(&mut self, line: &str) {
+        self.cursor = None;
+        if line.is_empty() || line.contains('\n') || self.capacity == 0 {
+            return;
+        }
+        if self.dedup {
+            self.entries.retain(|e| e != line);
+        } else if self.entries.back().map(|e| &e[..]) == Some(line) {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(line.to_owned());
+    }
+
+    /// Moves one entry towards the oldest and returns it.
+    ///
+    /// Stays on the oldest entry once it has been reached. Returns `None` only if the history is
+    /// empty.
+    pub fn older(&mut self) -> Option<&str> {
+        let index = match self.cursor {
+            Some(0) => 0,
+            Some(i) => i - 1,
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.cursor = Some(index);
+        self.get(index)
+    }
+
+    /// Moves one entry towards the newest and returns it.
+    ///
+    /// Returns `None` when moving past the newest entry, meaning the caller should restore the
+    /// line that was being edited before navigation started.
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.cursor {
+            Some(i) if i + 1 < self.entries.len() => {
+                self.cursor = Some(i + 1);
+                self.get(i + 1)
+            }
+            _ => {
+                self.cursor = None;
+                None
+            }
+        }
+    }
+
+    /// Resets navigation to past the newest entry.
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cursor = None;
+    }
+
+    /// Pushes every line of the file at `path` into the history, oldest first.
+    ///
+    /// A missing file is not an error, so a history file can be loaded unconditionally on
+    /// startup.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            self.push(&try!(line));
+        }
+        Ok(())
+    }
+
+    /// Writes the entries to the file at `path`, one per line and oldest first, replacing any
+    /// existing contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::History;
+    ///
+    /// let path = std::env::temp_dir().join("rustty_history_doctest");
+    ///
+    /// let mut hist = History::new(10);
+    /// hist.push("first");
+    /// hist.push("second");
+    /// hist.save(&path).unwrap();
+    ///
+    /// let mut loaded = History::new(10);
+    /// loaded.load(&path).unwrap();
+    /// assert_eq!(loaded.iter().collect::<Vec<_>>(), vec!["first", "second"]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut out = BufWriter::new(try!(File::create(path)));
+        for entry in &self.entries {
+            try!(writeln!(out, "{}", entry));
+        }
+        out.flush()
+    }
+}
@@ -0,0 +1,195 @@
+use core::position::Size;
+use core::cellbuffer::CellAccessor;
+use ui::draw::Draw;
+
+/// A point on the screen that HUD items are placed against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    MiddleLeft,
+    Center,
+    MiddleRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Anchor {
+    fn column(self) -> usize {
+        match self {
+            Anchor::TopLeft | Anchor::MiddleLeft | Anchor::BottomLeft => 0,
+            Anchor::TopCenter | Anchor::Center | Anchor::BottomCenter => 1,
+            Anchor::TopRight | Anchor::MiddleRight | Anchor::BottomRight => 2,
+        }
+    }
+
+    fn row(self) -> usize {
+        match self {
+            Anchor::TopLeft | Anchor::TopCenter | Anchor::TopRight => 0,
+            Anchor::MiddleLeft | Anchor::Center | Anchor::MiddleRight => 1,
+            Anchor::BottomLeft | Anchor::BottomCenter | Anchor::BottomRight => 2,
+        }
+    }
+}
+
+/// Identifies an item of a `Hud`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HudId(usize);
+
+struct Item<T> {
+    id: HudId,
+    anchor: Anchor,
+    visible: bool,
+    item: T,
+}
+
+/// A heads-up display of items anchored to the edges and center of the screen.
+///
+/// Item positions are computed from the size of the cells being drawn into on every draw, so the
+/// HUD follows the terminal when it is resized. Items sharing an anchor are stacked rather than
+/// drawn over each other: from the top down for top and middle anchors, and from the bottom up
+/// for bottom anchors, with the first item added nearest the edge.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::CellAccessor;
+/// use rustty::ui::{Anchor, Hud, Painter, Widget};
+///
+/// let mut score = Widget::new(5, 1);
+/// score.printline(0, 0, "S:100");
+/// let mut lives = Widget::new(3, 1);
+/// lives.printline(0, 0, "<3 ");
+///
+/// let mut hud = Hud::new();
+/// hud.add(Anchor::TopRight, score);
+/// hud.add(Anchor::TopRight, lives);
+///
+/// let mut screen = Widget::new(20, 5);
+/// hud.draw_into(&mut screen);
+/// assert_eq!(screen.get(15, 0).unwrap().ch(), 'S');
+/// assert_eq!(screen.get(17, 1).unwrap().ch(), '<');
+///
+/// // After a resize the items follow the corner.
+/// let mut screen = Widget::new(30, 5);
+/// hud.draw_into(&mut screen);
+/// assert_eq!(screen.get(25, 0).unwrap().ch(), 'S');
+/// ```
+pub struct Hud<T: Draw> {
+    items: Vec<Item<T>>,
+    margin: usize,
+    next_id: usize,
+}
+
+impl<T: Draw> Hud<T> {
+    /// Constructs a new, empty `Hud`.
+    pub fn new() -> Hud<T> {
+        Hud {
+            items: Vec::new(),
+            margin: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Sets the number of cells left between the items and the edges of the screen.
+    pub fn set_margin(&mut self, margin: usize) {
+        self.margin = margin;
+    }
+
+    /// Adds an item at the given anchor, after any items already there.
+    pub fn add(&mut self, anchor: Anchor, item: T) -> HudId {
+        self.next_id += 1;
+        let id = HudId(self.next_id);
+        self.items.push(Item {
+            id,
+            anchor,
+            visible: true,
+            item,
+        });
+        id
+    }
+
+    /// Removes an item and returns it.
+    pub fn remove(&mut self, id: HudId) -> Option<T> {
+        let i = self.items.iter().position(|i| i.id == id)?;
+        Some(self.items.remove(i).item)
+    }
+
+    /// Returns a reference to an item.
+    pub fn get(&self, id: HudId) -> Option<&T> {
+        self.items.iter().find(|i| i.id == id).map(|i| &i.item)
+    }
+
+    /// Returns a mutable reference to an item.
+    pub fn get_mut(&mut self, id: HudId) -> Option<&mut T> {
+        self.items.iter_mut().find(|i| i.id == id).map(|i| &mut i.item)
+    }
+
+    /// Shows or hides an item. Hidden items don't take up space in their stack.
+    pub fn set_visible(&mut self, id: HudId, visible: bool) -> bool {
+        self.items.iter_mut().find(|i| i.id == id).map(|i| i.visible = visible).is_some()
+    }
+
+    /// Computes the positions of the visible items on a screen of the given size.
+    ///
+    /// Positions are negative where an item doesn't fit on the screen.
+    pub fn layout(&self, screen: Size) -> Vec<(HudId, (isize, isize))> {
+        let (cols, rows) = (screen.0 as isize, screen.1 as isize);
+        let margin = self.margin as isize;
+        let mut positions = Vec::new();
+        for anchor in ANCHORS.iter().cloned() {
+            let mut stack: Vec<&Item<T>> = self.items
+                                               .iter()
+                                               .filter(|i| i.visible && i.anchor == anchor)
+                                               .collect();
+            let total = stack.iter().map(|i| i.item.size().1 as isize).sum::<isize>();
+            let mut y = match anchor.row() {
+                0 => margin,
+                1 => (rows - total) / 2,
+                _ => rows - margin - total,
+            };
+            if anchor.row() == 2 {
+                stack.reverse();
+            }
+            for item in stack {
+                let (w, h) = item.item.size();
+                let x = match anchor.column() {
+                    0 => margin,
+                    1 => (cols - w as isize) / 2,
+                    _ => cols - margin - w as isize,
+                };
+                positions.push((item.id, (x, y)));
+                y += h as isize;
+            }
+        }
+        positions
+    }
+
+    /// Draws the visible items into `cells`, anchored to its edges.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        let size = cells.size();
+        for (id, (x, y)) in self.layout(size) {
+            if let Some(item) = self.get(id) {
+                item.draw(cells, x, y);
+            }
+        }
+    }
+}
+
+impl<T: Draw> Default for Hud<T> {
+    fn default() -> Hud<T> {
+        Hud::new()
+    }
+}
+
+const ANCHORS: [Anchor; 9] = [Anchor::TopLeft,
+                              Anchor::TopCenter,
+                              Anchor::TopRight,
+                              Anchor::MiddleLeft,
+                              Anchor::Center,
+                              Anchor::MiddleRight,
+                              Anchor::BottomLeft,
+                              Anchor::BottomCenter,
+                              Anchor::BottomRight];
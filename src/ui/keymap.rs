@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+/// The context a `Keymap` belongs to, which decides its precedence within a `KeymapStack`.
+///
+/// Contexts are ordered from lowest to highest precedence: widget bindings shadow modal
+/// (dialog) bindings, which in turn shadow global bindings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeymapContext {
+    Global,
+    Modal,
+    Widget,
+}
+
+/// A set of key bindings, mapping keys to actions of type `A`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::Keymap;
+///
+/// let mut keymap = Keymap::new();
+/// keymap.bind('q', "quit");
+/// assert_eq!(keymap.get('q'), Some(&"quit"));
+///
+/// keymap.unbind('q');
+/// assert_eq!(keymap.get('q'), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    bindings: HashMap<char, A>,
+}
+
+impl<A> Keymap<A> {
+    /// Constructs a new, empty `Keymap`.
+    pub fn new() -> Keymap<A> {
+        Keymap { bindings: HashMap::new() }
+    }
+
+    /// Binds `key` to `action`, returning the action previously bound to `key`, if any.
+    pub fn bind(&mut self, key: char, action: A) -> Option<A> {
+        self.bindings.insert(key, action)
+    }
+
+    /// Removes the binding for `key` and returns its action, if any.
+    pub fn unbind(&mut self, key: char) -> Option<A> {
+        self.bindings.remove(&key)
+    }
+
+    /// Returns the action bound to `key`, if any.
+    pub fn get(&self, key: char) -> Option<&A> {
+        self.bindings.get(&key)
+    }
+
+    /// Returns the number of bindings.
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Returns `true` if there are no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Returns an iterator over the bindings, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (char, &A)> {
+        self.bindings.iter().map(|(k, a)| (*k, a))
+    }
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Keymap<A> {
+        Keymap::new()
+    }
+}
+
+/// Translates the characters typed on one keyboard layout into the characters the same keys
+/// type on another, so that accelerators keep working when the user switches layouts.
+///
+/// Accelerators are usually Latin letters, which a Cyrillic or Greek layout does not type: with a
+/// Russian layout, the key marked `Y` types `н`. A `KeyTranslation` maps `н` back to `y`, for
+/// example in `Dialog::set_key_translation()`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::KeyTranslation;
+///
+/// let mut table = KeyTranslation::russian();
+/// assert_eq!(table.translate('н'), Some('y'));
+/// assert_eq!(table.translate('y'), None);
+///
+/// table.insert('ё', '`');
+/// assert_eq!(table.translate('ё'), Some('`'));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeyTranslation {
+    table: HashMap<char, char>,
+}
+
+impl KeyTranslation {
+    /// Constructs an empty `KeyTranslation`.
+    pub fn new() -> KeyTranslation {
+        KeyTranslation::default()
+    }
+
+    /// Constructs a `KeyTranslation` from the characters of `from` to the characters at the same
+    /// positions of `to`. Extra characters in either are ignored.
+    pub fn from_pairs(from: &str, to: &str) -> KeyTranslation {
+        KeyTranslation { table: from.chars().zip(to.chars()).collect() }
+    }
+
+    /// Constructs a `KeyTranslation` from the Russian ЙЦУКЕН layout to US QWERTY.
+    pub fn russian() -> KeyTranslation {
+        KeyTranslation::from_pairs("йцукенгшщзхъфывапролджэячсмитьбю",
+                                   "qwertyuiop[]asdfghjkl;'zxcvbnm,.")
+    }
+
+    /// Translates `from` to `to`, returning the previous translation of `from`, if any.
+    pub fn insert(&mut self, from: char, to: char) -> Option<char> {
+        self.table.insert(from, to)
+    }
+
+    /// Removes the translation of `from` and returns it, if any.
+    pub fn remove(&mut self, from: char) -> Option<char> {
+        self.table.remove(&from)
+    }
+
+    /// Returns the translation of `ch`, if it has one.
+    pub fn translate(&self, ch: char) -> Option<char> {
+        self.table.get(&ch).cloned()
+    }
+}
+
+/// A binding in effect in a `KeymapStack`, as returned by `KeymapStack::effective()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding<'a, A: 'a> {
+    pub key: char,
+    pub action: &'a A,
+    pub context: KeymapContext,
+}
+
+/// A stack of `Keymap`s from different contexts, resolved by precedence.
+///
+/// A key is looked up in the keymaps of the highest precedence context first; among keymaps
+/// of the same context, the most recently pushed one wins. This lets a focused widget shadow the
+/// bindings of the dialog it lives in, and the dialog shadow the application's global bindings,
+/// while keys a layer doesn't bind fall through to the layers below it.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::{Keymap, KeymapStack, KeymapContext};
+///
+/// let mut global = Keymap::new();
+/// global.bind('q', "quit");
+/// global.bind('?', "help");
+///
+/// let mut dialog = Keymap::new();
+/// dialog.bind('q', "close dialog");
+///
+/// let mut stack = KeymapStack::new();
+/// stack.push(KeymapContext::Global, global);
+/// stack.push(KeymapContext::Modal, dialog);
+///
+/// assert_eq!(stack.lookup('q'), Some(&"close dialog"));
+/// assert_eq!(stack.lookup('?'), Some(&"help"));
+///
+/// stack.pop();
+/// assert_eq!(stack.lookup('q'), Some(&"quit"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeymapStack<A> {
+    // Kept sorted by context; keymaps of equal context are in push order.
+    layers: Vec<(KeymapContext, Keymap<A>)>,
+}
+
+impl<A> KeymapStack<A> {
+    /// Constructs a new, empty `KeymapStack`.
+    pub fn new() -> KeymapStack<A> {
+        KeymapStack { layers: Vec::new() }
+    }
+
+    /// Pushes `keymap` as the newest keymap of the given context.
+    pub fn push(&mut self, context: KeymapContext, keymap: Keymap<A>) {
+        let index = self.layers
+            .iter()
+            .position(|&(c, _)| c > context)
+            .unwrap_or(self.layers.len());
+        self.layers.insert(index, (context, keymap));
+    }
+
+    /// Removes the most recently pushed keymap of the highest precedence context and returns it.
+    pub fn pop(&mut self) -> Option<(KeymapContext, Keymap<A>)> {
+        self.layers.pop()
+    }
+
+    /// Removes the most recently pushed keymap of the given context and returns it.
+    pub fn pop_context(&mut self, context: KeymapContext) -> Option<Keymap<A>> {
+        let index = self.layers.iter().rposition(|&(c, _)| c == context)?;
+        Some(self.layers.remove(index).1)
+    }
+
+    /// Returns the newest keymap of the given context for modification.
+    pub fn top_mut(&mut self, context: KeymapContext) -> Option<&mut Keymap<A>> {
+        self.layers
+            .iter_mut()
+            .rev()
+            .find(|layer| layer.0 == context)
+            .map(|layer| &mut layer.1)
+    }
+
+    /// Returns the number of keymaps on the stack.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if there are no keymaps on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Looks up the action `key` resolves to.
+    pub fn lookup(&self, key: char) -> Option<&A> {
+        self.resolve(key).map(|(_, a)| a)
+    }
+
+    /// Looks up the action `key` resolves to, along with the context it was found in.
+    pub fn resolve(&self, key: char) -> Option<(KeymapContext, &A)> {
+        self.layers
+            .iter()
+            .rev()
+            .filter_map(|&(c, ref k)| k.get(key).map(|a| (c, a)))
+            .next()
+    }
+
+    /// Returns every binding currently in effect, sorted by key.
+    ///
+    /// Shadowed bindings are left out, so the result is exactly what a help overlay should
+    /// display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{Keymap, KeymapStack, KeymapContext};
+    ///
+    /// let mut global = Keymap::new();
+    /// global.bind('q', "quit");
+    /// global.bind('s', "save");
+    ///
+    /// let mut widget = Keymap::new();
+    /// widget.bind('s', "sort");
+    ///
+    /// let mut stack = KeymapStack::new();
+    /// stack.push(KeymapContext::Widget, widget);
+    /// stack.push(KeymapContext::Global, global);
+    ///
+    /// let help: Vec<_> = stack.effective()
+    ///     .iter()
+    ///     .map(|b| (b.key, *b.action, b.context))
+    ///     .collect();
+    /// assert_eq!(help, vec![('q', "quit", KeymapContext::Global),
+    ///                       ('s', "sort", KeymapContext::Widget)]);
+    /// ```
+    pub fn effective(&self) -> Vec<Binding<'_, A>> {
+        let mut seen = HashMap::new();
+        for &(context, ref keymap) in self.layers.iter().rev() {
+            for (key, action) in keymap.iter() {
+                seen.entry(key).or_insert(Binding {
+                    key,
+                    action,
+                    context,
+                });
+            }
+        }
+        let mut bindings: Vec<_> = seen.into_values().collect();
+        bindings.sort_by_key(|b| b.key);
+        bindings
+    }
+}
+
+impl<A> Default for KeymapStack<A> {
+    fn default() -> KeymapStack<A> {
+        KeymapStack::new()
+    }
+}
@@ -1,50 +1,218 @@
+use std::cmp;
+
 use core::position::{Pos, Size, HasSize, HasPosition};
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum VerticalAlign {
     Top,
     Middle,
     Bottom,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum HorizontalAlign {
     Left,
     Middle,
     Right,
 }
 
+/// Where a widget goes relative to a sibling, see `Alignable::place_by()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Placement {
+    /// Left of the sibling, with the top edges lined up.
+    LeftOf,
+    /// Right of the sibling, with the top edges lined up.
+    RightOf,
+    /// Above the sibling, with the left edges lined up.
+    Above,
+    /// Below the sibling, with the left edges lined up.
+    Below,
+}
+
+/// One dimension of a `SizeSpec`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Extent {
+    /// A fixed number of cells.
+    Cells(usize),
+    /// A percentage of the parent's extent, rounded down.
+    Percent(usize),
+}
+
+impl Extent {
+    /// Returns the number of cells this extent covers in a parent `parent` cells long.
+    pub fn resolve(self, parent: usize) -> usize {
+        match self {
+            Extent::Cells(n) => n,
+            Extent::Percent(p) => parent * p / 100,
+        }
+    }
+}
+
+/// A size given in cells or relative to a parent, resolved when the parent's size is known.
+///
+/// Specifying sizes relative to the parent lets a UI scale with the terminal instead of relying
+/// on hard-coded dimensions; resolve the spec again whenever the terminal is resized.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::{Extent, SizeSpec, Widget};
+///
+/// let screen = Widget::new(80, 24);
+/// assert_eq!(SizeSpec::percent(80, 50).resolve(&screen), (64, 12));
+///
+/// let spec = SizeSpec { cols: Extent::Percent(50), rows: Extent::Cells(3) };
+/// assert_eq!(spec.resolve(&screen), (40, 3));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SizeSpec {
+    pub cols: Extent,
+    pub rows: Extent,
+}
+
+impl SizeSpec {
+    /// A fixed size of `cols` by `rows` cells.
+    pub fn cells(cols: usize, rows: usize) -> SizeSpec {
+        SizeSpec {
+            cols: Extent::Cells(cols),
+            rows: Extent::Cells(rows),
+        }
+    }
+
+    /// A size of `cols` percent of the parent's width by `rows` percent of its height.
+    pub fn percent(cols: usize, rows: usize) -> SizeSpec {
+        SizeSpec {
+            cols: Extent::Percent(cols),
+            rows: Extent::Percent(rows),
+        }
+    }
+
+    /// Returns the size this spec describes within `parent`.
+    pub fn resolve(&self, parent: &dyn HasSize) -> Size {
+        let (cols, rows) = parent.size();
+        (self.cols.resolve(cols), self.rows.resolve(rows))
+    }
+}
+
+/// Something that can be positioned relative to a parent.
+///
+/// Alignment never fails: a widget larger than its parent is placed at the parent's origin edge
+/// and whatever sticks out is clipped when it is drawn. Each method returns whether the widget
+/// fits, so callers can switch to a more compact layout when it doesn't.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::HasPosition;
+/// use rustty::ui::{Alignable, HorizontalAlign, VerticalAlign, Widget};
+///
+/// let screen = Widget::new(20, 5);
+/// let mut dialog = Widget::new(30, 3);
+///
+/// let fits = dialog.align(&screen, HorizontalAlign::Middle, VerticalAlign::Middle, 0);
+/// assert!(!fits);
+/// assert_eq!(dialog.origin(), (0, 1));
+/// ```
 pub trait Alignable: HasSize + HasPosition {
-    fn halign(&mut self, parent: &HasSize, halign: HorizontalAlign, margin: usize) {
+    /// Aligns horizontally within `parent`, returning `false` if the widget doesn't fit.
+    fn halign(&mut self, parent: &HasSize, halign: HorizontalAlign, margin: usize) -> bool {
         let (cols, _) = self.size();
         let (_, y) = self.origin();
         let (parent_cols, _) = parent.size();
-        let newx = match halign {
-            HorizontalAlign::Left => margin,
-            HorizontalAlign::Right => parent_cols - cols - margin,
-            HorizontalAlign::Middle => (parent_cols - cols) / 2,
+        let (newx, fits) = match halign {
+            HorizontalAlign::Left => (margin, margin + cols <= parent_cols),
+            HorizontalAlign::Right => {
+                (parent_cols.saturating_sub(cols + margin), cols + margin <= parent_cols)
+            }
+            HorizontalAlign::Middle => (parent_cols.saturating_sub(cols) / 2, cols <= parent_cols),
         };
         self.set_origin((newx, y));
+        fits
     }
 
-    fn valign(&mut self, parent: &HasSize, valign: VerticalAlign, margin: usize) {
+    /// Aligns vertically within `parent`, returning `false` if the widget doesn't fit.
+    fn valign(&mut self, parent: &HasSize, valign: VerticalAlign, margin: usize) -> bool {
         let (_, rows) = self.size();
         let (x, _) = self.origin();
         let (_, parent_rows) = parent.size();
-        let newy = match valign {
-            VerticalAlign::Top => margin,
-            VerticalAlign::Bottom => parent_rows - rows - margin,
-            VerticalAlign::Middle => (parent_rows - rows) / 2,
+        let (newy, fits) = match valign {
+            VerticalAlign::Top => (margin, margin + rows <= parent_rows),
+            VerticalAlign::Bottom => {
+                (parent_rows.saturating_sub(rows + margin), rows + margin <= parent_rows)
+            }
+            VerticalAlign::Middle => (parent_rows.saturating_sub(rows) / 2, rows <= parent_rows),
         };
         self.set_origin((x, newy));
+        fits
     }
 
+    /// Aligns within `parent` in both directions, returning `false` if the widget doesn't fit.
     fn align(&mut self,
              parent: &HasSize,
              halign: HorizontalAlign,
              valign: VerticalAlign,
-             margin: usize) {
-        self.halign(parent, halign, margin);
-        self.valign(parent, valign, margin);
+             margin: usize)
+             -> bool {
+        let hfits = self.halign(parent, halign, margin);
+        let vfits = self.valign(parent, valign, margin);
+        hfits && vfits
     }
+
+    /// Places the widget next to `sibling`, `gap` cells away from it, such as an input right of
+    /// its label. Returns `false` if there is no room left or above the sibling, in which case
+    /// the widget is placed at the edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::HasPosition;
+    /// use rustty::ui::{Alignable, Placement, Widget};
+    ///
+    /// let mut label = Widget::new(6, 1);
+    /// label.set_origin((2, 3));
+    /// let mut input = Widget::new(20, 1);
+    ///
+    /// assert!(input.place_by(&label, Placement::RightOf, 2));
+    /// assert_eq!(input.origin(), (10, 3));
+    /// ```
+    fn place_by(&mut self, sibling: &dyn Alignable, placement: Placement, gap: usize) -> bool {
+        let (cols, rows) = self.size();
+        let (sx, sy) = sibling.origin();
+        let (scols, srows) = sibling.size();
+        let (newx, newy, fits) = match placement {
+            Placement::LeftOf => (sx.saturating_sub(cols + gap), sy, cols + gap <= sx),
+            Placement::RightOf => (sx + scols + gap, sy, true),
+            Placement::Above => (sx, sy.saturating_sub(rows + gap), rows + gap <= sy),
+            Placement::Below => (sx, sy + srows + gap, true),
+        };
+        self.set_origin((newx, newy));
+        fits
+    }
+}
+
+/// Something that can report how much space it would like before it is laid out.
+///
+/// Layouts measure their elements first and arrange them afterwards, so sizes are derived from
+/// content instead of being chosen by hand.
+pub trait Measure {
+    /// Returns the size needed to show everything comfortably.
+    fn preferred_size(&self) -> Size;
+
+    /// Returns the smallest size that is still usable, for when space is short. Defaults to the
+    /// preferred size.
+    fn minimum_size(&self) -> Size {
+        self.preferred_size()
+    }
+}
+
+/// Something that can lay itself out again when its parent changes size.
+///
+/// Call `relayout()` on the roots of the widget tree after the terminal is resized, for instance
+/// when `Terminal::try_resize()` returns a new size.
+pub trait Relayout {
+    /// Re-resolves sizes and alignments against `parent`, returning `true` if the size changed
+    /// and the contents need to be drawn again.
+    fn relayout(&mut self, parent: &dyn HasSize) -> bool;
 }
 
 pub struct HorizontalLayout<'a> {
@@ -57,16 +225,24 @@ pub struct HorizontalLayout<'a> {
 impl<'a> HorizontalLayout<'a> {
     pub fn new(elems: Vec<&mut Alignable>, inner_margin: usize) -> HorizontalLayout {
         let first_origin = elems.first().unwrap().origin();
-        let total_width = elems.iter().fold(0, |acc, item| acc + item.size().0);
-        let width = total_width + inner_margin * (elems.len() - 1);
-        HorizontalLayout {
+        let mut layout = HorizontalLayout {
             origin: first_origin,
-            size: (width, 1),
+            size: (0, 0),
             inner_margin: inner_margin,
             elems: elems,
-        }
+        };
+        layout.size = layout.preferred_size();
+        layout
     }
 
+    /// Measures the elements again, picking up any changes to their sizes since the layout was
+    /// constructed. Call this before aligning the layout itself.
+    pub fn measure(&mut self) -> Size {
+        self.size = self.preferred_size();
+        self.size
+    }
+
+    /// Arranges the elements side by side from the layout's origin.
     pub fn align_elems(&mut self) {
         let (x, y) = self.origin();
         let mut current_x = x;
@@ -94,3 +270,26 @@ impl<'a> HasPosition for HorizontalLayout<'a> {
 }
 
 impl<'a> Alignable for HorizontalLayout<'a> {}
+
+impl<'a> Measure for HorizontalLayout<'a> {
+    /// Returns the width of the elements and the margins between them, and the height of the
+    /// tallest element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{create_button, Alignable, HorizontalLayout, Measure};
+    ///
+    /// let mut ok = create_button("Ok", None);
+    /// let mut cancel = create_button("Cancel", None);
+    /// let layout = HorizontalLayout::new(vec![&mut ok as &mut dyn Alignable, &mut cancel], 2);
+    /// assert_eq!(layout.preferred_size(), (18, 1));
+    /// ```
+    fn preferred_size(&self) -> Size {
+        let gaps = self.inner_margin * self.elems.len().saturating_sub(1);
+        self.elems.iter().fold((gaps, 0), |(w, h), elem| {
+            let (cols, rows) = elem.size();
+            (w + cols, cmp::max(h, rows))
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use core::cellbuffer::{Cell, CellBuilder, Color};
+
+/// Prints text with inline style markup into cells, see `Painter::stamp()`.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate rustty;
+///
+/// use rustty::{Color, CellAccessor};
+/// use rustty::ui::Widget;
+///
+/// fn main() {
+///     let mut status = Widget::new(20, 1);
+///     stamp!(status, 0, 0, "disk {red bold}full{/}!");
+///     assert_eq!(status.get(5, 0).unwrap().fg(), Color::Red);
+///     assert_eq!(status.get(9, 0).unwrap().fg(), Color::Default);
+/// }
+/// ```
+#[macro_export]
+macro_rules! stamp {
+    ($cells:expr, $x:expr, $y:expr, $markup:expr) => {{
+        use $crate::ui::Painter;
+        $cells.stamp($x, $y, $markup)
+    }};
+}
+
+// Splits `markup` into runs of text and the cells holding their style, starting from `base`.
+//
+// `{...}` holds a space-separated list of style words that apply until the matching `{/}`: color
+// names set the foreground, `on` followed by a color name sets the background, and `bold`,
+// `underline`, `reverse`, `italic`, `dim`, `strikethrough` and `blink` add attributes. Unknown
+// words are ignored. `{{` and `}}` stand for literal braces.
+pub fn runs(markup: &str, base: Cell) -> Vec<(String, Cell)> {
+    let mut runs = Vec::new();
+    let mut styles = vec![base];
+    let mut text = String::new();
+    let mut chars = markup.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '}' if chars.peek() == Some(&ch) => {
+                chars.next();
+                text.push(ch);
+            }
+            '{' => {
+                let tag: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let current = *styles.last().unwrap();
+                if !text.is_empty() {
+                    runs.push((text.clone(), current));
+                    text.clear();
+                }
+                if tag.trim() == "/" {
+                    if styles.len() > 1 {
+                        styles.pop();
+                    }
+                } else {
+                    styles.push(apply(&tag, current));
+                }
+            }
+            ch => text.push(ch),
+        }
+    }
+    if !text.is_empty() {
+        runs.push((text, *styles.last().unwrap()));
+    }
+    runs
+}
+
+// Returns `cell` with the style words in `tag` applied.
+fn apply(tag: &str, cell: Cell) -> Cell {
+    let mut style = CellBuilder::from(cell);
+    let mut words = tag.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.bold(),
+            "underline" => style.underline(),
+            "reverse" => style.reverse(),
+            "italic" => style.italic(),
+            "dim" => style.dim(),
+            "strikethrough" => style.strikethrough(),
+            "blink" => style.blink(),
+            "on" => {
+                match words.next().and_then(color) {
+                    Some(bg) => style.bg(bg),
+                    None => style,
+                }
+            }
+            word => {
+                match color(word) {
+                    Some(fg) => style.fg(fg),
+                    None => style,
+                }
+            }
+        };
+    }
+    style.build()
+}
+
+fn color(name: &str) -> Option<Color> {
+    match name {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        "default" => Some(Color::Default),
+        _ => None,
+    }
+}
@@ -3,9 +3,53 @@ mod layout;
 mod widget;
 mod button;
 mod dialog;
+mod history;
+mod completion;
+mod secret;
+mod colorpicker;
+mod keymap;
+mod editing;
+mod preedit;
+mod draw;
+mod scene;
+mod hud;
+mod chart;
+mod dashboard;
+mod progress;
+mod clock;
+mod decorator;
+mod scrollbar;
+mod markup;
+mod clipboard;
+mod undo;
+mod validate;
+mod format;
 
-pub use ui::painter::Painter;
-pub use ui::layout::{Alignable, HorizontalAlign, VerticalAlign, HorizontalLayout};
+pub mod feedback;
+
+pub use ui::painter::{Painter, Orientation};
+pub use ui::layout::{Alignable, HorizontalAlign, VerticalAlign, HorizontalLayout, Placement, Extent,
+                     SizeSpec, Measure, Relayout};
 pub use ui::widget::Widget;
 pub use ui::button::create_button;
 pub use ui::dialog::{Dialog, DialogResult};
+pub use ui::history::History;
+pub use ui::completion::Completer;
+pub use ui::secret::{SecretText, Mask};
+pub use ui::colorpicker::{ColorPicker, ColorGrid};
+pub use ui::keymap::{Keymap, KeymapStack, KeymapContext, Binding, KeyTranslation};
+pub use ui::editing::{EditBindings, EditPreset, EditMode, Edit, EditAction};
+pub use ui::preedit::Preedit;
+pub use ui::draw::{Draw, Offset, Styled, Clipped, Then};
+pub use ui::scene::{Scene, LayerId, EntityId};
+pub use ui::hud::{Hud, HudId, Anchor};
+pub use ui::chart::{Chart, Series, SeriesStyle, Marker};
+pub use ui::dashboard::{Dashboard, SlotView, SlotValue};
+pub use ui::progress::{MultiProgress, ProgressBar};
+pub use ui::clock::{Clock, ClockMode};
+pub use ui::decorator::{Bordered, Padded, BorderStyle, draw_border, draw_title};
+pub use ui::scrollbar::Scrollbar;
+pub use ui::clipboard::{Clipboard, ClipboardBackend};
+pub use ui::undo::UndoStack;
+pub use ui::validate::InputMask;
+pub use ui::format::{Formatter, DefaultFormatter};
@@ -1,5 +1,8 @@
 use core::cellbuffer::{CellAccessor, Cell};
+use core::width;
 use ui::layout::{HorizontalAlign, VerticalAlign};
+use ui::decorator::{self, BorderStyle};
+use ui::markup;
 
 #[derive(Clone, Copy)]
 pub enum Orientation {
@@ -13,6 +16,10 @@ pub trait Painter: CellAccessor {
     /// This is a shorthand for setting each cell individually. `cell`'s style is going to be
     /// copied to each destination cell.
     ///
+    /// Characters advance by their display width under the current `WidthPolicy`: wide
+    /// characters make the cell after theirs a continuation cell, see `CellAccessor::put_char()`,
+    /// and characters without width are left out.
+    ///
     /// # Examples
     ///
     /// ```
@@ -25,20 +32,28 @@ pub trait Painter: CellAccessor {
     /// ```
     fn printline_with_cell(&mut self, x: usize, y: usize, line: &str, cell: Cell) {
         let (cols, _) = self.size();
-        for (index, ch) in line.chars().enumerate() {
-            let current_x = x + index;
-            if current_x >= cols {
+        let mut current_x = x;
+        for grapheme in width::graphemes(line) {
+            // Cells hold a single char, so combining marks and joined characters are dropped.
+            let ch = grapheme.chars().next().unwrap();
+            let width = width::char_width(ch);
+            if width == 0 {
+                continue;
+            }
+            if current_x + width > cols {
                 break;
             }
-            match self.get_mut(current_x, y) {
+            let cell_x = current_x;
+            current_x += width;
+            match self.get_mut(cell_x, y) {
                 Some(c) => {
                     c.set_fg(cell.fg());
                     c.set_bg(cell.bg());
                     c.set_attrs(cell.attrs());
-                    c.set_ch(ch);
                 }
-                None => {}
+                None => continue,
             }
+            self.put_char(cell_x, y, ch);
         }
     }
 
@@ -50,15 +65,64 @@ pub trait Painter: CellAccessor {
         self.printline_with_cell(x, y, line, Cell::default());
     }
 
+    /// Prints text with inline style markup at the specified position and returns the number of
+    /// columns printed.
+    ///
+    /// A tag such as `{red on blue bold}` styles the text up to the matching `{/}`. Color names
+    /// set the foreground, `on` followed by a color name sets the background, and `bold`,
+    /// `underline` and `reverse` add attributes; tags nest. Write `{{` and `}}` for literal braces.
+    /// The `stamp!` macro is a shorthand for this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{Attr, Color, CellAccessor};
+    /// use rustty::ui::{Painter, Widget};
+    ///
+    /// let mut status = Widget::new(30, 1);
+    /// status.stamp(0, 0, "{bold}3{/} jobs, {yellow on blue}1 {underline}late{/}{/}");
+    /// assert_eq!(status.get(0, 0).unwrap().attrs(), Attr::Bold);
+    /// assert_eq!(status.get(12, 0).unwrap().bg(), Color::Blue);
+    /// assert_eq!(status.get(12, 0).unwrap().attrs(), Attr::Underline);
+    /// ```
+    fn stamp(&mut self, x: usize, y: usize, markup: &str) -> usize {
+        let mut cx = x;
+        for (text, cell) in markup::runs(markup, Cell::default()) {
+            self.printline_with_cell(cx, y, &text, cell);
+            cx += width::display_width(&text);
+        }
+        cx - x
+    }
+
     /// Returns the proper x coord to align `line` in the specified `halign` alignment.
     ///
     /// `margin` is the number of characters we want to leave near the borders.
+    ///
+    /// The line is measured by its display width under the current `WidthPolicy`, so wide
+    /// characters count as two columns and combining characters as none. Lines too wide to be
+    /// aligned start at column 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{HorizontalAlign, Painter, Widget};
+    ///
+    /// let frame = Widget::new(10, 1);
+    /// assert_eq!(frame.halign_line("日本", HorizontalAlign::Middle, 0), 3);
+    /// assert_eq!(frame.halign_line("cafe\u{301}", HorizontalAlign::Right, 1), 4);
+    ///
+    /// // Lines wider than the frame don't underflow.
+    /// let long = "much too long for the frame";
+    /// assert_eq!(frame.halign_line(long, HorizontalAlign::Middle, 0), 0);
+    /// assert_eq!(frame.halign_line(long, HorizontalAlign::Right, 1), 0);
+    /// ```
     fn halign_line(&self, line: &str, halign: HorizontalAlign, margin: usize) -> usize {
         let (cols, _) = self.size();
+        let width = width::display_width(line);
         match halign {
             HorizontalAlign::Left => margin,
-            HorizontalAlign::Right => cols - line.chars().count() - margin - 1,
-            HorizontalAlign::Middle => (cols - line.chars().count()) / 2,
+            HorizontalAlign::Right => cols.saturating_sub(width + margin + 1),
+            HorizontalAlign::Middle => cols.saturating_sub(width) / 2,
         }
     }
 
@@ -69,12 +133,23 @@ pub trait Painter: CellAccessor {
     /// For now, the contents of line has no incidence whatsoever on the result, but when we
     /// support multi-line strings, it will, so we might as well stay consistent with
     /// `halign_line()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{VerticalAlign, Painter, Widget};
+    ///
+    /// let frame = Widget::new(10, 1);
+    /// assert_eq!(frame.valign_line("", VerticalAlign::Bottom, 0), 0);
+    /// // Margins larger than the frame don't underflow.
+    /// assert_eq!(frame.valign_line("", VerticalAlign::Bottom, 3), 0);
+    /// ```
     #[allow(unused_variables)]
     fn valign_line(&self, line: &str, valign: VerticalAlign, margin: usize) -> usize {
         let (_, rows) = self.size();
         match valign {
             VerticalAlign::Top => margin,
-            VerticalAlign::Bottom => rows - margin - 1,
+            VerticalAlign::Bottom => rows.saturating_sub(margin + 1),
             VerticalAlign::Middle => rows / 2,
         }
     }
@@ -99,24 +174,108 @@ pub trait Painter: CellAccessor {
         }
     }
 
-    fn draw_box(&mut self) {
-        let (cols, rows) = self.size();
-        let corners = [(0, 0, '┌'),
-                       (cols - 1, 0, '┐'),
-                       (cols - 1, rows - 1, '┘'),
-                       (0, rows - 1, '└')];
-        for &(x, y, ch) in corners.iter() {
-            self.get_mut(x, y).unwrap().set_ch(ch);
-        }
-        let lines = [(1, 0, cols - 2, Orientation::Horizontal, '─'),
-                     (1, rows - 1, cols - 2, Orientation::Horizontal, '─'),
-                     (0, 1, rows - 2, Orientation::Vertical, '│'),
-                     (cols - 1, 1, rows - 2, Orientation::Vertical, '│')];
-        for &(x, y, count, orientation, ch) in lines.iter() {
-            let cell = Cell::with_char(ch);
-            self.repeat_cell(x, y, orientation, count, cell);
+    /// Marks `count` cells starting at `(x, y)` as protected, or unprotected, from editing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::{Painter, Widget, Orientation};
+    ///
+    /// let mut repl = Widget::new(20, 1);
+    /// repl.printline(0, 0, ">>> ");
+    /// repl.set_protected(0, 0, Orientation::Horizontal, 4, true);
+    ///
+    /// assert!(repl.get(3, 0).unwrap().is_protected());
+    /// assert!(!repl.get(4, 0).unwrap().is_protected());
+    /// ```
+    fn set_protected(&mut self,
+                     x: usize,
+                     y: usize,
+                     orientation: Orientation,
+                     count: usize,
+                     protected: bool) {
+        for i in 0..count {
+            let (ix, iy) = match orientation {
+                Orientation::Horizontal => (x + i, y),
+                Orientation::Vertical => (x, y + i),
+            };
+            if let Some(c) = self.get_mut(ix, iy) {
+                c.set_protected(protected);
+            }
         }
     }
+
+    /// Draws a border around the edge of the cells in the given style.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::{BorderStyle, Painter, Widget};
+    ///
+    /// # rustty::set_supported(rustty::Feature::Unicode, true);
+    /// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+    /// let mut panel = Widget::new(4, 3);
+    /// panel.draw_border(BorderStyle::Double);
+    /// assert_eq!(panel.get(0, 0).unwrap().ch(), '╔');
+    /// assert_eq!(panel.get(3, 1).unwrap().ch(), '║');
+    /// ```
+    fn draw_border(&mut self, style: BorderStyle) {
+        let size = self.size();
+        decorator::draw_border(self, 0, 0, size, style, None);
+    }
+
+    /// Draws a title into the top row, between separators, as in `┌─┤ title ├──┐`.
+    ///
+    /// Titles too long for the width are cut short with an ellipsis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::{HorizontalAlign, Painter, Widget};
+    ///
+    /// # rustty::set_supported(rustty::Feature::Unicode, true);
+    /// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+    /// let mut panel = Widget::new(16, 3);
+    /// panel.draw_box();
+    /// panel.draw_title("Options", HorizontalAlign::Right);
+    /// assert_eq!(panel.get(3, 0).unwrap().ch(), '┤');
+    /// assert_eq!(panel.get(13, 0).unwrap().ch(), '├');
+    ///
+    /// panel.draw_box();
+    /// panel.draw_title("Preferences", HorizontalAlign::Left);
+    /// assert_eq!(panel.get(12, 0).unwrap().ch(), '…');
+    /// ```
+    fn draw_title(&mut self, title: &str, align: HorizontalAlign) {
+        let (cols, _) = self.size();
+        decorator::draw_title(self, 0, 0, cols, BorderStyle::Single, title, align);
+    }
+
+    /// Draws a single-line border around the edge of the cells.
+    ///
+    /// Cells too small for a box get a single line instead, and empty cells are left alone, so
+    /// this is safe to call after the terminal has been resized to almost nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::CellAccessor;
+    /// use rustty::ui::{Painter, Widget};
+    ///
+    /// # rustty::set_supported(rustty::Feature::Unicode, true);
+    /// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+    /// let mut bar = Widget::new(3, 1);
+    /// bar.draw_box();
+    /// assert_eq!(bar.get(0, 0).unwrap().ch(), '─');
+    ///
+    /// Widget::new(0, 0).draw_box();
+    /// Widget::new(1, 1).draw_box();
+    /// ```
+    fn draw_box(&mut self) {
+        self.draw_border(BorderStyle::Single);
+    }
 }
 
-impl<T: CellAccessor> Painter for T {}
+impl<T: CellAccessor + ?Sized> Painter for T {}
@@ -0,0 +1,89 @@
+use std::cmp;
+
+use core::position::Pos;
+use core::cellbuffer::{Attr, CellAccessor};
+
+/// Uncommitted text being composed by an input method.
+///
+/// While an input method (IME) composes a character, or offers candidate text, the pre-edit
+/// string is shown underlined at the cursor without being inserted into the widget's buffer.
+/// `draw()` paints it over the widget's cells and returns where the hardware cursor belongs, so
+/// the terminal's IME opens its candidate window next to the composition.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{Attr, CellAccessor};
+/// use rustty::ui::{Painter, Preedit, Widget};
+///
+/// let mut input = Widget::new(20, 1);
+/// input.printline(0, 0, "name: ");
+///
+/// let mut preedit = Preedit::new();
+/// preedit.set("にほ", 2);
+///
+/// let cursor = preedit.draw(&mut input, (6, 0));
+/// assert_eq!(cursor, (8, 0));
+/// assert_eq!(input.get(6, 0).unwrap().ch(), 'に');
+/// assert_eq!(input.get(6, 0).unwrap().attrs(), Attr::Underline);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preedit {
+    text: String,
+    caret: usize,
+}
+
+impl Preedit {
+    /// Constructs a new, empty `Preedit`.
+    pub fn new() -> Preedit {
+        Preedit::default()
+    }
+
+    /// Replaces the pre-edit text, with the input method's caret `caret` characters into it.
+    pub fn set(&mut self, text: &str, caret: usize) {
+        self.text = text.to_owned();
+        self.caret = cmp::min(caret, self.text.chars().count());
+    }
+
+    /// Removes the pre-edit text, typically once it has been committed or cancelled.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.caret = 0;
+    }
+
+    /// Returns the pre-edit text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the position of the caret within the pre-edit text, in characters.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Returns `true` if there is no pre-edit text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Draws the pre-edit text underlined at `pos`, keeping the colors of the cells it covers.
+    ///
+    /// Text that doesn't fit on the row is clipped, and protected cells are left untouched.
+    /// Returns the position the hardware cursor should be moved to, relative to the same cells.
+    pub fn draw(&self, cells: &mut dyn CellAccessor, pos: Pos) -> Pos {
+        let (x, y) = pos;
+        let (cols, _) = cells.size();
+        for (i, ch) in self.text.chars().enumerate() {
+            if let Some(cell) = cells.get_editable_mut(x + i, y) {
+                let attrs = underlined(cell.attrs());
+                cell.set_ch(ch).set_attrs(attrs);
+            }
+        }
+        (cmp::min(x + self.caret, cols.saturating_sub(1)), y)
+    }
+}
+
+// Adds underlining to `attr`.
+fn underlined(attr: Attr) -> Attr {
+    attr | Attr::Underline
+}
@@ -0,0 +1,247 @@
+use std::cmp;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use core::cellbuffer::CellAccessor;
+use core::width;
+use ui::format::{DefaultFormatter, Formatter};
+use ui::painter::Painter;
+
+struct Bar {
+    id: usize,
+    message: String,
+    pos: u64,
+    len: u64,
+    finished: bool,
+}
+
+struct State {
+    bars: Vec<Bar>,
+    next_id: usize,
+    collapse: bool,
+    dirty: bool,
+    formatter: Box<dyn Formatter + Send>,
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    // The state is always left consistent, so a poisoned lock can be recovered.
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// A set of progress bars updated concurrently, drawn one per row.
+///
+/// Bars are drawn in the order they were added, each with its message, a bar and a
+/// `position/length` count. Finished bars are collapsed out of the display by default so that
+/// only work in progress takes up space. Bars are updated through `ProgressBar` handles, which
+/// can be sent to worker threads; the UI thread redraws when `take_dirty()` reports a change.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use rustty::CellAccessor;
+/// use rustty::ui::{MultiProgress, Widget};
+///
+/// let progress = MultiProgress::new();
+/// let download = progress.add("download", 100);
+/// let build = progress.add("build", 10);
+///
+/// let worker = thread::spawn(move || {
+///     download.set_position(50);
+///     download.set_message("download foo.tar");
+/// });
+/// worker.join().unwrap();
+/// build.finish();
+///
+/// assert!(progress.take_dirty());
+/// assert_eq!(progress.len(), 1);
+///
+/// let mut screen = Widget::new(40, 2);
+/// progress.draw_into(&mut screen);
+/// assert_eq!(screen.get(0, 0).unwrap().ch(), 'd');
+/// assert_eq!(screen.get(0, 1).unwrap().ch(), ' ');
+/// ```
+pub struct MultiProgress {
+    state: Arc<Mutex<State>>,
+}
+
+impl MultiProgress {
+    /// Constructs a new `MultiProgress` with no bars.
+    pub fn new() -> MultiProgress {
+        MultiProgress {
+            state: Arc::new(Mutex::new(State {
+                bars: Vec::new(),
+                next_id: 0,
+                collapse: true,
+                dirty: false,
+                formatter: Box::new(DefaultFormatter),
+            })),
+        }
+    }
+
+    /// Adds a bar below the existing ones, for work of the given length.
+    pub fn add(&self, message: &str, len: u64) -> ProgressBar {
+        let mut state = lock(&self.state);
+        state.next_id += 1;
+        let id = state.next_id;
+        state.bars.push(Bar {
+            id,
+            message: message.to_owned(),
+            pos: 0,
+            len,
+            finished: false,
+        });
+        state.dirty = true;
+        ProgressBar {
+            state: self.state.clone(),
+            id,
+        }
+    }
+
+    /// Sets whether finished bars are hidden. The default is `true`.
+    pub fn set_collapse(&self, collapse: bool) {
+        let mut state = lock(&self.state);
+        state.collapse = collapse;
+        state.dirty = true;
+    }
+
+    /// Sets how the counts of the bars are formatted, with `Formatter::count()`.
+    pub fn set_formatter<F: Formatter + Send + 'static>(&self, formatter: F) {
+        let mut state = lock(&self.state);
+        state.formatter = Box::new(formatter);
+        state.dirty = true;
+    }
+
+    /// Returns the number of bars shown.
+    pub fn len(&self) -> usize {
+        let state = lock(&self.state);
+        state.bars.iter().filter(|b| !(state.collapse && b.finished)).count()
+    }
+
+    /// Returns `true` if no bars are shown.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if all bars have finished.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.state).bars.iter().all(|b| b.finished)
+    }
+
+    /// Returns `true` if any bar changed since the last call, resetting the flag.
+    pub fn take_dirty(&self) -> bool {
+        let mut state = lock(&self.state);
+        let dirty = state.dirty;
+        state.dirty = false;
+        dirty
+    }
+
+    /// Draws the shown bars into `cells`, one per row from the top, clearing the rows below.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        let state = lock(&self.state);
+        let (cols, rows) = cells.size();
+        let bars: Vec<&Bar> = state.bars
+                                   .iter()
+                                   .filter(|b| !(state.collapse && b.finished))
+                                   .collect();
+        let longest = bars.iter().map(|b| width::display_width(&b.message)).max().unwrap_or(0);
+        let msg_width = cmp::min(longest, cols / 3);
+        let counts: Vec<String> = bars.iter()
+            .map(|b| format!("{}/{}", state.formatter.count(b.pos), state.formatter.count(b.len)))
+            .collect();
+        let count_width = counts.iter().map(|c| width::display_width(c)).max().unwrap_or(0);
+        for y in 0..rows {
+            let line = match bars.get(y).zip(counts.get(y)) {
+                Some((bar, count)) => render(bar, count, msg_width, count_width, cols),
+                None => String::new(),
+            };
+            let pad = cols.saturating_sub(width::display_width(&line));
+            cells.printline(0, y, &format!("{}{}", line, " ".repeat(pad)));
+        }
+    }
+}
+
+impl Default for MultiProgress {
+    fn default() -> MultiProgress {
+        MultiProgress::new()
+    }
+}
+
+/// A handle to one bar of a `MultiProgress`, which can be cloned and sent between threads.
+#[derive(Clone)]
+pub struct ProgressBar {
+    state: Arc<Mutex<State>>,
+    id: usize,
+}
+
+impl ProgressBar {
+    /// Sets how much of the work is done.
+    pub fn set_position(&self, pos: u64) {
+        self.update(|b| b.pos = cmp::min(pos, b.len));
+    }
+
+    /// Advances the bar by `delta`.
+    pub fn inc(&self, delta: u64) {
+        self.update(|b| b.pos = cmp::min(b.pos.saturating_add(delta), b.len));
+    }
+
+    /// Sets the length of the work.
+    pub fn set_length(&self, len: u64) {
+        self.update(|b| {
+            b.len = len;
+            b.pos = cmp::min(b.pos, len);
+        });
+    }
+
+    /// Sets the message shown before the bar.
+    pub fn set_message(&self, message: &str) {
+        self.update(|b| b.message = message.to_owned());
+    }
+
+    /// Marks the work as done, filling the bar.
+    pub fn finish(&self) {
+        self.update(|b| {
+            b.pos = b.len;
+            b.finished = true;
+        });
+    }
+
+    /// Returns `true` if the bar has finished.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.state).bars.iter().any(|b| b.id == self.id && b.finished)
+    }
+
+    fn update<F: FnOnce(&mut Bar)>(&self, f: F) {
+        let mut state = lock(&self.state);
+        if let Some(bar) = state.bars.iter_mut().find(|b| b.id == self.id) {
+            f(bar);
+            state.dirty = true;
+        }
+    }
+}
+
+// Renders a bar as `message [=====>    ] pos/len`, fitted to `cols` columns. The message and
+// count are padded by display width, since `format!` pads by chars.
+fn render(bar: &Bar, count: &str, msg_width: usize, count_width: usize, cols: usize) -> String {
+    let message = width::truncate_to_width(&bar.message, msg_width);
+    let fixed = msg_width + count_width + 4;
+    let mut line = format!("{}{}", message, " ".repeat(msg_width - width::display_width(message)));
+    if cols > fixed {
+        let width = cols - fixed;
+        let filled = if bar.len == 0 {
+            width
+        } else {
+            (bar.pos as f64 / bar.len as f64 * width as f64) as usize
+        };
+        let head = if filled < width { ">" } else { "" };
+        let empty = width - filled - head.len();
+        line.push_str(&format!(" [{}{}{}] ", "=".repeat(filled), head, " ".repeat(empty)));
+    } else {
+        line.push(' ');
+    }
+    line.push_str(&" ".repeat(count_width - width::display_width(count)));
+    line.push_str(count);
+    width::truncate_to_width(&line, cols).to_owned()
+}
@@ -0,0 +1,204 @@
+use core::cellbuffer::CellAccessor;
+use ui::draw::Draw;
+
+/// Identifies a layer of a `Scene`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LayerId(usize);
+
+/// Identifies an object in a `Scene`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EntityId(usize);
+
+struct Entity<T> {
+    id: EntityId,
+    pos: (isize, isize),
+    visible: bool,
+    item: T,
+}
+
+struct Layer<T> {
+    id: LayerId,
+    z: i32,
+    offset: (isize, isize),
+    visible: bool,
+    entities: Vec<Entity<T>>,
+}
+
+/// A set of positioned objects arranged in layers, drawn back to front.
+///
+/// Layers are drawn in order of increasing z-index, and objects within a layer in the order they
+/// were added, so a game can move its entities around and redraw the whole scene each frame
+/// instead of erasing and redrawing them by hand. Each layer has an offset added to the positions
+/// of its objects, which gives parallax scrolling when background layers are moved more slowly
+/// than foreground ones.
+///
+/// Use `Scene<Box<dyn Draw>>` to mix different kinds of objects.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::CellAccessor;
+/// use rustty::ui::{Painter, Scene, Widget};
+///
+/// let mut scene = Scene::new();
+/// let stars = scene.add_layer(0);
+/// let ships = scene.add_layer(10);
+///
+/// let mut star = Widget::new(1, 1);
+/// star.printline(0, 0, "*");
+/// let mut ship = Widget::new(1, 1);
+/// ship.printline(0, 0, "A");
+///
+/// scene.add(stars, (2, 0), star);
+/// let player = scene.add(ships, (2, 0), ship);
+///
+/// let mut screen = Widget::new(5, 1);
+/// scene.draw_into(&mut screen);
+/// assert_eq!(screen.get(2, 0).unwrap().ch(), 'A');
+///
+/// // Move the player away and scroll the background.
+/// scene.set_position(player, (4, 0));
+/// scene.set_layer_offset(stars, (-1, 0));
+/// screen.clear(Default::default());
+/// scene.draw_into(&mut screen);
+/// assert_eq!(screen.get(1, 0).unwrap().ch(), '*');
+/// assert_eq!(screen.get(4, 0).unwrap().ch(), 'A');
+/// ```
+pub struct Scene<T: Draw> {
+    layers: Vec<Layer<T>>,
+    next_id: usize,
+}
+
+impl<T: Draw> Scene<T> {
+    /// Constructs a new, empty `Scene`.
+    pub fn new() -> Scene<T> {
+        Scene {
+            layers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds an empty layer with the given z-index. Layers with equal z-indices are drawn in the
+    /// order they were added.
+    pub fn add_layer(&mut self, z: i32) -> LayerId {
+        let id = LayerId(self.next_id());
+        let at = self.layers.iter().position(|l| l.z > z).unwrap_or(self.layers.len());
+        self.layers.insert(at,
+                           Layer {
+                               id,
+                               z,
+                               offset: (0, 0),
+                               visible: true,
+                               entities: Vec::new(),
+                           });
+        id
+    }
+
+    /// Removes a layer and all objects on it. Returns `false` if there is no such layer.
+    pub fn remove_layer(&mut self, layer: LayerId) -> bool {
+        let len = self.layers.len();
+        self.layers.retain(|l| l.id != layer);
+        self.layers.len() != len
+    }
+
+    /// Sets the offset added to the positions of all objects on a layer.
+    pub fn set_layer_offset(&mut self, layer: LayerId, offset: (isize, isize)) -> bool {
+        self.layer_mut(layer).map(|l| l.offset = offset).is_some()
+    }
+
+    /// Returns the offset of a layer.
+    pub fn layer_offset(&self, layer: LayerId) -> Option<(isize, isize)> {
+        self.layers.iter().find(|l| l.id == layer).map(|l| l.offset)
+    }
+
+    /// Shows or hides a whole layer.
+    pub fn set_layer_visible(&mut self, layer: LayerId, visible: bool) -> bool {
+        self.layer_mut(layer).map(|l| l.visible = visible).is_some()
+    }
+
+    /// Adds an object to a layer at the given position, above the objects already on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such layer.
+    pub fn add(&mut self, layer: LayerId, pos: (isize, isize), item: T) -> EntityId {
+        let id = EntityId(self.next_id());
+        let layer = self.layer_mut(layer).expect("no such layer");
+        layer.entities.push(Entity {
+            id,
+            pos,
+            visible: true,
+            item,
+        });
+        id
+    }
+
+    /// Removes an object from the scene and returns it.
+    pub fn remove(&mut self, entity: EntityId) -> Option<T> {
+        for layer in &mut self.layers {
+            if let Some(i) = layer.entities.iter().position(|e| e.id == entity) {
+                return Some(layer.entities.remove(i).item);
+            }
+        }
+        None
+    }
+
+    /// Returns a reference to an object.
+    pub fn get(&self, entity: EntityId) -> Option<&T> {
+        self.entity(entity).map(|e| &e.item)
+    }
+
+    /// Returns a mutable reference to an object.
+    pub fn get_mut(&mut self, entity: EntityId) -> Option<&mut T> {
+        self.entity_mut(entity).map(|e| &mut e.item)
+    }
+
+    /// Returns the position of an object, relative to its layer's offset.
+    pub fn position(&self, entity: EntityId) -> Option<(isize, isize)> {
+        self.entity(entity).map(|e| e.pos)
+    }
+
+    /// Moves an object. Returns `false` if there is no such object.
+    pub fn set_position(&mut self, entity: EntityId, pos: (isize, isize)) -> bool {
+        self.entity_mut(entity).map(|e| e.pos = pos).is_some()
+    }
+
+    /// Shows or hides an object. Returns `false` if there is no such object.
+    pub fn set_visible(&mut self, entity: EntityId, visible: bool) -> bool {
+        self.entity_mut(entity).map(|e| e.visible = visible).is_some()
+    }
+
+    /// Draws all visible objects into `cells`, back to front.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        for layer in self.layers.iter().filter(|l| l.visible) {
+            let (ox, oy) = layer.offset;
+            for entity in layer.entities.iter().filter(|e| e.visible) {
+                let (x, y) = entity.pos;
+                entity.item.draw(cells, x + ox, y + oy);
+            }
+        }
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn layer_mut(&mut self, layer: LayerId) -> Option<&mut Layer<T>> {
+        self.layers.iter_mut().find(|l| l.id == layer)
+    }
+
+    fn entity(&self, entity: EntityId) -> Option<&Entity<T>> {
+        self.layers.iter().flat_map(|l| l.entities.iter()).find(|e| e.id == entity)
+    }
+
+    fn entity_mut(&mut self, entity: EntityId) -> Option<&mut Entity<T>> {
+        self.layers.iter_mut().flat_map(|l| l.entities.iter_mut()).find(|e| e.id == entity)
+    }
+}
+
+impl<T: Draw> Default for Scene<T> {
+    fn default() -> Scene<T> {
+        Scene::new()
+    }
+}
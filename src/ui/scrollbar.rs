@@ -0,0 +1,180 @@
+use std::cmp;
+
+use core::position::{Pos, Size, HasSize, HasPosition};
+use core::cellbuffer::CellAccessor;
+use core::features::{self, Feature};
+use ui::draw::Draw;
+use ui::layout::Alignable;
+use ui::painter::Orientation;
+
+/// A scrollbar showing which part of some content is visible.
+///
+/// The thumb's length is proportional to the fraction of the content in view, and its position
+/// to the scroll offset. A scrollbar can be drawn on its own or placed along the edge of a view
+/// with `attach_to()`.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, HasPosition};
+/// use rustty::ui::{Orientation, Scrollbar, Widget};
+///
+/// # rustty::set_supported(rustty::Feature::Unicode, true);
+/// # rustty::set_supported(rustty::Feature::LineDrawing, true);
+/// let view = Widget::new(20, 10);
+/// let mut bar = Scrollbar::new(Orientation::Vertical, 0);
+/// bar.attach_to(&view);
+/// assert_eq!(bar.origin(), (20, 0));
+///
+/// // 100 lines of content, 10 of them in view, scrolled halfway.
+/// bar.set_content(100, 10);
+/// bar.set_offset(45);
+/// assert_eq!(bar.thumb(), (5, 1));
+///
+/// let mut screen = Widget::new(21, 10);
+/// bar.draw_into(&mut screen);
+/// assert_eq!(screen.get(20, 5).unwrap().ch(), '█');
+/// assert_eq!(screen.get(20, 0).unwrap().ch(), '│');
+/// ```
+pub struct Scrollbar {
+    orientation: Orientation,
+    length: usize,
+    content: usize,
+    viewport: usize,
+    offset: usize,
+    origin: Pos,
+}
+
+impl Scrollbar {
+    /// Constructs a new `Scrollbar` with a track `length` cells long, for content that fits
+    /// entirely in view.
+    pub fn new(orientation: Orientation, length: usize) -> Scrollbar {
+        Scrollbar {
+            orientation,
+            length,
+            content: 0,
+            viewport: 0,
+            offset: 0,
+            origin: (0, 0),
+        }
+    }
+
+    /// Sets the length of the track.
+    pub fn set_length(&mut self, length: usize) {
+        self.length = length;
+    }
+
+    /// Sets the length of the content and of the part of it that is in view, clamping the
+    /// offset to match.
+    pub fn set_content(&mut self, content: usize, viewport: usize) {
+        self.content = content;
+        self.viewport = viewport;
+        self.offset = cmp::min(self.offset, self.max_offset());
+    }
+
+    /// Sets how far the view is scrolled into the content, clamped to the end of the content.
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = cmp::min(offset, self.max_offset());
+    }
+
+    /// Returns how far the view is scrolled into the content.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the largest possible offset.
+    pub fn max_offset(&self) -> usize {
+        self.content.saturating_sub(self.viewport)
+    }
+
+    /// Returns the start and length of the thumb, in cells along the track.
+    pub fn thumb(&self) -> (usize, usize) {
+        if self.content <= self.viewport || self.length == 0 {
+            return (0, self.length);
+        }
+        let len = cmp::max(1, self.length * self.viewport / self.content);
+        let travel = self.length - len;
+        let start = (travel * self.offset + self.max_offset() / 2) / self.max_offset();
+        (start, len)
+    }
+
+    /// Returns the offset that puts the thumb at cell `pos` of the track, for jumping to where
+    /// the track was clicked.
+    pub fn offset_at(&self, pos: usize) -> usize {
+        let (_, len) = self.thumb();
+        let travel = self.length.saturating_sub(len);
+        if travel == 0 {
+            return 0;
+        }
+        cmp::min(pos, travel) * self.max_offset() / travel
+    }
+
+    /// Places the scrollbar along the outside of `view`'s right edge if vertical, or below its
+    /// bottom edge if horizontal, with a track as long as that edge.
+    pub fn attach_to<T: HasSize + HasPosition>(&mut self, view: &T) {
+        let (x, y) = view.origin();
+        let (cols, rows) = view.size();
+        match self.orientation {
+            Orientation::Vertical => {
+                self.origin = (x + cols, y);
+                self.length = rows;
+            }
+            Orientation::Horizontal => {
+                self.origin = (x, y + rows);
+                self.length = cols;
+            }
+        }
+    }
+
+    /// Draws the scrollbar into `cells` at its origin.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        let (x, y) = self.origin;
+        self.draw(cells, x as isize, y as isize);
+    }
+}
+
+impl HasSize for Scrollbar {
+    fn size(&self) -> Size {
+        match self.orientation {
+            Orientation::Vertical => (1, self.length),
+            Orientation::Horizontal => (self.length, 1),
+        }
+    }
+}
+
+impl HasPosition for Scrollbar {
+    fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    fn set_origin(&mut self, new_origin: Pos) {
+        self.origin = new_origin;
+    }
+}
+
+impl Alignable for Scrollbar {}
+
+impl Draw for Scrollbar {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        let (start, len) = self.thumb();
+        let track = match (self.orientation, features::supported(Feature::LineDrawing)) {
+            (Orientation::Vertical, true) => '│',
+            (Orientation::Horizontal, true) => '─',
+            (Orientation::Vertical, false) => '|',
+            (Orientation::Horizontal, false) => '-',
+        };
+        let thumb = if features::supported(Feature::Unicode) { '█' } else { '#' };
+        for i in 0..self.length {
+            let (px, py) = match self.orientation {
+                Orientation::Vertical => (x, y + i as isize),
+                Orientation::Horizontal => (x + i as isize, y),
+            };
+            if px < 0 || py < 0 {
+                continue;
+            }
+            if let Some(cell) = cells.get_mut(px as usize, py as usize) {
+                cell.set_ch(if i >= start && i < start + len { thumb } else { track });
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::ptr;
+
+/// How the contents of a `SecretText` are displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mask {
+    /// Each character is displayed as the given `char`.
+    Char(char),
+    /// Nothing is displayed at all, not even the length of the text.
+    Hidden,
+}
+
+/// An editable text buffer for passwords and other secrets.
+///
+/// A `SecretText` is the value of an input in password mode: the real text can be retrieved
+/// with `reveal()`, but it only ever renders as its `Mask`. It implements neither `Clone` nor
+/// `Display`, so the contents cannot end up on a clipboard or in a log by accident, and its
+/// memory is overwritten with zeroes when it is dropped or grows.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::{SecretText, Mask};
+///
+/// let mut secret = SecretText::new(Mask::Char('*'));
+/// for ch in "hunter2".chars() {
+///     secret.push(ch);
+/// }
+/// secret.pop();
+///
+/// assert_eq!(secret.masked(), "******");
+/// assert_eq!(secret.reveal(), "hunter");
+///
+/// secret.set_mask(Mask::Hidden);
+/// assert_eq!(secret.masked(), "");
+/// ```
+pub struct SecretText {
+    buf: String,
+    mask: Mask,
+}
+
+impl SecretText {
+    /// Constructs a new, empty `SecretText` displayed with the given `Mask`.
+    pub fn new(mask: Mask) -> SecretText {
+        SecretText {
+            buf: String::with_capacity(64),
+            mask,
+        }
+    }
+
+    /// Returns the `Mask` used to display the text.
+    pub fn mask(&self) -> Mask {
+        self.mask
+    }
+
+    /// Sets the `Mask` used to display the text.
+    pub fn set_mask(&mut self, mask: Mask) {
+        self.mask = mask;
+    }
+
+    /// Appends a character to the end of the text.
+    pub fn push(&mut self, ch: char) {
+        if self.buf.len() + ch.len_utf8() > self.buf.capacity() {
+            // Grow by hand so the old allocation can be zeroed instead of being left behind by
+            // the reallocation `String::push` would perform.
+            let mut grown = String::with_capacity(self.buf.capacity() * 2 + ch.len_utf8());
+            grown.push_str(&self.buf);
+            zeroize(&mut self.buf);
+            self.buf = grown;
+        }
+        self.buf.push(ch);
+    }
+
+    /// Removes the last character of the text and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.buf.pop();
+        if let Some(c) = ch {
+            // Scrub the bytes that were just released.
+            let len = self.buf.len();
+            unsafe {
+                let bytes = self.buf.as_mut_vec();
+                for i in len..len + c.len_utf8() {
+                    ptr::write_volatile(bytes.as_mut_ptr().add(i), 0);
+                }
+            }
+        }
+        ch
+    }
+
+    /// Erases the text.
+    pub fn clear(&mut self) {
+        zeroize(&mut self.buf);
+    }
+
+    /// Returns the number of characters in the text.
+    pub fn len(&self) -> usize {
+        self.buf.chars().count()
+    }
+
+    /// Returns `true` if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the real text.
+    pub fn reveal(&self) -> &str {
+        &self.buf
+    }
+
+    /// Returns the text as it should be displayed.
+    pub fn masked(&self) -> String {
+        match self.mask {
+            Mask::Char(ch) => self.buf.chars().map(|_| ch).collect(),
+            Mask::Hidden => String::new(),
+        }
+    }
+}
+
+impl fmt::Debug for SecretText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SecretText").field("mask", &self.mask).finish()
+    }
+}
+
+impl Drop for SecretText {
+    fn drop(&mut self) {
+        zeroize(&mut self.buf);
+    }
+}
+
+// Overwrites the whole allocation of `s` with zeroes and truncates it.
+fn zeroize(s: &mut String) {
+    unsafe {
+        let bytes = s.as_mut_vec();
+        let cap = bytes.capacity();
+        let p = bytes.as_mut_ptr();
+        for i in 0..cap {
+            ptr::write_volatile(p.add(i), 0);
+        }
+        bytes.set_len(0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+/// A bounded undo and redo history of states of type `T`, such as the text and cursor of an
+/// editor.
+///
+/// Before changing its state, an editor pushes a copy of the state it is about to change. Undoing
+/// hands back the last state pushed in exchange for the current one, which redoing hands back in
+/// turn. Pushing anything discards the states that could be redone.
+///
+/// Typing is recorded with `push_insert()`, which merges consecutive insertions into one step
+/// ending after a run of whitespace, so that undo removes a word at a time rather than a character
+/// at a time. At most `capacity` steps are kept, dropping the oldest ones.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::UndoStack;
+///
+/// let mut undo = UndoStack::new(100);
+/// let mut text = String::new();
+/// for ch in "hi you".chars() {
+///     undo.push_insert(text.clone(), ch);
+///     text.push(ch);
+/// }
+/// undo.push(text.clone());
+/// text.clear();
+///
+/// text = undo.undo(text).unwrap();
+/// assert_eq!(text, "hi you");
+/// text = undo.undo(text).unwrap();
+/// assert_eq!(text, "hi ");
+/// text = undo.undo(text).unwrap();
+/// assert_eq!(text, "");
+/// assert_eq!(undo.undo(text.clone()), None);
+///
+/// text = undo.redo(text).unwrap();
+/// assert_eq!(text, "hi ");
+/// ```
+#[derive(Debug, Clone)]
+pub struct UndoStack<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    capacity: usize,
+    // The kind of the last step, if the next insertion may be merged into it.
+    open: Option<Insertion>,
+}
+
+// What a run of insertions has typed so far.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Insertion {
+    Word,
+    Space,
+}
+
+impl<T> UndoStack<T> {
+    /// Constructs an empty `UndoStack` keeping at most `capacity` steps.
+    pub fn new(capacity: usize) -> UndoStack<T> {
+        UndoStack {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity: capacity.max(1),
+            open: None,
+        }
+    }
+
+    /// Returns the maximum number of steps kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of steps kept, dropping the oldest ones beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Returns `true` if there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Removes all steps.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.open = None;
+    }
+
+    /// Records `state`, the state before a change, as a step of its own.
+    pub fn push(&mut self, state: T) {
+        self.record(state);
+        self.open = None;
+    }
+
+    /// Records `state`, the state before `ch` is typed, merging it into the previous step if that
+    /// was typing too and `ch` does not start a new word.
+    pub fn push_insert(&mut self, state: T, ch: char) {
+        let kind = if ch.is_whitespace() {
+            Insertion::Space
+        } else {
+            Insertion::Word
+        };
+        match (self.open, kind) {
+            (Some(Insertion::Word), _) | (Some(Insertion::Space), Insertion::Space) => {}
+            _ => self.record(state),
+        }
+        self.open = Some(kind);
+    }
+
+    /// Ends the current run of insertions, for example when the cursor moves, so that the next
+    /// one starts a step of its own.
+    pub fn seal(&mut self) {
+        self.open = None;
+    }
+
+    /// Returns the state to restore to undo the last step, taking `current` as the state to
+    /// restore when redoing it. Returns `None` if there is nothing to undo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let state = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.open = None;
+        Some(state)
+    }
+
+    /// Returns the state to restore to redo the last undone step, taking `current` as the state
+    /// to restore when undoing it again. Returns `None` if there is nothing to redo.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let state = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.open = None;
+        Some(state)
+    }
+
+    fn record(&mut self, state: T) {
+        self.redo.clear();
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(state);
+    }
+}
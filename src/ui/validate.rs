@@ -0,0 +1,82 @@
+/// Callback checking the text of an input.
+type Check = Box<dyn Fn(&str) -> bool>;
+
+/// Declarative validation of the text of an input.
+///
+/// A mask has two kinds of rules. Restrictions, such as `numeric()`, `max_length()` and
+/// `filter()`, must hold after every keystroke: an editor applies an edit to a copy of its text
+/// and drops the edit unless `accepts()` the result. Requirements added with `require()` only
+/// need to hold once the input is complete, so an editor can flag text that does not meet them
+/// yet, for example by drawing it in another color, and a form checks `is_valid()` before it is
+/// submitted. A regular expression can be used as either kind of rule through a closure.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::ui::InputMask;
+///
+/// let pin = InputMask::new().numeric().max_length(4).require(|text| text.len() == 4);
+///
+/// assert!(pin.accepts("12"));
+/// assert!(!pin.accepts("12a"));
+/// assert!(!pin.accepts("12345"));
+///
+/// assert!(!pin.is_valid("12"));
+/// assert!(pin.is_valid("1234"));
+/// ```
+#[derive(Default)]
+pub struct InputMask {
+    numeric: bool,
+    max_length: Option<usize>,
+    filters: Vec<Check>,
+    requirements: Vec<Check>,
+}
+
+impl InputMask {
+    /// Constructs a mask accepting any text.
+    pub fn new() -> InputMask {
+        InputMask::default()
+    }
+
+    /// Restricts the text to the digits `0` to `9`.
+    pub fn numeric(mut self) -> InputMask {
+        self.numeric = true;
+        self
+    }
+
+    /// Restricts the text to at most `len` characters.
+    pub fn max_length(mut self, len: usize) -> InputMask {
+        self.max_length = Some(len);
+        self
+    }
+
+    /// Restricts the text to what `filter` returns `true` for, after every keystroke.
+    pub fn filter<F>(mut self, filter: F) -> InputMask
+        where F: Fn(&str) -> bool + 'static
+    {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Requires `requirement` to return `true` for the complete text.
+    pub fn require<F>(mut self, requirement: F) -> InputMask
+        where F: Fn(&str) -> bool + 'static
+    {
+        self.requirements.push(Box::new(requirement));
+        self
+    }
+
+    /// Returns `true` if `text` meets the restrictions, so that the keystroke producing it
+    /// should be accepted.
+    pub fn accepts(&self, text: &str) -> bool {
+        (!self.numeric || text.chars().all(|ch| ch.is_ascii_digit())) &&
+        self.max_length.is_none_or(|len| text.chars().count() <= len) &&
+        self.filters.iter().all(|filter| filter(text))
+    }
+
+    /// Returns `true` if `text` meets the restrictions and the requirements, so that it can be
+    /// submitted.
+    pub fn is_valid(&self, text: &str) -> bool {
+        self.accepts(text) && self.requirements.iter().all(|requirement| requirement(text))
+    }
+}
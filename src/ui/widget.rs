@@ -1,11 +1,17 @@
-use core::position::{Pos, Size, HasSize, HasPosition};
-use core::cellbuffer::{CellAccessor, Cell};
-use ui::layout::Alignable;
+use std::cmp;
+use std::time::{Duration, Instant};
+
+use core::position::{Pos, Size, Rect, HasSize, HasPosition};
+use core::cellbuffer::{Attr, CellAccessor, Cell};
+use ui::layout::{Alignable, Measure};
+use ui::draw::Draw;
 
 pub struct Widget {
     origin: Pos,
     size: Size,
     buf: Vec<Cell>,
+    flash_until: Option<Instant>,
+    clip: Option<Rect>,
 }
 
 impl Widget {
@@ -14,27 +20,174 @@ impl Widget {
             origin: (0, 0),
             size: (cols, rows),
             buf: vec![Cell::default(); cols * rows],
+            flash_until: None,
+            clip: None,
         }
     }
 
-    pub fn draw_into(&self, cells: &mut CellAccessor) {
-        let (cols, rows) = self.size();
+    /// Constructs a new `Widget` the size of `size`, which can be a `(cols, rows)` tuple, a
+    /// `Rect`, the `Terminal` or anything else with a size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{HasSize, Rect};
+    /// use rustty::ui::Widget;
+    ///
+    /// let screen = Widget::with_size((80, 24));
+    /// let full = Widget::with_size(&screen);
+    /// assert_eq!(full.size(), (80, 24));
+    /// assert_eq!(Widget::with_size(Rect::new((5, 5), (10, 2))).size(), (10, 2));
+    /// ```
+    pub fn with_size<S: HasSize>(size: S) -> Widget {
+        let (cols, rows) = size.size();
+        Widget::new(cols, rows)
+    }
+
+    /// Constructs a new `Widget` covering `rect`, with its origin at the rectangle's top-left
+    /// corner. Accepts a `Rect` or an `(origin, size)` pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{HasPosition, HasSize};
+    /// use rustty::ui::Widget;
+    ///
+    /// let status = Widget::covering(((0, 23), (80, 1)));
+    /// assert_eq!(status.origin(), (0, 23));
+    /// assert_eq!(status.size(), (80, 1));
+    /// ```
+    pub fn covering<R: Into<Rect>>(rect: R) -> Widget {
+        let rect = rect.into();
+        let mut widget = Widget::with_size(rect);
+        widget.set_origin(rect.origin);
+        widget
+    }
+
+    /// Returns the rectangle the widget covers when drawn at its origin.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.origin, self.size)
+    }
+
+    /// Resizes the widget, keeping its origin and the cells that are still inside it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{CellAccessor, HasSize};
+    /// use rustty::ui::{Painter, Widget};
+    ///
+    /// let mut w = Widget::new(4, 1);
+    /// w.printline(0, 0, "abcd");
+    /// w.resize(2, 2);
+    /// assert_eq!(w.size(), (2, 2));
+    /// assert_eq!(w.get(1, 0).unwrap().ch(), 'b');
+    /// assert_eq!(w.get(0, 1).unwrap().ch(), ' ');
+    /// ```
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let (old_cols, old_rows) = self.size;
+        let mut buf = vec![Cell::default(); cols * rows];
+        for y in 0..cmp::min(rows, old_rows) {
+            for x in 0..cmp::min(cols, old_cols) {
+                buf[y * cols + x] = self.buf[y * old_cols + x];
+            }
+        }
+        self.buf = buf;
+        self.size = (cols, rows);
+    }
+
+    /// Flashes the outermost ring of cells of the widget in reverse video for the given
+    /// duration.
+    ///
+    /// The flash only affects how the widget is drawn by `draw_into()`; its cells are unchanged.
+    pub fn flash(&mut self, duration: Duration) {
+        self.flash_until = Some(Instant::now() + duration);
+    }
+
+    /// Returns `true` if the widget is currently flashing.
+    pub fn is_flashing(&self) -> bool {
+        self.flash_until.is_some_and(|t| Instant::now() < t)
+    }
+
+    /// Restricts drawing to a rectangle of the target cells.
+    ///
+    /// With no clip rectangle, which is the default, the widget is clipped only to the bounds of
+    /// whatever it is drawn into. Containers that scroll their children set a clip rectangle so
+    /// that children never paint over the container's siblings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::{CellAccessor, HasPosition, Rect};
+    /// use rustty::ui::{Painter, Widget};
+    ///
+    /// let mut label = Widget::new(6, 1);
+    /// label.printline(0, 0, "abcdef");
+    /// label.set_origin((2, 0));
+    /// label.set_clip(Some(Rect::new((0, 0), (5, 1))));
+    ///
+    /// let mut screen = Widget::new(10, 1);
+    /// label.draw_into(&mut screen);
+    /// assert_eq!(screen.get(4, 0).unwrap().ch(), 'c');
+    /// assert_eq!(screen.get(5, 0).unwrap().ch(), ' ');
+    /// ```
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+    }
+
+    /// Returns the clip rectangle.
+    pub fn clip(&self) -> Option<Rect> {
+        self.clip
+    }
+
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
         let (x, y) = self.origin();
-        for ix in 0..cols {
-            let offset_x = x + ix;
-            for iy in 0..rows {
-                let offset_y = y + iy;
-                match cells.get_mut(offset_x, offset_y) {
-                    Some(cell) => {
-                        *cell = *self.get(ix, iy).unwrap();
+        self.paint(cells, x as isize, y as isize);
+    }
+
+    // Copies the widget's cells into `cells` with the top-left corner at `(x, y)`, honoring the
+    // clip rectangle and flashing.
+    fn paint(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        trace_span!("draw");
+        let (cols, rows) = self.size();
+        let flashing = self.is_flashing();
+        for iy in 0..rows {
+            for ix in 0..cols {
+                let (px, py) = (x + ix as isize, y + iy as isize);
+                if px < 0 || py < 0 {
+                    continue;
+                }
+                let pos = (px as usize, py as usize);
+                if !self.clip.is_none_or(|clip| clip.contains(pos)) {
+                    continue;
+                }
+                if let Some(cell) = cells.get_mut(pos.0, pos.1) {
+                    *cell = *self.get(ix, iy).unwrap();
+                    if flashing && (ix == 0 || iy == 0 || ix + 1 == cols || iy + 1 == rows) {
+                        let attrs = reversed(cell.attrs());
+                        cell.set_attrs(attrs);
                     }
-                    None => (),
                 }
             }
         }
     }
 }
 
+impl Draw for Widget {
+    fn draw(&self, cells: &mut dyn CellAccessor, x: isize, y: isize) {
+        self.paint(cells, x, y);
+    }
+}
+
+// Toggles reverse video in `attr`.
+fn reversed(attr: Attr) -> Attr {
+    if attr.contains(Attr::Reverse) {
+        attr.difference(Attr::Reverse)
+    } else {
+        attr | Attr::Reverse
+    }
+}
+
 impl HasSize for Widget {
     fn size(&self) -> Size {
         self.size
@@ -62,3 +215,30 @@ impl HasPosition for Widget {
 }
 
 impl Alignable for Widget {}
+
+impl Measure for Widget {
+    /// Returns the smallest size that holds every non-blank cell of the widget, measured from its
+    /// top-left corner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::ui::{Measure, Painter, Widget};
+    ///
+    /// let mut w = Widget::new(20, 5);
+    /// assert_eq!(w.preferred_size(), (0, 0));
+    /// w.printline(1, 2, "hello");
+    /// assert_eq!(w.preferred_size(), (6, 3));
+    /// ```
+    fn preferred_size(&self) -> Size {
+        let (cols, _) = self.size;
+        let blank = Cell::default();
+        self.buf
+            .iter()
+            .enumerate()
+            .filter(|&(_, cell)| *cell != blank)
+            .fold((0, 0), |(w, h), (i, _)| {
+                (cmp::max(w, i % cols + 1), cmp::max(h, i / cols + 1))
+            })
+    }
+}
@@ -0,0 +1,934 @@
+//! A window manager for several logical windows on one `Terminal`.
+//!
+//! A `WindowManager` owns a set of windows, each backed by a `Widget` the application draws
+//! into, and composes them onto the screen. Windows can be moved, resized and stacked over each
+//! other in floating mode, or laid out side by side in tiling mode. One window has the focus and
+//! receives keyboard input: the application routes key events to `focused_mut()` and binds keys
+//! to `focus_next()` and `focus_prev()` to move the focus, much like a terminal multiplexer.
+//!
+//! Decorated windows have a frame with a title bar and glyphs to minimize and close them. Mouse
+//! events passed to `handle_mouse()` focus windows, drag them by their title bar and resize them
+//! by their bottom-right corner, snapping them to the edges of the screen.
+
+use std::cmp;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+
+use core::cellbuffer::{Cell, CellAccessor};
+use core::input::{MouseAction, MouseButton, MouseEvent};
+use core::position::{Pos, Size, Rect, HasSize, HasPosition};
+use ui::{BorderStyle, HorizontalAlign, Relayout, Widget, draw_border, draw_title};
+
+/// Identifies a window of a `WindowManager`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WindowId(usize);
+
+/// How a `WindowManager` places its windows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LayoutMode {
+    /// Windows keep the position and size they are given, and overlap in stacking order.
+    Floating,
+    /// Windows share the screen in a grid of columns, in the order they were opened. Their
+    /// floating positions and sizes are restored when switching back.
+    Tiling,
+}
+
+/// What a `WindowManager` did with a mouse event, see `WindowManager::handle_mouse()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WindowEvent {
+    /// The window was clicked and now has the focus.
+    Focus(WindowId),
+    /// The close glyph of the window was clicked. The window stays open, so that the application
+    /// can ask for confirmation before calling `close()`.
+    Close(WindowId),
+    /// The minimize glyph of the window was clicked and the window was minimized.
+    Minimize(WindowId),
+    /// The window was dragged by its title bar.
+    Move(WindowId),
+    /// The window was resized by dragging its bottom-right corner.
+    Resize(WindowId),
+}
+
+struct Window {
+    id: WindowId,
+    floating: Rect,
+    // The rectangle the window covers, frame included, in the current layout mode.
+    rect: Rect,
+    title: Option<String>,
+    state: Option<String>,
+    decorated: bool,
+    minimized: bool,
+    widget: Widget,
+}
+
+impl Window {
+    // Returns the rectangle covered by the window's contents.
+    fn content(&self) -> Rect {
+        if self.decorated {
+            self.rect.inset(1, 1)
+        } else {
+            self.rect
+        }
+    }
+}
+
+// The part of a window under the mouse.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Part {
+    Close,
+    Minimize,
+    TitleBar,
+    Corner,
+    Content,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum DragKind {
+    Move,
+    Resize,
+}
+
+#[derive(Copy, Clone)]
+struct Drag {
+    id: WindowId,
+    kind: DragKind,
+    from: Pos,
+    start: Rect,
+}
+
+// The glyphs at the right of a title bar, and the narrowest frame that has them.
+const MINIMIZE_GLYPH: char = '_';
+const CLOSE_GLYPH: char = 'x';
+const GLYPHS_MIN_COLS: usize = 5;
+
+/// Movable, resizable and stackable windows sharing a screen.
+///
+/// # Examples
+///
+/// ```
+/// use rustty::{CellAccessor, HasSize, Rect};
+/// use rustty::ui::{Painter, Widget};
+/// use rustty::wm::{LayoutMode, WindowManager};
+///
+/// let mut wm = WindowManager::new(20, 4);
+/// let log = wm.open(Rect::new((0, 0), (10, 4)));
+/// let shell = wm.open(Rect::new((5, 0), (10, 4)));
+/// assert_eq!(wm.focused(), Some(shell));
+///
+/// wm.window_mut(log).unwrap().printline(5, 0, "log");
+/// wm.window_mut(shell).unwrap().printline(0, 0, "$");
+///
+/// let mut screen = Widget::new(20, 4);
+/// wm.draw_into(&mut screen);
+/// assert_eq!(screen.get(5, 0).unwrap().ch(), '$');
+///
+/// // Focusing a floating window raises it.
+/// wm.focus_next();
+/// assert_eq!(wm.focused(), Some(log));
+/// wm.draw_into(&mut screen);
+/// assert_eq!(screen.get(5, 0).unwrap().ch(), 'l');
+///
+/// // Tiling gives each window half of the screen.
+/// wm.set_mode(LayoutMode::Tiling);
+/// assert_eq!(wm.rect(shell), Some(Rect::new((10, 0), (10, 4))));
+/// ```
+///
+/// Decorated windows are dragged with the mouse:
+///
+/// ```
+/// use rustty::{MouseAction, MouseButton, MouseEvent, Rect};
+/// use rustty::wm::{WindowEvent, WindowManager};
+///
+/// fn left(action: MouseAction, x: usize, y: usize) -> MouseEvent {
+///     MouseEvent { button: MouseButton::Left, action, x, y }
+/// }
+///
+/// let mut wm = WindowManager::new(40, 20);
+/// let win = wm.open(Rect::new((10, 5), (12, 6)));
+/// wm.set_decorated(win, true);
+/// wm.set_title(win, "notes");
+/// assert_eq!(wm.content_rect(win), Some(Rect::new((11, 6), (10, 4))));
+///
+/// // Drag the title bar 8 columns to the left, close enough to the edge to snap to it.
+/// assert_eq!(wm.handle_mouse(left(MouseAction::Press, 12, 5)), Some(WindowEvent::Focus(win)));
+/// assert_eq!(wm.handle_mouse(left(MouseAction::Drag, 4, 5)), Some(WindowEvent::Move(win)));
+/// wm.handle_mouse(left(MouseAction::Release, 4, 5));
+/// assert_eq!(wm.rect(win), Some(Rect::new((0, 5), (12, 6))));
+///
+/// // The close glyph sits left of the top-right corner.
+/// assert_eq!(wm.handle_mouse(left(MouseAction::Press, 10, 5)), Some(WindowEvent::Close(win)));
+/// ```
+pub struct WindowManager {
+    size: Size,
+    mode: LayoutMode,
+    // In the order the windows were opened.
+    windows: Vec<Window>,
+    // From the bottom up.
+    stack: Vec<WindowId>,
+    focus: Option<WindowId>,
+    next_id: usize,
+    style: BorderStyle,
+    focus_style: BorderStyle,
+    snap: usize,
+    drag: Option<Drag>,
+}
+
+impl WindowManager {
+    /// Constructs a new `WindowManager` for a screen of the given size, with no windows, in
+    /// floating mode.
+    pub fn new(cols: usize, rows: usize) -> WindowManager {
+        WindowManager {
+            size: (cols, rows),
+            mode: LayoutMode::Floating,
+            windows: Vec::new(),
+            stack: Vec::new(),
+            focus: None,
+            next_id: 0,
+            style: BorderStyle::Single,
+            focus_style: BorderStyle::Double,
+            snap: 2,
+            drag: None,
+        }
+    }
+
+    /// Sets the size of the screen the windows share, laying them out again.
+    pub fn set_screen_size(&mut self, cols: usize, rows: usize) {
+        self.size = (cols, rows);
+        self.arrange();
+    }
+
+    /// Returns the layout mode.
+    pub fn mode(&self) -> LayoutMode {
+        self.mode
+    }
+
+    /// Sets the layout mode, laying the windows out again.
+    pub fn set_mode(&mut self, mode: LayoutMode) {
+        self.mode = mode;
+        self.arrange();
+    }
+
+    /// Sets the border styles of the frames of decorated windows, the window with the focus
+    /// being drawn in `focused`. The defaults are single and double lines.
+    pub fn set_border_styles(&mut self, normal: BorderStyle, focused: BorderStyle) {
+        self.style = normal;
+        self.focus_style = focused;
+    }
+
+    /// Sets how close to the edges of the screen, in cells, dragged windows snap to them. The
+    /// default is 2, and 0 snaps only windows dragged past the edges.
+    pub fn set_snap_distance(&mut self, cells: usize) {
+        self.snap = cells;
+    }
+
+    /// Opens a new, blank and undecorated window covering `rect` in floating mode, on top of the
+    /// others, and gives it the focus.
+    pub fn open<R: Into<Rect>>(&mut self, rect: R) -> WindowId {
+        self.next_id += 1;
+        let id = WindowId(self.next_id);
+        let floating = rect.into();
+        self.windows.push(Window {
+            id,
+            floating,
+            rect: floating,
+            title: None,
+            state: None,
+            decorated: false,
+            minimized: false,
+            widget: Widget::covering(floating),
+        });
+        self.stack.push(id);
+        self.focus = Some(id);
+        self.arrange();
+        id
+    }
+
+    /// Closes a window and returns its widget. If it had the focus, the focus passes to the
+    /// topmost remaining window.
+    pub fn close(&mut self, id: WindowId) -> Option<Widget> {
+        let i = self.index(id)?;
+        let window = self.windows.remove(i);
+        self.stack.retain(|&w| w != id);
+        if self.focus == Some(id) {
+            self.focus_top();
+        }
+        self.arrange();
+        Some(window.widget)
+    }
+
+    /// Gives a window a frame, with its title and glyphs to minimize and close it, or removes
+    /// it. The frame takes up the outermost cells of the window. Returns `false` if there is no
+    /// such window.
+    pub fn set_decorated(&mut self, id: WindowId, decorated: bool) -> bool {
+        self.update(id, |w| w.decorated = decorated)
+    }
+
+    /// Sets the title shown in the frame of a decorated window. Returns `false` if there is no
+    /// such window.
+    pub fn set_title(&mut self, id: WindowId, title: &str) -> bool {
+        self.update(id, |w| w.title = Some(title.to_owned()))
+    }
+
+    /// Sets the application state saved with a window by `save_workspace()`, such as the
+    /// scroll position of its contents serialized in any format. Returns `false` if there is no
+    /// such window.
+    pub fn set_window_state(&mut self, id: WindowId, state: &str) -> bool {
+        self.update(id, |w| w.state = Some(state.to_owned()))
+    }
+
+    /// Returns the application state of a window, as set with `set_window_state()` or loaded
+    /// by `load_workspace()`.
+    pub fn window_state(&self, id: WindowId) -> Option<&str> {
+        self.index(id).and_then(|i| self.windows[i].state.as_deref())
+    }
+
+    /// Hides a window until it is restored. If it had the focus, the focus passes to the
+    /// topmost remaining window. Returns `false` if there is no such window.
+    pub fn minimize(&mut self, id: WindowId) -> bool {
+        if !self.update(id, |w| w.minimized = true) {
+            return false;
+        }
+        if self.focus == Some(id) {
+            self.focus_top();
+        }
+        true
+    }
+
+    /// Shows a minimized window again and gives it the focus. Returns `false` if there is no
+    /// such window.
+    pub fn restore(&mut self, id: WindowId) -> bool {
+        self.update(id, |w| w.minimized = false) && self.focus(id)
+    }
+
+    /// Returns `true` if a window is minimized.
+    pub fn is_minimized(&self, id: WindowId) -> bool {
+        self.index(id).is_some_and(|i| self.windows[i].minimized)
+    }
+
+    /// Returns the ids of the windows in the order they were opened.
+    pub fn windows(&self) -> Vec<WindowId> {
+        self.windows.iter().map(|w| w.id).collect()
+    }
+
+    /// Returns a reference to a window's widget.
+    pub fn window(&self, id: WindowId) -> Option<&Widget> {
+        self.index(id).map(|i| &self.windows[i].widget)
+    }
+
+    /// Returns a mutable reference to a window's widget, to draw into it.
+    ///
+    /// The window manager owns the widget's position and size, which are overwritten the next
+    /// time the windows are laid out.
+    pub fn window_mut(&mut self, id: WindowId) -> Option<&mut Widget> {
+        let i = self.index(id)?;
+        Some(&mut self.windows[i].widget)
+    }
+
+    /// Returns the rectangle a window currently covers on the screen, frame included.
+    pub fn rect(&self, id: WindowId) -> Option<Rect> {
+        self.index(id).map(|i| self.windows[i].rect)
+    }
+
+    /// Returns the rectangle covered by a window's contents, which is where its widget is
+    /// drawn.
+    pub fn content_rect(&self, id: WindowId) -> Option<Rect> {
+        self.index(id).map(|i| self.windows[i].content())
+    }
+
+    /// Moves a window's top-left corner to `pos` in floating mode. Returns `false` if there is
+    /// no such window.
+    pub fn move_window(&mut self, id: WindowId, pos: Pos) -> bool {
+        self.update(id, |w| w.floating.origin = pos)
+    }
+
+    /// Resizes a window in floating mode, frame included, keeping its top-left corner and the
+    /// cells that are still inside it. Returns `false` if there is no such window.
+    pub fn resize_window(&mut self, id: WindowId, size: Size) -> bool {
+        self.update(id, |w| w.floating.size = size)
+    }
+
+    /// Raises a window above all others. Returns `false` if there is no such window.
+    pub fn raise(&mut self, id: WindowId) -> bool {
+        if self.index(id).is_none() {
+            return false;
+        }
+        self.stack.retain(|&w| w != id);
+        self.stack.push(id);
+        true
+    }
+
+    /// Lowers a window below all others. Returns `false` if there is no such window.
+    pub fn lower(&mut self, id: WindowId) -> bool {
+        if self.index(id).is_none() {
+            return false;
+        }
+        self.stack.retain(|&w| w != id);
+        self.stack.insert(0, id);
+        true
+    }
+
+    /// Gives a window the focus, raising it. Returns `false` if there is no such window or it
+    /// is minimized.
+    pub fn focus(&mut self, id: WindowId) -> bool {
+        if self.is_minimized(id) || !self.raise(id) {
+            return false;
+        }
+        self.focus = Some(id);
+        true
+    }
+
+    /// Returns the window with the focus, which is `None` only when every window is closed or
+    /// minimized.
+    pub fn focused(&self) -> Option<WindowId> {
+        self.focus
+    }
+
+    /// Returns a mutable reference to the widget of the window with the focus.
+    pub fn focused_mut(&mut self) -> Option<&mut Widget> {
+        let id = self.focus?;
+        self.window_mut(id)
+    }
+
+    /// Passes the focus to the window opened after the focused one, wrapping around and
+    /// skipping minimized windows.
+    pub fn focus_next(&mut self) {
+        self.cycle_focus(true);
+    }
+
+    /// Passes the focus to the window opened before the focused one, wrapping around and
+    /// skipping minimized windows.
+    pub fn focus_prev(&mut self) {
+        self.cycle_focus(false);
+    }
+
+    /// Returns the topmost window covering the cell at `pos`, such as the target of a mouse
+    /// click. Minimized windows cover nothing.
+    pub fn window_at(&self, pos: Pos) -> Option<WindowId> {
+        self.stack.iter().rev().cloned().find(|&id| {
+            self.index(id).is_some_and(|i| {
+                let window = &self.windows[i];
+                !window.minimized && window.rect.contains(pos)
+            })
+        })
+    }
+
+    /// Handles a mouse event, returning what it did to the windows, if anything.
+    ///
+    /// Pressing the left button over a window focuses it. Over the frame of a decorated window,
+    /// it also minimizes the window from the `_` glyph or asks to close it from the `x` glyph,
+    /// and in floating mode starts dragging the window by its title bar or resizing it by its
+    /// bottom-right corner until the button is released.
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> Option<WindowEvent> {
+        let pos = (event.x, event.y);
+        match (event.button, event.action) {
+            (MouseButton::Left, MouseAction::Press) => self.press(pos),
+            (_, MouseAction::Drag) => self.drag_to(pos),
+            (_, MouseAction::Release) => {
+                self.drag = None;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Draws the windows that are not minimized into `cells` from the bottom of the stack up.
+    pub fn draw_into(&self, cells: &mut dyn CellAccessor) {
+        for &id in &self.stack {
+            let window = match self.index(id) {
+                Some(i) => &self.windows[i],
+                None => continue,
+            };
+            if window.minimized {
+                continue;
+            }
+            if window.decorated {
+                self.draw_frame(window, cells);
+            }
+            window.widget.draw_into(cells);
+        }
+    }
+
+    /// Writes the floating position and size of every window to the file at `path`, one line
+    /// per window in the order they were opened, replacing any existing contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Rect;
+    /// use rustty::wm::WindowManager;
+    ///
+    /// let path = std::env::temp_dir().join("rustty_wm_doctest");
+    ///
+    /// let mut wm = WindowManager::new(80, 24);
+    /// let log = wm.open(Rect::new((0, 0), (40, 24)));
+    /// wm.move_window(log, (40, 0));
+    /// wm.save_geometry(&path).unwrap();
+    ///
+    /// // On the next run.
+    /// let mut wm = WindowManager::new(80, 24);
+    /// let log = wm.open(Rect::new((0, 0), (40, 24)));
+    /// wm.load_geometry(&path).unwrap();
+    /// assert_eq!(wm.rect(log), Some(Rect::new((40, 0), (40, 24))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_geometry<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut out = BufWriter::new(try!(File::create(path)));
+        for window in &self.windows {
+            try!(writeln!(out, "{}", format_geometry(window)));
+        }
+        out.flush()
+    }
+
+    /// Restores the floating positions and sizes saved with `save_geometry()`, applying the
+    /// lines of the file at `path` to the windows in the order they were opened.
+    ///
+    /// A missing file is not an error, so that geometry can be loaded unconditionally on startup
+    /// once the windows are open. Fails with `ErrorKind::InvalidData` if a line is malformed.
+    pub fn load_geometry<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        // Parse the whole file first, so that a malformed one changes nothing.
+        let mut saved = Vec::new();
+        for line in BufReader::new(file).lines().take(self.windows.len()) {
+            let line = try!(line);
+            saved.push(try!(parse_geometry(&line).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, format!("invalid window geometry: {}", line))
+            })));
+        }
+        for (window, (rect, minimized)) in self.windows.iter_mut().zip(saved) {
+            window.floating = rect;
+            window.minimized = minimized;
+        }
+        self.arrange();
+        if self.focus.is_none_or(|id| self.is_minimized(id)) {
+            self.focus_top();
+        }
+        Ok(())
+    }
+
+    /// Writes the whole workspace to the file at `path`, replacing any existing contents: the
+    /// layout mode, the floating position and size of every window, whether it is minimized,
+    /// its application state, the stacking order and the focus.
+    ///
+    /// Windows are identified by the order they were opened in, so an application restores its
+    /// workspace by opening the same windows on startup and calling `load_workspace()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustty::Rect;
+    /// use rustty::wm::{LayoutMode, WindowManager};
+    ///
+    /// let path = std::env::temp_dir().join("rustty_workspace_doctest");
+    ///
+    /// let mut wm = WindowManager::new(80, 24);
+    /// let cpu = wm.open(Rect::new((0, 0), (40, 12)));
+    /// let net = wm.open(Rect::new((40, 0), (40, 12)));
+    /// wm.set_window_state(net, "interface=eth0\nunits=bits");
+    /// wm.focus(cpu);
+    /// wm.set_mode(LayoutMode::Tiling);
+    /// wm.save_workspace(&path).unwrap();
+    ///
+    /// // On the next run.
+    /// let mut wm = WindowManager::new(80, 24);
+    /// let cpu = wm.open(Rect::new((0, 0), (10, 10)));
+    /// let net = wm.open(Rect::new((0, 0), (10, 10)));
+    /// wm.load_workspace(&path).unwrap();
+    /// assert_eq!(wm.mode(), LayoutMode::Tiling);
+    /// assert_eq!(wm.focused(), Some(cpu));
+    /// assert_eq!(wm.window_state(net), Some("interface=eth0\nunits=bits"));
+    ///
+    /// wm.set_mode(LayoutMode::Floating);
+    /// assert_eq!(wm.rect(net), Some(Rect::new((40, 0), (40, 12))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_workspace<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut out = BufWriter::new(try!(File::create(path)));
+        let mode = match self.mode {
+            LayoutMode::Floating => "floating",
+            LayoutMode::Tiling => "tiling",
+        };
+        try!(writeln!(out, "mode {}", mode));
+        for (i, window) in self.windows.iter().enumerate() {
+            try!(writeln!(out, "window {}", format_geometry(window)));
+            if let Some(ref state) = window.state {
+                try!(writeln!(out, "state {} {}", i, escape(state)));
+            }
+        }
+        let stack: Vec<String> = self.stack
+            .iter()
+            .filter_map(|&id| self.index(id))
+            .map(|i| i.to_string())
+            .collect();
+        try!(writeln!(out, "stack {}", stack.join(" ")));
+        if let Some(i) = self.focus.and_then(|id| self.index(id)) {
+            try!(writeln!(out, "focus {}", i));
+        }
+        out.flush()
+    }
+
+    /// Restores a workspace saved with `save_workspace()` from the file at `path`, applying the
+    /// saved windows to the open windows in the order they were opened.
+    ///
+    /// Saved windows beyond the open ones are ignored, and open windows beyond the saved ones
+    /// keep their geometry and stay on top. A missing file is not an error. Fails with
+    /// `ErrorKind::InvalidData`, changing nothing, if a line is malformed or the stacking order
+    /// lists a window twice.
+    pub fn load_workspace<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        // Parse the whole file first, so that a malformed one changes nothing.
+        let workspace = try!(parse_workspace(BufReader::new(file)));
+        if let Some(mode) = workspace.mode {
+            self.mode = mode;
+        }
+        for (window, &(rect, minimized)) in self.windows.iter_mut().zip(&workspace.windows) {
+            window.floating = rect;
+            window.minimized = minimized;
+        }
+        for (index, state) in workspace.states {
+            if let Some(window) = self.windows.get_mut(index) {
+                window.state = Some(state);
+            }
+        }
+        if let Some(indices) = workspace.stack {
+            let ids: Vec<WindowId> =
+                indices.iter().filter_map(|&i| self.windows.get(i)).map(|w| w.id).collect();
+            // Windows the workspace doesn't know about stay on top.
+            self.stack.retain(|id| !ids.contains(id));
+            self.stack.splice(0..0, ids);
+        }
+        self.arrange();
+        // The focus is restored without raising the window, which keeps the saved stacking.
+        match workspace.focus.and_then(|i| self.windows.get(i)) {
+            Some(window) if !window.minimized => self.focus = Some(window.id),
+            _ => self.focus_top(),
+        }
+        Ok(())
+    }
+
+    fn index(&self, id: WindowId) -> Option<usize> {
+        self.windows.iter().position(|w| w.id == id)
+    }
+
+    // Applies `f` to a window and lays the windows out again.
+    fn update<F: FnOnce(&mut Window)>(&mut self, id: WindowId, f: F) -> bool {
+        match self.index(id) {
+            Some(i) => {
+                f(&mut self.windows[i]);
+                self.arrange();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Gives the focus to the topmost window that is not minimized.
+    fn focus_top(&mut self) {
+        let windows = &self.windows;
+        self.focus = self.stack
+            .iter()
+            .rev()
+            .cloned()
+            .find(|&id| windows.iter().any(|w| w.id == id && !w.minimized));
+    }
+
+    fn cycle_focus(&mut self, forward: bool) {
+        let n = self.windows.len();
+        let start = match self.focus.and_then(|id| self.index(id)) {
+            Some(i) => i,
+            None => return,
+        };
+        for step in 1..n {
+            let i = if forward { (start + step) % n } else { (start + n - step) % n };
+            if !self.windows[i].minimized {
+                let id = self.windows[i].id;
+                self.focus(id);
+                return;
+            }
+        }
+    }
+
+    fn press(&mut self, pos: Pos) -> Option<WindowEvent> {
+        let id = self.window_at(pos)?;
+        let window = &self.windows[self.index(id)?];
+        let part = if window.decorated { frame_part(window.rect, pos) } else { Part::Content };
+        let start = window.floating;
+        self.focus(id);
+        let kind = match part {
+            Part::Close => return Some(WindowEvent::Close(id)),
+            Part::Minimize => {
+                self.minimize(id);
+                return Some(WindowEvent::Minimize(id));
+            }
+            Part::TitleBar => DragKind::Move,
+            Part::Corner => DragKind::Resize,
+            Part::Content => return Some(WindowEvent::Focus(id)),
+        };
+        if self.mode == LayoutMode::Floating {
+            self.drag = Some(Drag {
+                id,
+                kind,
+                from: pos,
+                start,
+            });
+        }
+        Some(WindowEvent::Focus(id))
+    }
+
+    fn drag_to(&mut self, pos: Pos) -> Option<WindowEvent> {
+        let drag = self.drag?;
+        let decorated = self.windows[self.index(drag.id)?].decorated;
+        let (dx, dy) = (pos.0 as isize - drag.from.0 as isize,
+                        pos.1 as isize - drag.from.1 as isize);
+        let (cols, rows) = (self.size.0 as isize, self.size.1 as isize);
+        let snap = self.snap as isize;
+        let Rect { origin: (x, y), size: (w, h) } = drag.start;
+        let mut rect = drag.start;
+        match drag.kind {
+            DragKind::Move => {
+                rect.origin = (snap_to_edges(x as isize + dx, w, cols, snap),
+                               snap_to_edges(y as isize + dy, h, rows, snap));
+            }
+            DragKind::Resize => {
+                // Decorated windows keep room for their frame and a cell of contents.
+                let min = if decorated { 3 } else { 1 };
+                rect.size = (snap_size(x, (x + w) as isize + dx, cols, snap, min),
+                             snap_size(y, (y + h) as isize + dy, rows, snap, min));
+            }
+        }
+        self.update(drag.id, |w| w.floating = rect);
+        Some(match drag.kind {
+            DragKind::Move => WindowEvent::Move(drag.id),
+            DragKind::Resize => WindowEvent::Resize(drag.id),
+        })
+    }
+
+    fn draw_frame(&self, window: &Window, cells: &mut dyn CellAccessor) {
+        let rect = window.rect;
+        let (x, y) = (rect.origin.0 as isize, rect.origin.1 as isize);
+        let style = if self.focus == Some(window.id) { self.focus_style } else { self.style };
+        // The frame covers whatever lies below the window.
+        for py in rect.origin.1..rect.bottom() {
+            for px in rect.origin.0..rect.right() {
+                if let Some(cell) = cells.get_mut(px, py) {
+                    *cell = Cell::default();
+                }
+            }
+        }
+        draw_border(cells, x, y, rect.size, style, None);
+        if rect.size.0 < GLYPHS_MIN_COLS {
+            return;
+        }
+        if let Some(ref title) = window.title {
+            // Leave room for the glyphs.
+            draw_title(cells, x, y, rect.size.0 - 2, style, title, HorizontalAlign::Left);
+        }
+        let right = rect.right() - 1;
+        let glyphs = [(right - 2, MINIMIZE_GLYPH), (right - 1, CLOSE_GLYPH)];
+        for &(px, glyph) in &glyphs {
+            if let Some(cell) = cells.get_mut(px, rect.origin.1) {
+                cell.set_ch(glyph);
+            }
+        }
+    }
+
+    // Positions and sizes the windows for the layout mode.
+    fn arrange(&mut self) {
+        let visible = self.windows.iter().filter(|w| !w.minimized).count();
+        let mut tiles = tiles(Rect::new((0, 0), self.size), visible).into_iter();
+        for window in &mut self.windows {
+            window.rect = match self.mode {
+                LayoutMode::Tiling if !window.minimized => tiles.next().unwrap_or_default(),
+                _ => window.floating,
+            };
+            let content = window.content();
+            window.widget.set_origin(content.origin);
+            if window.widget.size() != content.size {
+                window.widget.resize(content.size.0, content.size.1);
+            }
+        }
+    }
+}
+
+impl Relayout for WindowManager {
+    /// Sets the size of the screen to the size of `parent`, returning `true` if any window was
+    /// resized.
+    fn relayout(&mut self, parent: &dyn HasSize) -> bool {
+        let sizes: Vec<Size> = self.windows.iter().map(|w| w.widget.size()).collect();
+        let (cols, rows) = parent.size();
+        self.set_screen_size(cols, rows);
+        self.windows.iter().zip(sizes).any(|(w, size)| w.widget.size() != size)
+    }
+}
+
+// Returns the part of the decorated window covering `rect` at `pos`.
+fn frame_part(rect: Rect, pos: Pos) -> Part {
+    let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+    let glyphs = rect.size.0 >= GLYPHS_MIN_COLS;
+    if pos.1 == rect.origin.1 {
+        match right - pos.0 {
+            1 if glyphs => Part::Close,
+            2 if glyphs => Part::Minimize,
+            _ => Part::TitleBar,
+        }
+    } else if pos == (right, bottom) {
+        Part::Corner
+    } else {
+        Part::Content
+    }
+}
+
+// Returns where a window `len` cells long dragged to `pos` along a screen `total` cells long
+// goes, snapping it to the edge of the screen it is within `snap` cells of and keeping it on the
+// screen if it fits.
+fn snap_to_edges(pos: isize, len: usize, total: isize, snap: isize) -> usize {
+    let far = total - len as isize;
+    let pos = if pos <= snap {
+        0
+    } else if pos >= far - snap {
+        far
+    } else {
+        pos
+    };
+    cmp::max(pos, 0) as usize
+}
+
+// Returns the length of a window starting at `start` whose far edge is dragged to `end`,
+// snapping the edge to the end of a screen `total` cells long within `snap` cells of it.
+fn snap_size(start: usize, end: isize, total: isize, snap: isize, min: usize) -> usize {
+    let end = if end >= total - snap { total } else { end };
+    cmp::max(end - start as isize, min as isize) as usize
+}
+
+// A workspace read by `load_workspace()`, with windows given by the order they were opened in.
+struct Workspace {
+    mode: Option<LayoutMode>,
+    windows: Vec<(Rect, bool)>, // The floating rectangles and whether they are minimized.
+    states: Vec<(usize, String)>,
+    stack: Option<Vec<usize>>,
+    focus: Option<usize>,
+}
+
+// Parses a workspace written by `save_workspace()`.
+fn parse_workspace<R: BufRead>(reader: R) -> Result<Workspace, Error> {
+    let mut workspace = Workspace {
+        mode: None,
+        windows: Vec::new(),
+        states: Vec::new(),
+        stack: None,
+        focus: None,
+    };
+    for line in reader.lines() {
+        let line = try!(line);
+        let invalid = || Error::new(ErrorKind::InvalidData, format!("invalid workspace: {}", line));
+        let (key, value) = match line.find(' ') {
+            Some(i) => (&line[..i], &line[i + 1..]),
+            None => (&line[..], ""),
+        };
+        match key {
+            "mode" => {
+                workspace.mode = Some(match value {
+                    "floating" => LayoutMode::Floating,
+                    "tiling" => LayoutMode::Tiling,
+                    _ => return Err(invalid()),
+                })
+            }
+            "window" => workspace.windows.push(try!(parse_geometry(value).ok_or_else(invalid))),
+            "state" => {
+                let (index, state) = value.split_at(try!(value.find(' ').ok_or_else(invalid)));
+                let index = try!(index.parse::<usize>().map_err(|_| invalid()));
+                workspace.states.push((index, unescape(&state[1..])));
+            }
+            "stack" => {
+                let indices: Vec<usize> = try!(value.split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| invalid()));
+                if indices.iter().enumerate().any(|(i, index)| indices[..i].contains(index)) {
+                    return Err(invalid());
+                }
+                workspace.stack = Some(indices);
+            }
+            "focus" => {
+                workspace.focus = Some(try!(value.parse::<usize>().map_err(|_| invalid())))
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(workspace)
+}
+
+// Returns the floating geometry of `window` as saved by `save_geometry()` and `save_workspace()`.
+fn format_geometry(window: &Window) -> String {
+    let Rect { origin: (x, y), size: (cols, rows) } = window.floating;
+    let minimized = if window.minimized { " minimized" } else { "" };
+    format!("{} {} {} {}{}", x, y, cols, rows, minimized)
+}
+
+// Parses the geometry written by `format_geometry()`.
+fn parse_geometry(line: &str) -> Option<(Rect, bool)> {
+    let mut words = line.split_whitespace();
+    let mut number = || words.next()?.parse::<usize>().ok();
+    let rect = Rect::new((number()?, number()?), (number()?, number()?));
+    let minimized = match words.next() {
+        None => false,
+        Some("minimized") => true,
+        Some(_) => return None,
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    Some((rect, minimized))
+}
+
+// Escapes backslashes and line breaks in a window's state, so that it fits on one line.
+fn escape(state: &str) -> String {
+    state.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(escaped: &str) -> String {
+    let mut state = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            state.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => state.push('\n'),
+            Some('r') => state.push('\r'),
+            Some(other) => state.push(other),
+            None => state.push('\\'),
+        }
+    }
+    state
+}
+
+// Splits `area` into `n` tiles, in as many columns as rows or one more, filled column by column.
+fn tiles(area: Rect, n: usize) -> Vec<Rect> {
+    let mut tiles = Vec::with_capacity(n);
+    let cols = (1..).find(|c| c * c >= n).unwrap_or(1);
+    let mut rest = area;
+    let mut left = n;
+    for col in 0..cols {
+        let cols_left = cols - col;
+        let in_col = left.div_ceil(cols_left);
+        let (mut column, r) = rest.split_cols(rest.size.0 / cols_left);
+        rest = r;
+        for row in 0..in_col {
+            let (tile, r) = column.split_rows(column.size.1 / (in_col - row));
+            tiles.push(tile);
+            column = r;
+        }
+        left -= in_col;
+    }
+    tiles
+}